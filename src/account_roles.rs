@@ -0,0 +1,79 @@
+pub mod system;
+pub mod token;
+
+use crate::utils::TransactionAccounts;
+use thiserror::Error;
+
+/// 按位置获取指令帐户时的错误
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AccountRoleError {
+    /// 指令的帐户数量不足，通常是截断或恶意构造的指令
+    #[error("指令帐户数量不足: 需要第{position}个帐户, 实际只有{len}个")]
+    MissingAccount { position: usize, len: usize },
+    /// 指令引用的帐户索引超出了交易帐户列表
+    #[error("交易帐户列表中不存在索引为{index}的帐户")]
+    UnknownAccount { index: u8 },
+    /// 帐户布局取决于子指令的扩展指令，目前无法解析其帐户角色
+    #[error("不支持解析该指令的帐户角色: {0}")]
+    UnsupportedInstruction(String),
+}
+
+/// 获取指令中第`position`个帐户在交易帐户列表中的索引
+pub fn account_at(accounts: &[u8], position: usize) -> Result<u8, AccountRoleError> {
+    accounts
+        .get(position)
+        .copied()
+        .ok_or(AccountRoleError::MissingAccount {
+            position,
+            len: accounts.len(),
+        })
+}
+
+/// 把指令的帐户索引解析为交易帐户列表中的帐户
+pub struct InstructionAccounts<'a, 'b, AccountType> {
+    accounts: &'b [u8],
+    transaction_accounts: &'b TransactionAccounts<'a, AccountType>,
+}
+
+impl<'a, 'b, AccountType> InstructionAccounts<'a, 'b, AccountType> {
+    pub fn new(
+        accounts: &'b [u8],
+        transaction_accounts: &'b TransactionAccounts<'a, AccountType>,
+    ) -> Self {
+        Self {
+            accounts,
+            transaction_accounts,
+        }
+    }
+
+    /// 获取指令中第`position`个帐户
+    pub fn get(&self, position: usize) -> Result<&'a AccountType, AccountRoleError> {
+        self.resolve(account_at(self.accounts, position)?)
+    }
+
+    /// 获取可选的第`position`个帐户，指令没有这个位置时返回None
+    pub fn get_optional(
+        &self,
+        position: usize,
+    ) -> Result<Option<&'a AccountType>, AccountRoleError> {
+        match self.accounts.get(position) {
+            Some(index) => self.resolve(*index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 获取从第`from`个开始的所有帐户，通常是多签的签名者列表
+    pub fn remaining(&self, from: usize) -> Result<Vec<&'a AccountType>, AccountRoleError> {
+        self.accounts
+            .iter()
+            .skip(from)
+            .map(|index| self.resolve(*index))
+            .collect()
+    }
+
+    fn resolve(&self, index: u8) -> Result<&'a AccountType, AccountRoleError> {
+        self.transaction_accounts
+            .get(index as usize)
+            .ok_or(AccountRoleError::UnknownAccount { index })
+    }
+}
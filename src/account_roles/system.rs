@@ -0,0 +1,119 @@
+use crate::account_roles::{AccountRoleError, InstructionAccounts};
+use crate::utils::TransactionAccounts;
+use solana_sdk::system_instruction::SystemInstruction;
+
+/// System程序指令中各帐户的角色
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemAccountRoles<'a, AccountType> {
+    /// CreateAccount/CreateAccountWithSeed, 只有WithSeed带base
+    CreateAccount {
+        funding: &'a AccountType,
+        new_account: &'a AccountType,
+        base: Option<&'a AccountType>,
+    },
+    /// Assign/AssignWithSeed, 只有WithSeed带base
+    Assign {
+        account: &'a AccountType,
+        base: Option<&'a AccountType>,
+    },
+    /// Transfer/TransferWithSeed, 只有WithSeed带base
+    Transfer {
+        from: &'a AccountType,
+        to: &'a AccountType,
+        base: Option<&'a AccountType>,
+    },
+    /// Allocate/AllocateWithSeed, 只有WithSeed带base
+    Allocate {
+        account: &'a AccountType,
+        base: Option<&'a AccountType>,
+    },
+    AdvanceNonceAccount {
+        nonce: &'a AccountType,
+        authority: &'a AccountType,
+    },
+    WithdrawNonceAccount {
+        nonce: &'a AccountType,
+        recipient: &'a AccountType,
+        authority: &'a AccountType,
+    },
+    /// 新的nonce authority在指令数据中，不在帐户列表里
+    InitializeNonceAccount {
+        nonce: &'a AccountType,
+    },
+    AuthorizeNonceAccount {
+        nonce: &'a AccountType,
+        authority: &'a AccountType,
+    },
+    UpgradeNonceAccount {
+        nonce: &'a AccountType,
+    },
+}
+
+/// 解析System程序指令的帐户角色
+pub fn system_account_roles<'a, AccountType>(
+    instruction: &SystemInstruction,
+    accounts: &[u8],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+) -> Result<SystemAccountRoles<'a, AccountType>, AccountRoleError> {
+    let a = InstructionAccounts::new(accounts, transaction_accounts);
+    let roles = match instruction {
+        SystemInstruction::CreateAccount { .. } => SystemAccountRoles::CreateAccount {
+            funding: a.get(0)?,
+            new_account: a.get(1)?,
+            base: None,
+        },
+        // base与funding相同时不会单独出现在帐户列表中
+        SystemInstruction::CreateAccountWithSeed { .. } => SystemAccountRoles::CreateAccount {
+            funding: a.get(0)?,
+            new_account: a.get(1)?,
+            base: a.get_optional(2)?,
+        },
+        SystemInstruction::Assign { .. } => SystemAccountRoles::Assign {
+            account: a.get(0)?,
+            base: None,
+        },
+        SystemInstruction::AssignWithSeed { .. } => SystemAccountRoles::Assign {
+            account: a.get(0)?,
+            base: Some(a.get(1)?),
+        },
+        SystemInstruction::Transfer { .. } => SystemAccountRoles::Transfer {
+            from: a.get(0)?,
+            to: a.get(1)?,
+            base: None,
+        },
+        SystemInstruction::TransferWithSeed { .. } => SystemAccountRoles::Transfer {
+            from: a.get(0)?,
+            to: a.get(2)?,
+            base: Some(a.get(1)?),
+        },
+        SystemInstruction::Allocate { .. } => SystemAccountRoles::Allocate {
+            account: a.get(0)?,
+            base: None,
+        },
+        SystemInstruction::AllocateWithSeed { .. } => SystemAccountRoles::Allocate {
+            account: a.get(0)?,
+            base: Some(a.get(1)?),
+        },
+        SystemInstruction::AdvanceNonceAccount => SystemAccountRoles::AdvanceNonceAccount {
+            nonce: a.get(0)?,
+            authority: a.get(2)?,
+        },
+        SystemInstruction::WithdrawNonceAccount(_) => SystemAccountRoles::WithdrawNonceAccount {
+            nonce: a.get(0)?,
+            recipient: a.get(1)?,
+            authority: a.get(4)?,
+        },
+        SystemInstruction::InitializeNonceAccount(_) => {
+            SystemAccountRoles::InitializeNonceAccount { nonce: a.get(0)? }
+        }
+        SystemInstruction::AuthorizeNonceAccount(_) => SystemAccountRoles::AuthorizeNonceAccount {
+            nonce: a.get(0)?,
+            authority: a.get(1)?,
+        },
+        SystemInstruction::UpgradeNonceAccount => {
+            SystemAccountRoles::UpgradeNonceAccount { nonce: a.get(0)? }
+        }
+    };
+
+    Ok(roles)
+}
@@ -0,0 +1,347 @@
+use crate::account_roles::{AccountRoleError, InstructionAccounts};
+use crate::instructions::spl_token::TokenInstruction as SplTokenInstruction;
+use crate::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use crate::transaction::account_meta::AccountPermissions;
+use crate::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+
+/// 新token帐户的owner
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenAccountOwner<'a, AccountType> {
+    /// InitializeAccount的owner在指令帐户列表中
+    Account(&'a AccountType),
+    /// InitializeAccount2/3的owner在指令数据中，不一定出现在交易帐户列表里
+    Pubkey(Pubkey),
+}
+
+/// SPL Token/Token-2022指令中各帐户的角色
+///
+/// `signers`为多签时附带的签名者列表，单签时为空
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenAccountRoles<'a, AccountType> {
+    /// InitializeMint/InitializeMint2以及只作用于mint的初始化扩展指令
+    InitializeMint {
+        mint: &'a AccountType,
+    },
+    /// InitializeAccount/2/3
+    InitializeAccount {
+        account: &'a AccountType,
+        mint: &'a AccountType,
+        owner: TokenAccountOwner<'a, AccountType>,
+    },
+    InitializeMultisig {
+        multisig: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    /// Transfer/TransferChecked/TransferCheckedWithFee, 只有Transfer不带mint
+    ///
+    /// Token-2022的mint带有transfer hook时，固定帐户之后还有hook需要的额外帐户，
+    /// 只看指令无法与多签的签名者区分，见[spl_token_2022_account_roles_with_permissions]
+    Transfer {
+        source: &'a AccountType,
+        mint: Option<&'a AccountType>,
+        destination: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+        /// 固定帐户之后不能确定是签名者的帐户，如transfer hook程序及其需要的帐户
+        extra_accounts: Vec<&'a AccountType>,
+    },
+    /// Approve/ApproveChecked, 只有ApproveChecked带mint
+    Approve {
+        source: &'a AccountType,
+        mint: Option<&'a AccountType>,
+        delegate: &'a AccountType,
+        owner: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    Revoke {
+        source: &'a AccountType,
+        owner: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    SetAuthority {
+        target: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    /// MintTo/MintToChecked
+    MintTo {
+        mint: &'a AccountType,
+        destination: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    /// Burn/BurnChecked
+    Burn {
+        account: &'a AccountType,
+        mint: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    CloseAccount {
+        account: &'a AccountType,
+        destination: &'a AccountType,
+        owner: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    FreezeAccount {
+        account: &'a AccountType,
+        mint: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    ThawAccount {
+        account: &'a AccountType,
+        mint: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    SyncNative {
+        account: &'a AccountType,
+    },
+    InitializeImmutableOwner {
+        account: &'a AccountType,
+    },
+    /// GetAccountDataSize/AmountToUiAmount/UiAmountToAmount等只读取mint的指令
+    MintQuery {
+        mint: &'a AccountType,
+    },
+    Reallocate {
+        account: &'a AccountType,
+        payer: &'a AccountType,
+        owner: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+    CreateNativeMint {
+        funding: &'a AccountType,
+        native_mint: &'a AccountType,
+    },
+    WithdrawExcessLamports {
+        source: &'a AccountType,
+        destination: &'a AccountType,
+        authority: &'a AccountType,
+        signers: Vec<&'a AccountType>,
+    },
+}
+
+/// 解析SPL Token指令的帐户角色
+pub fn spl_token_account_roles<'a, AccountType>(
+    instruction: &SplTokenInstruction,
+    accounts: &[u8],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+) -> Result<Option<TokenAccountRoles<'a, AccountType>>, AccountRoleError> {
+    // Token-2022是SPL Token的超集，帐户布局相同
+    let mut roles = spl_token_2022_account_roles(
+        &SplToken2022Instruction::from(instruction.clone()),
+        accounts,
+        transaction_accounts,
+    )?;
+    // SPL Token没有transfer hook，固定帐户之后的都是多签的签名者
+    if let Some(TokenAccountRoles::Transfer {
+        signers,
+        extra_accounts,
+        ..
+    }) = &mut roles
+    {
+        signers.append(extra_accounts);
+    }
+    Ok(roles)
+}
+
+/// 解析Token-2022指令的帐户角色
+///
+/// Transfer类指令固定帐户之后的帐户都放在`extra_accounts`中，`signers`为空。
+/// 除TransferCheckedWithFee外，扩展指令(TransferFeeExtension等)的帐户取决于子指令，
+/// 此时返回[AccountRoleError::UnsupportedInstruction]
+pub fn spl_token_2022_account_roles<'a, AccountType>(
+    instruction: &SplToken2022Instruction,
+    accounts: &[u8],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+) -> Result<Option<TokenAccountRoles<'a, AccountType>>, AccountRoleError> {
+    let a = InstructionAccounts::new(accounts, transaction_accounts);
+    let roles = match instruction {
+        SplToken2022Instruction::InitializeMint { .. }
+        | SplToken2022Instruction::InitializeMint2 { .. }
+        | SplToken2022Instruction::InitializeMintCloseAuthority { .. }
+        | SplToken2022Instruction::InitializeNonTransferableMint
+        | SplToken2022Instruction::InitializePermanentDelegate { .. } => {
+            TokenAccountRoles::InitializeMint { mint: a.get(0)? }
+        }
+        SplToken2022Instruction::InitializeAccount => TokenAccountRoles::InitializeAccount {
+            account: a.get(0)?,
+            mint: a.get(1)?,
+            owner: TokenAccountOwner::Account(a.get(2)?),
+        },
+        SplToken2022Instruction::InitializeAccount2 { owner }
+        | SplToken2022Instruction::InitializeAccount3 { owner } => {
+            TokenAccountRoles::InitializeAccount {
+                account: a.get(0)?,
+                mint: a.get(1)?,
+                owner: TokenAccountOwner::Pubkey(*owner),
+            }
+        }
+        SplToken2022Instruction::InitializeMultisig { .. } => {
+            TokenAccountRoles::InitializeMultisig {
+                multisig: a.get(0)?,
+                signers: a.remaining(2)?,
+            }
+        }
+        SplToken2022Instruction::InitializeMultisig2 { .. } => {
+            TokenAccountRoles::InitializeMultisig {
+                multisig: a.get(0)?,
+                signers: a.remaining(1)?,
+            }
+        }
+        #[allow(deprecated)]
+        SplToken2022Instruction::Transfer { .. } => TokenAccountRoles::Transfer {
+            source: a.get(0)?,
+            mint: None,
+            destination: a.get(1)?,
+            authority: a.get(2)?,
+            signers: vec![],
+            extra_accounts: a.remaining(3)?,
+        },
+        SplToken2022Instruction::TransferChecked { .. }
+        | SplToken2022Instruction::TransferCheckedWithFee { .. } => TokenAccountRoles::Transfer {
+            source: a.get(0)?,
+            mint: Some(a.get(1)?),
+            destination: a.get(2)?,
+            authority: a.get(3)?,
+            signers: vec![],
+            extra_accounts: a.remaining(4)?,
+        },
+        SplToken2022Instruction::Approve { .. } => TokenAccountRoles::Approve {
+            source: a.get(0)?,
+            mint: None,
+            delegate: a.get(1)?,
+            owner: a.get(2)?,
+            signers: a.remaining(3)?,
+        },
+        SplToken2022Instruction::ApproveChecked { .. } => TokenAccountRoles::Approve {
+            source: a.get(0)?,
+            mint: Some(a.get(1)?),
+            delegate: a.get(2)?,
+            owner: a.get(3)?,
+            signers: a.remaining(4)?,
+        },
+        SplToken2022Instruction::Revoke => TokenAccountRoles::Revoke {
+            source: a.get(0)?,
+            owner: a.get(1)?,
+            signers: a.remaining(2)?,
+        },
+        SplToken2022Instruction::SetAuthority { .. } => TokenAccountRoles::SetAuthority {
+            target: a.get(0)?,
+            authority: a.get(1)?,
+            signers: a.remaining(2)?,
+        },
+        SplToken2022Instruction::MintTo { .. } | SplToken2022Instruction::MintToChecked { .. } => {
+            TokenAccountRoles::MintTo {
+                mint: a.get(0)?,
+                destination: a.get(1)?,
+                authority: a.get(2)?,
+                signers: a.remaining(3)?,
+            }
+        }
+        SplToken2022Instruction::Burn { .. } | SplToken2022Instruction::BurnChecked { .. } => {
+            TokenAccountRoles::Burn {
+                account: a.get(0)?,
+                mint: a.get(1)?,
+                authority: a.get(2)?,
+                signers: a.remaining(3)?,
+            }
+        }
+        SplToken2022Instruction::CloseAccount => TokenAccountRoles::CloseAccount {
+            account: a.get(0)?,
+            destination: a.get(1)?,
+            owner: a.get(2)?,
+            signers: a.remaining(3)?,
+        },
+        SplToken2022Instruction::FreezeAccount => TokenAccountRoles::FreezeAccount {
+            account: a.get(0)?,
+            mint: a.get(1)?,
+            authority: a.get(2)?,
+            signers: a.remaining(3)?,
+        },
+        SplToken2022Instruction::ThawAccount => TokenAccountRoles::ThawAccount {
+            account: a.get(0)?,
+            mint: a.get(1)?,
+            authority: a.get(2)?,
+            signers: a.remaining(3)?,
+        },
+        SplToken2022Instruction::SyncNative => TokenAccountRoles::SyncNative { account: a.get(0)? },
+        SplToken2022Instruction::InitializeImmutableOwner => {
+            TokenAccountRoles::InitializeImmutableOwner { account: a.get(0)? }
+        }
+        SplToken2022Instruction::GetAccountDataSize { .. }
+        | SplToken2022Instruction::AmountToUiAmount { .. }
+        | SplToken2022Instruction::UiAmountToAmount { .. } => {
+            TokenAccountRoles::MintQuery { mint: a.get(0)? }
+        }
+        SplToken2022Instruction::Reallocate { .. } => TokenAccountRoles::Reallocate {
+            account: a.get(0)?,
+            payer: a.get(1)?,
+            owner: a.get(3)?,
+            signers: a.remaining(4)?,
+        },
+        SplToken2022Instruction::CreateNativeMint => TokenAccountRoles::CreateNativeMint {
+            funding: a.get(0)?,
+            native_mint: a.get(1)?,
+        },
+        SplToken2022Instruction::WithdrawExcessLamports => {
+            TokenAccountRoles::WithdrawExcessLamports {
+                source: a.get(0)?,
+                destination: a.get(1)?,
+                authority: a.get(2)?,
+                signers: a.remaining(3)?,
+            }
+        }
+        SplToken2022Instruction::TransferFeeExtension
+        | SplToken2022Instruction::ConfidentialTransferExtension
+        | SplToken2022Instruction::DefaultAccountStateExtension
+        | SplToken2022Instruction::MemoTransferExtension
+        | SplToken2022Instruction::InterestBearingMintExtension
+        | SplToken2022Instruction::CpiGuardExtension
+        | SplToken2022Instruction::TransferHookExtension
+        | SplToken2022Instruction::ConfidentialTransferFeeExtension
+        | SplToken2022Instruction::MetadataPointerExtension
+        | SplToken2022Instruction::GroupPointerExtension
+        | SplToken2022Instruction::GroupMemberPointerExtension
+        | SplToken2022Instruction::ConfidentialMintBurnExtension
+        | SplToken2022Instruction::ScaledUiAmountExtension
+        | SplToken2022Instruction::PausableExtension => {
+            return Err(AccountRoleError::UnsupportedInstruction(format!(
+                "{instruction:?}"
+            )));
+        }
+    };
+
+    Ok(Some(roles))
+}
+
+/// 解析Token-2022指令的帐户角色，根据交易中的签名者区分Transfer类指令的多签签名者和额外帐户
+///
+/// 多签的签名者必须签名交易，transfer hook需要的帐户则不是签名者
+pub fn spl_token_2022_account_roles_with_permissions<'a, AccountType>(
+    instruction: &SplToken2022Instruction,
+    accounts: &[u8],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+    permissions: &AccountPermissions,
+) -> Result<Option<TokenAccountRoles<'a, AccountType>>, AccountRoleError> {
+    let mut roles = spl_token_2022_account_roles(instruction, accounts, transaction_accounts)?;
+    if let Some(TokenAccountRoles::Transfer {
+        signers,
+        extra_accounts,
+        ..
+    }) = &mut roles
+    {
+        // extra_accounts是指令帐户列表的末尾部分
+        let from = accounts.len() - extra_accounts.len();
+        let (signed, unsigned): (Vec<_>, Vec<_>) = accounts[from..]
+            .iter()
+            .zip(extra_accounts.drain(..))
+            .partition(|(index, _)| permissions.is_signer(**index as usize));
+        *signers = signed.into_iter().map(|(_, account)| account).collect();
+        *extra_accounts = unsigned.into_iter().map(|(_, account)| account).collect();
+    }
+    Ok(roles)
+}
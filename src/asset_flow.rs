@@ -0,0 +1,274 @@
+use crate::account_roles::system::SystemAccountRoles;
+use crate::account_roles::token::TokenAccountRoles;
+use crate::account_roles::{AccountRoleError, InstructionAccounts, account_at};
+use crate::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use crate::parsed_instruction::instruction_iter::{InstructionIter, InstructionPath};
+use crate::parsed_instruction::{ParsedInstruction, ParsedInstructionData};
+use crate::utils::TransactionAccounts;
+use solana_sdk::system_instruction::SystemInstruction;
+use tracing::error;
+
+/// 资产流动的来源指令种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetFlowKind {
+    /// System Transfer/TransferWithSeed, Token Transfer/TransferChecked
+    Transfer,
+    /// Token-2022 TransferCheckedWithFee, 子指令在解析时已还原，不依赖[crate::parsed_instruction::ParseOptions::keep_raw_data]
+    TransferWithFee,
+    /// System CreateAccount/CreateAccountWithSeed的资金注入
+    CreateAccount,
+    MintTo,
+    Burn,
+    /// 关闭token帐户时退还租金
+    CloseAccount,
+    WithdrawExcessLamports,
+}
+
+/// 流动的资产
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowAsset<'a, AccountType> {
+    /// 原生SOL, 数量单位为lamports
+    Sol,
+    /// SPL token, 未检查的Transfer/MintTo/Burn指令中没有mint或decimals
+    Token {
+        mint: Option<&'a AccountType>,
+        decimals: Option<u8>,
+    },
+}
+
+/// 一次资产流动
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetFlow<'a, AccountType> {
+    pub kind: AssetFlowKind,
+    pub asset: FlowAsset<'a, AccountType>,
+    /// 铸造时没有来源
+    pub source: Option<&'a AccountType>,
+    /// 销毁时没有去向
+    pub destination: Option<&'a AccountType>,
+    /// CloseAccount/WithdrawExcessLamports退还的lamports不在指令中，取自来源帐户前后余额之差，没有余额数据时为None
+    pub amount: Option<u64>,
+    /// Token-2022 transfer-with-fee中扣除的手续费，已包含在amount中
+    pub fee: Option<u64>,
+    /// 产生此流动的指令路径
+    pub path: InstructionPath,
+}
+
+/// 提取交易中所有指令(包括内部指令)产生的资产流动
+///
+/// 帐户不完整的指令会被跳过并记录错误；没有余额数据，CloseAccount/WithdrawExcessLamports的数量为None，
+/// 有meta时请使用[extract_asset_flows_with_balances]
+pub fn extract_asset_flows<'a, AccountType>(
+    instructions: &[ParsedInstruction],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+) -> Vec<AssetFlow<'a, AccountType>> {
+    collect_asset_flows(instructions, transaction_accounts, None)
+}
+
+/// 同[extract_asset_flows]，CloseAccount/WithdrawExcessLamports的数量取自meta中来源帐户的前后余额之差
+///
+/// 同一帐户在交易中有多次lamports变动时，差值为所有变动之和
+pub fn extract_asset_flows_with_balances<'a, AccountType>(
+    instructions: &[ParsedInstruction],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+    pre_balances: &[u64],
+    post_balances: &[u64],
+) -> Vec<AssetFlow<'a, AccountType>> {
+    collect_asset_flows(
+        instructions,
+        transaction_accounts,
+        Some((pre_balances, post_balances)),
+    )
+}
+
+fn collect_asset_flows<'a, AccountType>(
+    instructions: &[ParsedInstruction],
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+    balances: Option<(&[u64], &[u64])>,
+) -> Vec<AssetFlow<'a, AccountType>> {
+    let mut flows = vec![];
+    for node in InstructionIter::new(instructions) {
+        match asset_flow(node.instruction, &node.path, transaction_accounts, balances) {
+            Ok(Some(flow)) => flows.push(flow),
+            Ok(None) => {}
+            Err(e) => error!("提取资产流动出错, 指令{}: {e}", node.path),
+        }
+    }
+
+    flows
+}
+
+/// 提取单条指令(不包括内部指令)产生的资产流动，不产生流动时返回Ok(None)
+///
+/// `balances`为meta中的(pre_balances, post_balances)，用于得到CloseAccount/WithdrawExcessLamports的数量
+pub fn asset_flow<'a, AccountType>(
+    instruction: &ParsedInstruction,
+    path: &InstructionPath,
+    transaction_accounts: &TransactionAccounts<'a, AccountType>,
+    balances: Option<(&[u64], &[u64])>,
+) -> Result<Option<AssetFlow<'a, AccountType>>, AccountRoleError> {
+    let flow = |kind, asset, source, destination, amount| AssetFlow {
+        kind,
+        asset,
+        source,
+        destination,
+        amount,
+        fee: None,
+        path: path.clone(),
+    };
+
+    match &instruction.instruction_data {
+        ParsedInstructionData::System(system) => {
+            let amount = match system {
+                SystemInstruction::Transfer { lamports }
+                | SystemInstruction::TransferWithSeed { lamports, .. }
+                | SystemInstruction::CreateAccount { lamports, .. }
+                | SystemInstruction::CreateAccountWithSeed { lamports, .. } => *lamports,
+                _ => return Ok(None),
+            };
+            let result = match instruction.system_account_roles(transaction_accounts)? {
+                Some(SystemAccountRoles::Transfer { from, to, .. }) => flow(
+                    AssetFlowKind::Transfer,
+                    FlowAsset::Sol,
+                    Some(from),
+                    Some(to),
+                    Some(amount),
+                ),
+                Some(SystemAccountRoles::CreateAccount {
+                    funding,
+                    new_account,
+                    ..
+                }) => flow(
+                    AssetFlowKind::CreateAccount,
+                    FlowAsset::Sol,
+                    Some(funding),
+                    Some(new_account),
+                    Some(amount),
+                ),
+                _ => return Ok(None),
+            };
+            Ok(Some(result))
+        }
+        ParsedInstructionData::SplToken2022(SplToken2022Instruction::TransferCheckedWithFee {
+            amount,
+            decimals,
+            fee,
+        }) => {
+            let a = InstructionAccounts::new(&instruction.accounts, transaction_accounts);
+            Ok(Some(AssetFlow {
+                kind: AssetFlowKind::TransferWithFee,
+                asset: FlowAsset::Token {
+                    mint: Some(a.get(1)?),
+                    decimals: Some(*decimals),
+                },
+                source: Some(a.get(0)?),
+                destination: Some(a.get(2)?),
+                amount: Some(*amount),
+                fee: Some(*fee),
+                path: path.clone(),
+            }))
+        }
+        ParsedInstructionData::SplToken(_) | ParsedInstructionData::SplToken2022(_) => {
+            let (amount, decimals) = match token_amount(&instruction.instruction_data) {
+                Some(amount) => amount,
+                None => return Ok(None),
+            };
+            let token = |mint| FlowAsset::Token { mint, decimals };
+            let result = match instruction.token_account_roles(transaction_accounts)? {
+                Some(TokenAccountRoles::Transfer {
+                    source,
+                    mint,
+                    destination,
+                    ..
+                }) => flow(
+                    AssetFlowKind::Transfer,
+                    token(mint),
+                    Some(source),
+                    Some(destination),
+                    amount,
+                ),
+                Some(TokenAccountRoles::MintTo {
+                    mint, destination, ..
+                }) => flow(
+                    AssetFlowKind::MintTo,
+                    token(Some(mint)),
+                    None,
+                    Some(destination),
+                    amount,
+                ),
+                Some(TokenAccountRoles::Burn { account, mint, .. }) => flow(
+                    AssetFlowKind::Burn,
+                    token(Some(mint)),
+                    Some(account),
+                    None,
+                    amount,
+                ),
+                Some(TokenAccountRoles::CloseAccount {
+                    account,
+                    destination,
+                    ..
+                }) => flow(
+                    AssetFlowKind::CloseAccount,
+                    FlowAsset::Sol,
+                    Some(account),
+                    Some(destination),
+                    released_lamports(&instruction.accounts, balances)?,
+                ),
+                Some(TokenAccountRoles::WithdrawExcessLamports {
+                    source,
+                    destination,
+                    ..
+                }) => flow(
+                    AssetFlowKind::WithdrawExcessLamports,
+                    FlowAsset::Sol,
+                    Some(source),
+                    Some(destination),
+                    released_lamports(&instruction.accounts, balances)?,
+                ),
+                _ => return Ok(None),
+            };
+            Ok(Some(result))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 指令第0个帐户(被关闭或提取lamports的帐户)在交易中减少的lamports，没有余额数据时为None
+fn released_lamports(
+    accounts: &[u8],
+    balances: Option<(&[u64], &[u64])>,
+) -> Result<Option<u64>, AccountRoleError> {
+    let Some((pre_balances, post_balances)) = balances else {
+        return Ok(None);
+    };
+    let index = account_at(accounts, 0)? as usize;
+    match (pre_balances.get(index), post_balances.get(index)) {
+        (Some(pre), Some(post)) => Ok(Some(pre.saturating_sub(*post))),
+        _ => Err(AccountRoleError::UnknownAccount { index: index as u8 }),
+    }
+}
+
+/// 获取产生资产流动的token指令中的数量和精度，外层None表示指令不产生流动
+fn token_amount(data: &ParsedInstructionData) -> Option<(Option<u64>, Option<u8>)> {
+    let instruction = match data {
+        ParsedInstructionData::SplToken(t) => SplToken2022Instruction::from(t.clone()),
+        ParsedInstructionData::SplToken2022(t) => t.clone(),
+        _ => return None,
+    };
+    let amount = match instruction {
+        #[allow(deprecated)]
+        SplToken2022Instruction::Transfer { amount }
+        | SplToken2022Instruction::MintTo { amount }
+        | SplToken2022Instruction::Burn { amount } => (Some(amount), None),
+        SplToken2022Instruction::TransferChecked { amount, decimals }
+        | SplToken2022Instruction::MintToChecked { amount, decimals }
+        | SplToken2022Instruction::BurnChecked { amount, decimals } => {
+            (Some(amount), Some(decimals))
+        }
+        SplToken2022Instruction::CloseAccount | SplToken2022Instruction::WithdrawExcessLamports => {
+            (None, None)
+        }
+        _ => return None,
+    };
+
+    Some(amount)
+}
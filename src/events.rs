@@ -0,0 +1,117 @@
+pub mod builtin;
+pub mod idl;
+pub mod reader;
+
+use crate::events::builtin::{MeteoraDlmmSwapEvent, PumpFunTradeEvent, RaydiumClmmSwapEvent};
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::program_log::{LogEntry, ProgramLogs};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::collections::HashMap;
+
+/// Anchor事件的discriminator: sha256("event:<事件名>")的前8字节
+pub type EventDiscriminator = [u8; 8];
+
+pub fn event_discriminator(name: &str) -> EventDiscriminator {
+    let hash = solana_sdk::hash::hashv(&[b"event:", name.as_bytes()]);
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+/// 解码后的事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedEvent {
+    PumpFunTrade(PumpFunTradeEvent),
+    RaydiumClmmSwap(RaydiumClmmSwapEvent),
+    MeteoraDlmmSwap(MeteoraDlmmSwapEvent),
+    /// 通过IDL解码的事件
+    Idl {
+        name: String,
+        fields: serde_json::Value,
+    },
+}
+
+/// 某个程序的事件解码器
+pub trait EventDecoder: Send + Sync + 'static {
+    fn program_id(&self) -> &str;
+
+    /// 能解码的事件
+    fn discriminators(&self) -> Vec<EventDiscriminator>;
+
+    /// `data`为去掉discriminator后的事件数据
+    fn decode(&self, discriminator: &EventDiscriminator, data: &[u8]) -> Option<DecodedEvent>;
+}
+
+/// 指令输出的事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramEvent {
+    /// 输出事件的指令路径
+    pub path: InstructionPath,
+    pub program_id: String,
+    pub event: DecodedEvent,
+}
+
+/// 以(程序id, discriminator)为键的事件解码器注册表
+#[derive(Default)]
+pub struct EventRegistry {
+    decoders: Vec<Box<dyn EventDecoder>>,
+    index: HashMap<(String, EventDiscriminator), usize>,
+}
+
+impl EventRegistry {
+    /// 空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 包含内置解码器的注册表
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        for decoder in builtin::decoders() {
+            registry.register(decoder);
+        }
+        registry
+    }
+
+    /// 注册解码器，相同(程序id, discriminator)的解码器会被覆盖
+    pub fn register(&mut self, decoder: impl EventDecoder) {
+        let position = self.decoders.len();
+        for discriminator in decoder.discriminators() {
+            self.index
+                .insert((decoder.program_id().to_string(), discriminator), position);
+        }
+        self.decoders.push(Box::new(decoder));
+    }
+
+    /// 解码完整的事件数据(包括discriminator)
+    pub fn decode(&self, program_id: &str, data: &[u8]) -> Option<DecodedEvent> {
+        let discriminator: EventDiscriminator = data.get(..8)?.try_into().ok()?;
+        let position = self.index.get(&(program_id.to_string(), discriminator))?;
+        self.decoders[*position].decode(&discriminator, &data[8..])
+    }
+
+    /// 解码日志中`Program data:`里的事件，并对应到输出事件的指令
+    pub fn decode_logs(&self, logs: &ProgramLogs) -> Vec<ProgramEvent> {
+        let mut events = vec![];
+        for invocation in &logs.invocations {
+            for entry in &invocation.logs {
+                let LogEntry::Data(chunks) = entry else {
+                    continue;
+                };
+                let Some(data) = chunks.first().and_then(|c| STANDARD.decode(c).ok()) else {
+                    continue;
+                };
+                if let Some(event) = self.decode(&invocation.program_id, &data) {
+                    events.push(ProgramEvent {
+                        path: invocation.path.clone(),
+                        program_id: invocation.program_id.clone(),
+                        event,
+                    });
+                }
+            }
+        }
+
+        events
+    }
+}
@@ -0,0 +1,134 @@
+use crate::events::reader::BorshReader;
+use crate::events::{DecodedEvent, EventDecoder, EventDiscriminator, event_discriminator};
+use solana_pubkey::Pubkey;
+
+pub const PUMP_FUN_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+pub const RAYDIUM_CLMM_PROGRAM: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+pub const METEORA_DLMM_PROGRAM: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo";
+
+/// pump.fun的TradeEvent, 只解码各版本共有的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PumpFunTradeEvent {
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+}
+
+/// Raydium CLMM的SwapEvent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaydiumClmmSwapEvent {
+    pub pool_state: Pubkey,
+    pub sender: Pubkey,
+    pub token_account_0: Pubkey,
+    pub token_account_1: Pubkey,
+    pub amount_0: u64,
+    pub transfer_fee_0: u64,
+    pub amount_1: u64,
+    pub transfer_fee_1: u64,
+    pub zero_for_one: bool,
+    pub sqrt_price_x64: u128,
+    pub liquidity: u128,
+    pub tick: i32,
+}
+
+/// Meteora DLMM的Swap事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeteoraDlmmSwapEvent {
+    pub lb_pair: Pubkey,
+    pub from: Pubkey,
+    pub start_bin_id: i32,
+    pub end_bin_id: i32,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub swap_for_y: bool,
+    pub fee: u64,
+    pub protocol_fee: u64,
+    pub fee_bps: u128,
+    pub host_fee: u64,
+}
+
+/// 单个事件的内置解码器
+struct BuiltinDecoder {
+    program_id: &'static str,
+    discriminator: EventDiscriminator,
+    decode: fn(&mut BorshReader) -> Option<DecodedEvent>,
+}
+
+impl EventDecoder for BuiltinDecoder {
+    fn program_id(&self) -> &str {
+        self.program_id
+    }
+
+    fn discriminators(&self) -> Vec<EventDiscriminator> {
+        vec![self.discriminator]
+    }
+
+    fn decode(&self, _discriminator: &EventDiscriminator, data: &[u8]) -> Option<DecodedEvent> {
+        (self.decode)(&mut BorshReader::new(data))
+    }
+}
+
+pub(crate) fn decoders() -> Vec<impl EventDecoder> {
+    vec![
+        BuiltinDecoder {
+            program_id: PUMP_FUN_PROGRAM,
+            discriminator: event_discriminator("TradeEvent"),
+            decode: |r| {
+                Some(DecodedEvent::PumpFunTrade(PumpFunTradeEvent {
+                    mint: r.pubkey()?,
+                    sol_amount: r.u64()?,
+                    token_amount: r.u64()?,
+                    is_buy: r.bool()?,
+                    user: r.pubkey()?,
+                    timestamp: r.i64()?,
+                    virtual_sol_reserves: r.u64()?,
+                    virtual_token_reserves: r.u64()?,
+                }))
+            },
+        },
+        BuiltinDecoder {
+            program_id: RAYDIUM_CLMM_PROGRAM,
+            discriminator: event_discriminator("SwapEvent"),
+            decode: |r| {
+                Some(DecodedEvent::RaydiumClmmSwap(RaydiumClmmSwapEvent {
+                    pool_state: r.pubkey()?,
+                    sender: r.pubkey()?,
+                    token_account_0: r.pubkey()?,
+                    token_account_1: r.pubkey()?,
+                    amount_0: r.u64()?,
+                    transfer_fee_0: r.u64()?,
+                    amount_1: r.u64()?,
+                    transfer_fee_1: r.u64()?,
+                    zero_for_one: r.bool()?,
+                    sqrt_price_x64: r.u128()?,
+                    liquidity: r.u128()?,
+                    tick: r.i32()?,
+                }))
+            },
+        },
+        BuiltinDecoder {
+            program_id: METEORA_DLMM_PROGRAM,
+            discriminator: event_discriminator("Swap"),
+            decode: |r| {
+                Some(DecodedEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
+                    lb_pair: r.pubkey()?,
+                    from: r.pubkey()?,
+                    start_bin_id: r.i32()?,
+                    end_bin_id: r.i32()?,
+                    amount_in: r.u64()?,
+                    amount_out: r.u64()?,
+                    swap_for_y: r.bool()?,
+                    fee: r.u64()?,
+                    protocol_fee: r.u64()?,
+                    fee_bps: r.u128()?,
+                    host_fee: r.u64()?,
+                }))
+            },
+        },
+    ]
+}
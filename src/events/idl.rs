@@ -0,0 +1,208 @@
+use crate::events::reader::BorshReader;
+use crate::events::{DecodedEvent, EventDecoder, EventDiscriminator, event_discriminator};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IdlError {
+    #[error("IDL不是合法的json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("IDL格式错误: {0}")]
+    InvalidIdl(String),
+}
+
+/// 根据Anchor IDL在运行时解码事件，同时支持新(0.30+)旧两种IDL格式
+///
+/// 解码结果为json, u128/i128等超出json数字范围的值使用字符串表示
+pub struct IdlEventDecoder {
+    program_id: String,
+    events: HashMap<EventDiscriminator, IdlEvent>,
+    /// IDL中定义的类型，键为类型名，值为其`type`定义
+    types: HashMap<String, Value>,
+}
+
+struct IdlEvent {
+    name: String,
+    /// 事件结构的`type`定义
+    definition: Value,
+}
+
+impl IdlEventDecoder {
+    pub fn from_json_str(program_id: impl Into<String>, idl: &str) -> Result<Self, IdlError> {
+        Self::from_json(program_id, &serde_json::from_str(idl)?)
+    }
+
+    pub fn from_json(program_id: impl Into<String>, idl: &Value) -> Result<Self, IdlError> {
+        let types: HashMap<String, Value> = idl
+            .get("types")
+            .and_then(Value::as_array)
+            .map(|types| {
+                types
+                    .iter()
+                    .filter_map(|t| {
+                        Some((t.get("name")?.as_str()?.to_string(), t.get("type")?.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut events = HashMap::new();
+        for event in idl
+            .get("events")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = event
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| IdlError::InvalidIdl("事件缺少name".to_string()))?;
+            let discriminator = match event.get("discriminator") {
+                Some(d) => serde_json::from_value::<EventDiscriminator>(d.clone())?,
+                None => event_discriminator(name),
+            };
+            // 旧格式的字段直接写在事件中，新格式的字段在同名类型中
+            let definition = match event.get("fields") {
+                Some(fields) => serde_json::json!({"kind": "struct", "fields": fields}),
+                None => types
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| IdlError::InvalidIdl(format!("找不到事件类型: {name}")))?,
+            };
+            events.insert(
+                discriminator,
+                IdlEvent {
+                    name: name.to_string(),
+                    definition,
+                },
+            );
+        }
+
+        Ok(Self {
+            program_id: program_id.into(),
+            events,
+            types,
+        })
+    }
+
+    fn decode_definition(&self, definition: &Value, reader: &mut BorshReader) -> Option<Value> {
+        match definition.get("kind")?.as_str()? {
+            "struct" => match definition.get("fields") {
+                Some(fields) => self.decode_fields(fields, reader),
+                None => Some(Value::Null),
+            },
+            "enum" => {
+                let variant = definition.get("variants")?.get(reader.u8()? as usize)?;
+                let name = variant.get("name")?.as_str()?.to_string();
+                match variant.get("fields") {
+                    Some(fields) => {
+                        let mut object = Map::new();
+                        object.insert(name, self.decode_fields(fields, reader)?);
+                        Some(Value::Object(object))
+                    }
+                    None => Some(Value::String(name)),
+                }
+            }
+            "alias" => self.decode_type(definition.get("value")?, reader),
+            _ => None,
+        }
+    }
+
+    /// 具名字段解码为object, 元组字段解码为array
+    fn decode_fields(&self, fields: &Value, reader: &mut BorshReader) -> Option<Value> {
+        let fields = fields.as_array()?;
+        if fields.iter().all(|f| f.get("name").is_some()) {
+            let mut object = Map::new();
+            for field in fields {
+                let name = field.get("name")?.as_str()?.to_string();
+                object.insert(name, self.decode_type(field.get("type")?, reader)?);
+            }
+            Some(Value::Object(object))
+        } else {
+            fields
+                .iter()
+                .map(|ty| self.decode_type(ty, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array)
+        }
+    }
+
+    fn decode_type(&self, ty: &Value, reader: &mut BorshReader) -> Option<Value> {
+        if let Some(name) = ty.as_str() {
+            return Some(match name {
+                "bool" => Value::from(reader.bool()?),
+                "u8" => Value::from(reader.u8()?),
+                "u16" => Value::from(reader.u16()?),
+                "u32" => Value::from(reader.u32()?),
+                "u64" => Value::from(reader.u64()?),
+                "i8" => Value::from(reader.i8()?),
+                "i16" => Value::from(reader.i16()?),
+                "i32" => Value::from(reader.i32()?),
+                "i64" => Value::from(reader.i64()?),
+                "u128" => Value::from(reader.u128()?.to_string()),
+                "i128" => Value::from(reader.i128()?.to_string()),
+                "f32" => Value::from(reader.f32()?),
+                "f64" => Value::from(reader.f64()?),
+                "string" => Value::from(reader.string()?),
+                "pubkey" | "publicKey" => Value::from(reader.pubkey()?.to_string()),
+                "bytes" => {
+                    let len = reader.u32()? as usize;
+                    Value::from(reader.take(len)?.to_vec())
+                }
+                _ => return None,
+            });
+        }
+
+        let ty = ty.as_object()?;
+        if let Some(inner) = ty.get("option") {
+            return match reader.u8()? {
+                0 => Some(Value::Null),
+                1 => self.decode_type(inner, reader),
+                _ => None,
+            };
+        }
+        if let Some(inner) = ty.get("vec") {
+            let len = reader.u32()? as usize;
+            return (0..len)
+                .map(|_| self.decode_type(inner, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(array) = ty.get("array").and_then(Value::as_array) {
+            let (inner, len) = (array.first()?, array.get(1)?.as_u64()? as usize);
+            return (0..len)
+                .map(|_| self.decode_type(inner, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(defined) = ty.get("defined") {
+            // 旧格式为类型名，新格式为{"name": 类型名}
+            let name = defined
+                .as_str()
+                .or_else(|| defined.get("name").and_then(Value::as_str))?;
+            return self.decode_definition(self.types.get(name)?, reader);
+        }
+
+        None
+    }
+}
+
+impl EventDecoder for IdlEventDecoder {
+    fn program_id(&self) -> &str {
+        &self.program_id
+    }
+
+    fn discriminators(&self) -> Vec<EventDiscriminator> {
+        self.events.keys().copied().collect()
+    }
+
+    fn decode(&self, discriminator: &EventDiscriminator, data: &[u8]) -> Option<DecodedEvent> {
+        let event = self.events.get(discriminator)?;
+        let fields = self.decode_definition(&event.definition, &mut BorshReader::new(data))?;
+        Some(DecodedEvent::Idl {
+            name: event.name.clone(),
+            fields,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use solana_pubkey::Pubkey;
+
+/// 按borsh格式顺序读取事件数据，数据不足时返回None
+pub struct BorshReader<'a> {
+    data: &'a [u8],
+}
+
+macro_rules! read_number {
+    ($($name:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $name(&mut self) -> Option<$ty> {
+                let bytes = self.take(size_of::<$ty>())?;
+                Some(<$ty>::from_le_bytes(bytes.try_into().ok()?))
+            }
+        )*
+    };
+}
+
+impl<'a> BorshReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// 剩余未读取的数据
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    read_number!(
+        u8: u8, u16: u16, u32: u32, u64: u64, u128: u128,
+        i8: i8, i16: i16, i32: i32, i64: i64, i128: i128,
+        f32: f32, f64: f64,
+    );
+
+    pub fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn pubkey(&mut self) -> Option<Pubkey> {
+        Some(Pubkey::new_from_array(self.take(32)?.try_into().ok()?))
+    }
+
+    pub fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
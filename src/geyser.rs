@@ -0,0 +1,2 @@
+pub mod geyser_error;
+pub mod transaction;
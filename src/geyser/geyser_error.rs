@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GeyserError {
+    #[error("推送数据缺少字段: {0}")]
+    MissingField(&'static str),
+    #[error("转换protobuf数据出错: {0}")]
+    Convert(String),
+}
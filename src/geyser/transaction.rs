@@ -0,0 +1,170 @@
+use crate::geyser::geyser_error::GeyserError;
+use crate::parsed_instruction::{ParsedInstruction, ParsedInstructionList};
+use crate::token_account_index::TokenAccountIndex;
+use crate::transaction::transaction_filter::{TransactionMeta, TransactionPropsProvider};
+use crate::utils::TransactionAccounts;
+use solana_sdk::message::MessageHeader;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::TransactionStatusMeta;
+use yellowstone_grpc_proto::convert_from::{create_tx_meta, create_tx_versioned};
+use yellowstone_grpc_proto::geyser::{
+    SubscribeUpdateBlock, SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo,
+};
+
+/// Yellowstone gRPC推送的交易，已转换成原生类型并解析好指令
+///
+/// 实现了[TransactionPropsProvider]，可以直接交给交易过滤器使用
+#[derive(Debug, Clone)]
+pub struct GeyserTransaction {
+    pub slot: u64,
+    /// 交易在区块中的位置
+    pub index: u64,
+    pub is_vote: bool,
+    pub signatures: Vec<String>,
+    pub transaction: VersionedTransaction,
+    pub meta: TransactionStatusMeta,
+    pub parsed_instructions: ParsedInstructionList,
+    account_keys: Vec<String>,
+    loaded_writable_accounts: Vec<String>,
+    loaded_readonly_accounts: Vec<String>,
+}
+
+impl GeyserTransaction {
+    pub fn from_transaction_info(
+        slot: u64,
+        info: &SubscribeUpdateTransactionInfo,
+    ) -> Result<Self, GeyserError> {
+        let transaction = info
+            .transaction
+            .clone()
+            .ok_or(GeyserError::MissingField("transaction"))?;
+        let meta = info.meta.clone().ok_or(GeyserError::MissingField("meta"))?;
+        let transaction =
+            create_tx_versioned(transaction).map_err(|e| GeyserError::Convert(e.to_string()))?;
+        let meta = create_tx_meta(meta).map_err(|e| GeyserError::Convert(e.to_string()))?;
+
+        let parsed_instructions = ParsedInstructionList::from_native(&transaction, &meta);
+        let signatures = transaction
+            .signatures
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let account_keys = transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let loaded_writable_accounts = meta
+            .loaded_addresses
+            .writable
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let loaded_readonly_accounts = meta
+            .loaded_addresses
+            .readonly
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+
+        Ok(Self {
+            slot,
+            index: info.index,
+            is_vote: info.is_vote,
+            signatures,
+            transaction,
+            meta,
+            parsed_instructions,
+            account_keys,
+            loaded_writable_accounts,
+            loaded_readonly_accounts,
+        })
+    }
+
+    /// 根据token余额构建token帐户索引
+    pub fn token_account_index(&self) -> TokenAccountIndex {
+        TokenAccountIndex::from_token_balances(
+            self.meta.pre_token_balances.as_deref().unwrap_or_default(),
+            self.meta.post_token_balances.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+impl TryFrom<&SubscribeUpdateTransaction> for GeyserTransaction {
+    type Error = GeyserError;
+
+    fn try_from(value: &SubscribeUpdateTransaction) -> Result<Self, Self::Error> {
+        let info = value
+            .transaction
+            .as_ref()
+            .ok_or(GeyserError::MissingField("transaction"))?;
+        Self::from_transaction_info(value.slot, info)
+    }
+}
+
+impl TransactionPropsProvider for GeyserTransaction {
+    fn get_accounts(&self) -> TransactionAccounts<'_, String> {
+        TransactionAccounts::from_accounts(
+            Some(self.account_keys.as_slice()),
+            Some(self.loaded_writable_accounts.as_slice()),
+            Some(self.loaded_readonly_accounts.as_slice()),
+        )
+    }
+
+    fn get_signatures(&self) -> Option<&[String]> {
+        Some(self.signatures.as_slice())
+    }
+
+    fn get_parsed_instructions(&self) -> Option<&[ParsedInstruction]> {
+        Some(self.parsed_instructions.as_slice())
+    }
+
+    fn get_meta(&self) -> Option<TransactionMeta<'_>> {
+        Some(
+            TransactionMeta::new(
+                &self.meta.status,
+                self.meta.fee,
+                self.meta.pre_balances.as_slice(),
+                self.meta.post_balances.as_slice(),
+            )
+            .with_token_balances(
+                self.meta.pre_token_balances.as_deref(),
+                self.meta.post_token_balances.as_deref(),
+            )
+            .with_log_messages(self.meta.log_messages.as_deref())
+            .with_compute_units_consumed(self.meta.compute_units_consumed),
+        )
+    }
+
+    fn get_message_header(&self) -> Option<MessageHeader> {
+        Some(*self.transaction.message.header())
+    }
+}
+
+/// Yellowstone gRPC推送的区块
+#[derive(Debug, Clone)]
+pub struct GeyserBlock {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub block_height: Option<u64>,
+    pub transactions: Vec<GeyserTransaction>,
+}
+
+impl TryFrom<&SubscribeUpdateBlock> for GeyserBlock {
+    type Error = GeyserError;
+
+    fn try_from(value: &SubscribeUpdateBlock) -> Result<Self, Self::Error> {
+        let mut transactions = Vec::with_capacity(value.transactions.len());
+        for info in &value.transactions {
+            transactions.push(GeyserTransaction::from_transaction_info(value.slot, info)?);
+        }
+
+        Ok(Self {
+            slot: value.slot,
+            block_time: value.block_time.as_ref().map(|t| t.timestamp),
+            block_height: value.block_height.as_ref().map(|h| h.block_height),
+            transactions,
+        })
+    }
+}
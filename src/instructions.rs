@@ -1,2 +1,4 @@
+pub mod bpf_loader_upgradeable;
+pub mod compute_budget;
 pub mod spl_token;
 pub mod spl_token_2022;
@@ -0,0 +1,87 @@
+use solana_sdk::program_error::ProgramError;
+
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+
+/// BPF Upgradeable Loader的指令，数据为4字节小端序tag加bincode编码的参数
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde-traits",
+    serde(rename_all_fields = "camelCase", rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpgradeableLoaderInstruction {
+    /// 初始化buffer帐户
+    InitializeBuffer,
+    /// 向buffer写入程序数据
+    Write {
+        offset: u32,
+        bytes: Vec<u8>,
+    },
+    /// 用buffer中的数据部署程序
+    DeployWithMaxDataLen {
+        max_data_len: u64,
+    },
+    /// 用buffer中的数据升级程序
+    Upgrade,
+    SetAuthority,
+    /// 关闭buffer或程序帐户
+    Close,
+    /// 扩大程序数据帐户
+    ExtendProgram {
+        additional_bytes: u32,
+    },
+    SetAuthorityChecked,
+    /// 迁移到Loader v4
+    Migrate,
+    /// 需要升级权限签名的ExtendProgram
+    ExtendProgramChecked {
+        additional_bytes: u32,
+    },
+}
+
+impl UpgradeableLoaderInstruction {
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let u32_at = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                .ok_or(ProgramError::InvalidInstructionData)
+        };
+        let u64_at = |offset: usize| {
+            data.get(offset..offset + 8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                .ok_or(ProgramError::InvalidInstructionData)
+        };
+        Ok(match u32_at(0)? {
+            0 => Self::InitializeBuffer,
+            1 => {
+                // bincode编码的Vec<u8>: u64长度加数据
+                let len = usize::try_from(u64_at(8)?)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                let bytes = data
+                    .get(16..)
+                    .and_then(|b| b.get(..len))
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                Self::Write {
+                    offset: u32_at(4)?,
+                    bytes: bytes.to_vec(),
+                }
+            }
+            2 => Self::DeployWithMaxDataLen {
+                max_data_len: u64_at(4)?,
+            },
+            3 => Self::Upgrade,
+            4 => Self::SetAuthority,
+            5 => Self::Close,
+            6 => Self::ExtendProgram {
+                additional_bytes: u32_at(4)?,
+            },
+            7 => Self::SetAuthorityChecked,
+            8 => Self::Migrate,
+            9 => Self::ExtendProgramChecked {
+                additional_bytes: u32_at(4)?,
+            },
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use solana_sdk::program_error::ProgramError;
+
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+
+/// ComputeBudget程序的指令，数据为1字节tag加小端序的参数
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComputeBudgetInstruction {
+    /// 已废弃的RequestUnits
+    Unused,
+    /// 请求的堆大小(字节)
+    RequestHeapFrame(u32),
+    /// 交易的CU上限
+    SetComputeUnitLimit(u32),
+    /// CU价格(微lamports/CU)
+    SetComputeUnitPrice(u64),
+    /// 交易可加载的帐户数据大小上限(字节)
+    SetLoadedAccountsDataSizeLimit(u32),
+}
+
+impl ComputeBudgetInstruction {
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let u32_arg = || {
+            rest.get(..4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                .ok_or(ProgramError::InvalidInstructionData)
+        };
+        Ok(match tag {
+            0 => Self::Unused,
+            1 => Self::RequestHeapFrame(u32_arg()?),
+            2 => Self::SetComputeUnitLimit(u32_arg()?),
+            3 => Self::SetComputeUnitPrice(
+                rest.get(..8)
+                    .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                    .ok_or(ProgramError::InvalidInstructionData)?,
+            ),
+            4 => Self::SetLoadedAccountsDataSizeLimit(u32_arg()?),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+}
@@ -699,6 +699,31 @@ pub enum TokenInstruction {
     ScaledUiAmountExtension,
     /// Instruction prefix for instructions to the pausable extension
     PausableExtension,
+    /// `TransferFeeExtension`中的`TransferCheckedWithFee`子指令，解析时从扩展数据中还原
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The source account's multisignature owner/delegate.
+    ///   4. ..`4+M` `[signer]` M signer accounts.
+    TransferCheckedWithFee {
+        /// The amount of tokens to transfer.
+        amount: u64,
+        /// Expected number of base 10 digits to the right of the decimal place.
+        decimals: u8,
+        /// Expected fee assessed on this transfer, calculated off-chain based
+        /// on the `transfer_fee_basis_points` and `maximum_fee` of the mint.
+        fee: u64,
+    },
 }
 
 impl<'a> From<spl_token_2022::instruction::TokenInstruction<'a>> for TokenInstruction {
@@ -859,3 +884,100 @@ impl<'a> From<spl_token_2022::instruction::TokenInstruction<'a>> for TokenInstru
         }
     }
 }
+
+impl From<crate::instructions::spl_token::AuthorityType> for AuthorityType {
+    fn from(value: crate::instructions::spl_token::AuthorityType) -> Self {
+        match value {
+            crate::instructions::spl_token::AuthorityType::MintTokens => AuthorityType::MintTokens,
+            crate::instructions::spl_token::AuthorityType::FreezeAccount => {
+                AuthorityType::FreezeAccount
+            }
+            crate::instructions::spl_token::AuthorityType::AccountOwner => {
+                AuthorityType::AccountOwner
+            }
+            crate::instructions::spl_token::AuthorityType::CloseAccount => {
+                AuthorityType::CloseAccount
+            }
+        }
+    }
+}
+
+/// SplToken2022的指令集是SplToken的超集
+impl From<crate::instructions::spl_token::TokenInstruction> for TokenInstruction {
+    fn from(value: crate::instructions::spl_token::TokenInstruction) -> Self {
+        use crate::instructions::spl_token::TokenInstruction as SplTokenInstruction;
+        match value {
+            SplTokenInstruction::InitializeMint {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => TokenInstruction::InitializeMint {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            },
+            SplTokenInstruction::InitializeAccount => TokenInstruction::InitializeAccount,
+            SplTokenInstruction::InitializeMultisig { m } => {
+                TokenInstruction::InitializeMultisig { m }
+            }
+            SplTokenInstruction::Transfer { amount } => TokenInstruction::Transfer { amount },
+            SplTokenInstruction::Approve { amount } => TokenInstruction::Approve { amount },
+            SplTokenInstruction::Revoke => TokenInstruction::Revoke,
+            SplTokenInstruction::SetAuthority {
+                authority_type,
+                new_authority,
+            } => TokenInstruction::SetAuthority {
+                authority_type: authority_type.into(),
+                new_authority,
+            },
+            SplTokenInstruction::MintTo { amount } => TokenInstruction::MintTo { amount },
+            SplTokenInstruction::Burn { amount } => TokenInstruction::Burn { amount },
+            SplTokenInstruction::CloseAccount => TokenInstruction::CloseAccount,
+            SplTokenInstruction::FreezeAccount => TokenInstruction::FreezeAccount,
+            SplTokenInstruction::ThawAccount => TokenInstruction::ThawAccount,
+            SplTokenInstruction::TransferChecked { amount, decimals } => {
+                TokenInstruction::TransferChecked { amount, decimals }
+            }
+            SplTokenInstruction::ApproveChecked { amount, decimals } => {
+                TokenInstruction::ApproveChecked { amount, decimals }
+            }
+            SplTokenInstruction::MintToChecked { amount, decimals } => {
+                TokenInstruction::MintToChecked { amount, decimals }
+            }
+            SplTokenInstruction::BurnChecked { amount, decimals } => {
+                TokenInstruction::BurnChecked { amount, decimals }
+            }
+            SplTokenInstruction::InitializeAccount2 { owner } => {
+                TokenInstruction::InitializeAccount2 { owner }
+            }
+            SplTokenInstruction::SyncNative => TokenInstruction::SyncNative,
+            SplTokenInstruction::InitializeAccount3 { owner } => {
+                TokenInstruction::InitializeAccount3 { owner }
+            }
+            SplTokenInstruction::InitializeMultisig2 { m } => {
+                TokenInstruction::InitializeMultisig2 { m }
+            }
+            SplTokenInstruction::InitializeMint2 {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => TokenInstruction::InitializeMint2 {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            },
+            SplTokenInstruction::GetAccountDataSize => TokenInstruction::GetAccountDataSize {
+                extension_types: vec![],
+            },
+            SplTokenInstruction::InitializeImmutableOwner => {
+                TokenInstruction::InitializeImmutableOwner
+            }
+            SplTokenInstruction::AmountToUiAmount { amount } => {
+                TokenInstruction::AmountToUiAmount { amount }
+            }
+            SplTokenInstruction::UiAmountToAmount { ui_amount } => {
+                TokenInstruction::UiAmountToAmount { ui_amount }
+            }
+        }
+    }
+}
@@ -5,8 +5,15 @@ pub mod utils;
 pub mod instruction_parser;
 pub mod pubkeys;
 pub mod token_transfer_data;
+pub mod token_account_index;
 pub mod transaction;
 pub mod api;
+pub mod account_roles;
+pub mod asset_flow;
+pub mod program_log;
+pub mod events;
+#[cfg(feature = "yellowstone")]
+pub mod geyser;
 // fn parse_tip(
 //     transaction_accounts: &[Vec<u8>],
 //     instruction: &CompiledInstruction,
@@ -1,22 +1,51 @@
+pub mod diagnostics;
+pub mod instruction_iter;
+mod json_parsed;
+
+use crate::account_roles::system::{SystemAccountRoles, system_account_roles};
+use crate::account_roles::token::{
+    TokenAccountRoles, spl_token_2022_account_roles, spl_token_2022_account_roles_with_permissions,
+    spl_token_account_roles,
+};
+use crate::account_roles::{AccountRoleError, account_at};
+use crate::instructions::bpf_loader_upgradeable::UpgradeableLoaderInstruction;
+use crate::instructions::compute_budget::ComputeBudgetInstruction;
 use crate::instructions::spl_token::TokenInstruction as SplTokenInstruction;
 use crate::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use crate::parsed_instruction::diagnostics::{
+    InstructionDiagnostic, InstructionIssue, InstructionLocation, ParseTransactionError,
+    ParsedInstructionReport,
+};
+use crate::parsed_instruction::instruction_iter::InstructionIter;
 use crate::token_transfer_data::TokenTransferData;
+use crate::transaction::account_meta::AccountPermissions;
+use crate::transaction::address_lookup_table::AddressLookupTableResolver;
 use crate::utils::TransactionAccounts;
 use solana_pubkey::Pubkey;
+use solana_sdk::address_lookup_table::instruction::ProgramInstruction as AddressLookupTableInstruction;
+use solana_sdk::instruction::CompiledInstruction;
 use solana_sdk::program_error::ProgramError;
 use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::transaction::VersionedTransaction;
 use solana_transaction_status_client_types::EncodedTransaction::LegacyBinary;
 use solana_transaction_status_client_types::option_serializer::OptionSerializer;
 use solana_transaction_status_client_types::{
-    EncodedTransaction, EncodedTransactionWithStatusMeta, UiCompiledInstruction, UiInstruction,
-    UiMessage,
+    EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionStatusMeta,
+    UiCompiledInstruction, UiInstruction, UiMessage, UiParsedInstruction, UiParsedMessage,
+    UiPartiallyDecodedInstruction, UiRawMessage, UiTransactionStatusMeta,
 };
+use spl_token_2022::extension::transfer_fee::instruction::TransferFeeInstruction;
 use std::borrow::Cow;
+use std::iter::Peekable;
 use std::ops::Deref;
+use std::str::FromStr;
 use thiserror::Error;
 use tracing::error;
 #[cfg(feature = "serde-traits")]
-use serde::{Deserialize, Serialize};
+use {
+    serde::{Deserialize, Serialize},
+    serde_with::{As, DisplayFromStr},
+};
 
 // #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
 // #[cfg_attr(
@@ -35,6 +64,66 @@ pub enum ParseInstructionDataError {
     BincodeError(#[from] bincode::Error),
 }
 
+/// 指令数据解析错误的类型
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde-traits",
+    serde(rename_all_fields = "camelCase", rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum InstructionParseErrorKind {
+    /// 程序解析指令时返回的错误，code为[ProgramError]转换成的u64
+    #[error("program error {code}: {message}")]
+    ProgramError { code: u64, message: String },
+    #[error("base58 decode error: {message}")]
+    Base58 { message: String },
+    #[error("bincode error: {message}")]
+    Bincode { message: String },
+    /// 指令数据长度不足
+    #[error("truncated instruction data")]
+    TruncatedData,
+    #[error("invalid program id")]
+    InvalidProgramId,
+}
+
+impl From<&ParseInstructionDataError> for InstructionParseErrorKind {
+    fn from(value: &ParseInstructionDataError) -> Self {
+        match value {
+            ParseInstructionDataError::ProgramError(e) => InstructionParseErrorKind::ProgramError {
+                code: u64::from(e.clone()),
+                message: e.to_string(),
+            },
+            ParseInstructionDataError::ParseBase58Error(e) => InstructionParseErrorKind::Base58 {
+                message: e.to_string(),
+            },
+            ParseInstructionDataError::ParsePubkeyError(_) => {
+                InstructionParseErrorKind::InvalidProgramId
+            }
+            ParseInstructionDataError::BincodeError(e) => match e.as_ref() {
+                bincode::ErrorKind::Io(io) if io.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    InstructionParseErrorKind::TruncatedData
+                }
+                _ => InstructionParseErrorKind::Bincode {
+                    message: e.to_string(),
+                },
+            },
+        }
+    }
+}
+
+/// 可序列化的指令数据解析错误
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{kind} (program: {program_id}, data length: {data_len:?})")]
+pub struct InstructionParseError {
+    pub kind: InstructionParseErrorKind,
+    /// 指令所属程序，base58格式
+    pub program_id: String,
+    /// 原始指令数据的字节数，数据本身无法解码时为None
+    pub data_len: Option<usize>,
+}
+
 #[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
 #[cfg_attr(
     feature = "serde-traits",
@@ -45,19 +134,23 @@ pub enum ParsedInstructionData {
     System(SystemInstruction),
     SplToken(SplTokenInstruction),
     SplToken2022(SplToken2022Instruction),
-    Error(String),
+    ComputeBudget(ComputeBudgetInstruction),
+    AddressLookupTable(AddressLookupTableInstruction),
+    BpfLoaderUpgradeable(UpgradeableLoaderInstruction),
+    Error(InstructionParseError),
     Unknown,
 }
 
 impl ParsedInstructionData {}
 
+#[derive(Clone, Copy)]
 pub enum InstructionDataFormat<'a> {
     Binary(&'a [u8]),
     Base58(&'a str),
     // Base64(&'a str),
 }
 
-#[allow(unused)]
+#[derive(Clone, Copy)]
 pub enum InstructionProgramId<'a> {
     Pubkey(&'a Pubkey),
     Base58(&'a str),
@@ -97,6 +190,44 @@ impl ParsedInstructionData {
         Self::do_parse(&pubkey, &data)
     }
 
+    /// 解析指令数据，出错时返回[ParsedInstructionData::Error]
+    pub fn parse_or_error(program_id: InstructionProgramId, data: InstructionDataFormat) -> Self {
+        let error = |kind: InstructionParseErrorKind, data_len: Option<usize>| {
+            let program_id = match program_id {
+                InstructionProgramId::Pubkey(p) => p.to_string(),
+                InstructionProgramId::Base58(s) => s.to_string(),
+            };
+            Self::Error(InstructionParseError {
+                kind,
+                program_id,
+                data_len,
+            })
+        };
+        let data = match data {
+            InstructionDataFormat::Binary(b) => Cow::Borrowed(b),
+            InstructionDataFormat::Base58(b) => match bs58::decode(b).into_vec() {
+                Ok(b) => Cow::Owned(b),
+                Err(e) => {
+                    return error(
+                        InstructionParseErrorKind::from(&ParseInstructionDataError::from(e)),
+                        None,
+                    );
+                }
+            },
+        };
+        match Self::parse(program_id, InstructionDataFormat::Binary(&data)) {
+            Ok(parsed) => parsed,
+            // 空数据时程序只会返回InvalidInstructionData，这里归为数据长度不足
+            Err(_) if data.is_empty() => error(InstructionParseErrorKind::TruncatedData, Some(0)),
+            // 数据已经是二进制，base58错误只可能来自程序id
+            Err(ParseInstructionDataError::ParseBase58Error(_)) => error(
+                InstructionParseErrorKind::InvalidProgramId,
+                Some(data.len()),
+            ),
+            Err(e) => error(InstructionParseErrorKind::from(&e), Some(data.len())),
+        }
+    }
+
     fn do_parse(program: &Pubkey, data: &[u8]) -> Result<Self, ParseInstructionDataError> {
         if program == &solana_sdk::system_program::id() {
             // solana_sdk::system_instruction::SystemInstruction::deserialize()
@@ -113,8 +244,46 @@ impl ParsedInstructionData {
 
         if program == &spl_token_2022::id() {
             let instruction = spl_token_2022::instruction::TokenInstruction::unpack(data)?;
+            // 扩展指令只解析出前缀，TransferCheckedWithFee会产生资产流动，需要还原子指令
+            let extension = match instruction {
+                spl_token_2022::instruction::TokenInstruction::TransferFeeExtension => {
+                    TransferFeeInstruction::unpack(&data[1..]).ok()
+                }
+                _ => None,
+            };
+            if let Some(TransferFeeInstruction::TransferCheckedWithFee {
+                amount,
+                decimals,
+                fee,
+            }) = extension
+            {
+                return Ok(ParsedInstructionData::SplToken2022(
+                    SplToken2022Instruction::TransferCheckedWithFee {
+                        amount,
+                        decimals,
+                        fee,
+                    },
+                ));
+            }
             return Ok(ParsedInstructionData::SplToken2022(
-                crate::instructions::spl_token_2022::TokenInstruction::from(instruction),
+                SplToken2022Instruction::from(instruction),
+            ));
+        }
+
+        if program == &solana_sdk::compute_budget::id() {
+            return Ok(ParsedInstructionData::ComputeBudget(
+                ComputeBudgetInstruction::unpack(data)?,
+            ));
+        }
+
+        if program == &solana_sdk::address_lookup_table::program::id() {
+            let instruction = bincode::deserialize::<AddressLookupTableInstruction>(data)?;
+            return Ok(ParsedInstructionData::AddressLookupTable(instruction));
+        }
+
+        if program == &solana_sdk::bpf_loader_upgradeable::id() {
+            return Ok(ParsedInstructionData::BpfLoaderUpgradeable(
+                UpgradeableLoaderInstruction::unpack(data)?,
             ));
         }
 
@@ -127,67 +296,159 @@ impl ParsedInstructionData {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParsedInstruction {
     pub program_id_index: u8,
+    /// 指令所属程序，[ParseOptions::keep_program_id]关闭时为None
+    #[cfg_attr(
+        feature = "serde-traits",
+        serde(with = "As::<Option<DisplayFromStr>>", default)
+    )]
+    pub program_id: Option<Pubkey>,
+    /// 指令帐户在交易帐户列表中的索引，jsonParsed中无法还原的指令为`info`中出现的帐户，顺序与原始指令无关
     pub accounts: Vec<u8>,
     pub instruction_data: ParsedInstructionData,
+    /// 原始指令数据，[ParseOptions::keep_raw_data]关闭或数据无法获取(如jsonParsed格式)时为None
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub raw_data: Option<Vec<u8>>,
     pub inner_instructions: Option<Vec<ParsedInstruction>>,
 }
 
+/// 解析选项
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    /// 是否在[ParsedInstruction::raw_data]中保留原始指令数据
+    pub keep_raw_data: bool,
+    /// 是否在[ParsedInstruction::program_id]中保留程序id
+    pub keep_program_id: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            keep_raw_data: true,
+            keep_program_id: true,
+        }
+    }
+}
+
 impl ParsedInstruction {
-    pub fn get_token_transfer_data(&self) -> Option<TokenTransferData> {
+    /// 无法解析的指令的占位，只保留指令的位置
+    ///
+    /// 程序无法确定时`program_id_index`为[u8::MAX]，具体原因见解析诊断信息
+    pub fn placeholder(program_id_index: u8) -> Self {
+        Self {
+            program_id_index,
+            program_id: None,
+            accounts: Vec::new(),
+            instruction_data: ParsedInstructionData::Unknown,
+            raw_data: None,
+            inner_instructions: None,
+        }
+    }
+
+    /// 获取指令中第`position`个帐户在交易帐户列表中的索引
+    pub fn account(&self, position: usize) -> Result<u8, AccountRoleError> {
+        account_at(&self.accounts, position)
+    }
+
+    /// 获取System程序指令的帐户角色，不是System指令时返回Ok(None)
+    pub fn system_account_roles<'a, AccountType>(
+        &self,
+        transaction_accounts: &TransactionAccounts<'a, AccountType>,
+    ) -> Result<Option<SystemAccountRoles<'a, AccountType>>, AccountRoleError> {
+        match &self.instruction_data {
+            ParsedInstructionData::System(s) => {
+                system_account_roles(s, &self.accounts, transaction_accounts).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 获取SPL Token/Token-2022指令的帐户角色，不是token指令时返回Ok(None)
+    pub fn token_account_roles<'a, AccountType>(
+        &self,
+        transaction_accounts: &TransactionAccounts<'a, AccountType>,
+    ) -> Result<Option<TokenAccountRoles<'a, AccountType>>, AccountRoleError> {
+        match &self.instruction_data {
+            ParsedInstructionData::SplToken(t) => {
+                spl_token_account_roles(t, &self.accounts, transaction_accounts)
+            }
+            ParsedInstructionData::SplToken2022(t) => {
+                spl_token_2022_account_roles(t, &self.accounts, transaction_accounts)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 获取SPL Token/Token-2022指令的帐户角色，使用帐户权限区分Token-2022转帐的多签签名者和
+    /// transfer hook需要的额外帐户
+    pub fn token_account_roles_with_permissions<'a, AccountType>(
+        &self,
+        transaction_accounts: &TransactionAccounts<'a, AccountType>,
+        permissions: &AccountPermissions,
+    ) -> Result<Option<TokenAccountRoles<'a, AccountType>>, AccountRoleError> {
         match &self.instruction_data {
+            ParsedInstructionData::SplToken2022(t) => {
+                spl_token_2022_account_roles_with_permissions(
+                    t,
+                    &self.accounts,
+                    transaction_accounts,
+                    permissions,
+                )
+            }
+            _ => self.token_account_roles(transaction_accounts),
+        }
+    }
+
+    /// 获取Transfer/TransferChecked指令的转帐数据，帐户数量不足时返回None
+    pub fn get_token_transfer_data(&self) -> Option<TokenTransferData> {
+        self.try_get_token_transfer_data().ok().flatten()
+    }
+
+    /// 获取Transfer/TransferChecked指令的转帐数据
+    ///
+    /// 不是转帐指令时返回Ok(None), 帐户数量不足时返回错误
+    pub fn try_get_token_transfer_data(
+        &self,
+    ) -> Result<Option<TokenTransferData>, AccountRoleError> {
+        let (amount, decimals) = match &self.instruction_data {
             ParsedInstructionData::SplToken(t) => match t {
-                SplTokenInstruction::Transfer { amount } => {
-                    let data = TokenTransferData {
-                        source: self.accounts[0],
-                        destination: self.accounts[1],
-                        signer: self.accounts[2],
-                        amount: *amount,
-                        mint: None,
-                        decimal: None,
-                    };
-                    Some(data)
-                }
+                SplTokenInstruction::Transfer { amount } => (*amount, None),
                 SplTokenInstruction::TransferChecked { amount, decimals } => {
-                    let data = TokenTransferData {
-                        source: self.accounts[0],
-                        mint: Some(self.accounts[1]),
-                        destination: self.accounts[2],
-                        signer: self.accounts[3],
-                        amount: *amount,
-                        decimal: Some(*decimals),
-                    };
-                    Some(data)
+                    (*amount, Some(*decimals))
                 }
-                _ => return None,
+                _ => return Ok(None),
             },
             ParsedInstructionData::SplToken2022(t) => match t {
                 #[allow(deprecated)]
-                SplToken2022Instruction::Transfer { amount } => {
-                    let data = TokenTransferData {
-                        source: self.accounts[0],
-                        destination: self.accounts[1],
-                        signer: self.accounts[2],
-                        amount: *amount,
-                        mint: None,
-                        decimal: None,
-                    };
-                    Some(data)
-                }
+                SplToken2022Instruction::Transfer { amount } => (*amount, None),
                 SplToken2022Instruction::TransferChecked { amount, decimals } => {
-                    let data = TokenTransferData {
-                        source: self.accounts[0],
-                        mint: Some(self.accounts[1]),
-                        destination: self.accounts[2],
-                        signer: self.accounts[3],
-                        amount: *amount,
-                        decimal: Some(*decimals),
-                    };
-                    Some(data)
+                    (*amount, Some(*decimals))
                 }
-                _ => None,
+                _ => return Ok(None),
             },
-            _ => None,
-        }
+            _ => return Ok(None),
+        };
+
+        let data = if decimals.is_some() {
+            TokenTransferData {
+                source: self.account(0)?,
+                mint: Some(self.account(1)?),
+                destination: self.account(2)?,
+                signer: self.account(3)?,
+                amount,
+                decimal: decimals,
+            }
+        } else {
+            TokenTransferData {
+                source: self.account(0)?,
+                destination: self.account(1)?,
+                signer: self.account(2)?,
+                amount,
+                mint: None,
+                decimal: None,
+            }
+        };
+
+        Ok(Some(data))
     }
 }
 
@@ -200,6 +461,11 @@ impl ParsedInstructionList {
     pub fn as_slice(&self) -> &[ParsedInstruction] {
         self.0.as_slice()
     }
+
+    /// 深度优先遍历所有指令(包括内部指令)，同时返回指令的路径及深度
+    pub fn iter_with_path(&self) -> InstructionIter<'_> {
+        InstructionIter::new(self.as_slice())
+    }
 }
 
 impl Deref for ParsedInstructionList {
@@ -212,123 +478,596 @@ impl Deref for ParsedInstructionList {
 
 impl From<&EncodedTransactionWithStatusMeta> for ParsedInstructionList {
     fn from(value: &EncodedTransactionWithStatusMeta) -> Self {
-        let message = match value.transaction {
+        match Self::try_from_encoded(value) {
+            Ok(report) => {
+                for diagnostic in &report.diagnostics {
+                    error!("{diagnostic}");
+                }
+                report.instructions
+            }
+            Err(e) => {
+                error!("{e}");
+                ParsedInstructionList(Vec::new())
+            }
+        }
+    }
+}
+
+impl ParsedInstructionList {
+    /// 解析交易中的指令
+    ///
+    /// 交易本身无法解析时返回错误；单条指令的问题不会导致整个解析失败，而是记录在返回的诊断信息中
+    pub fn try_from_encoded(
+        value: &EncodedTransactionWithStatusMeta,
+    ) -> Result<ParsedInstructionReport, ParseTransactionError> {
+        Self::try_from_encoded_with_options(value, &ParseOptions::default())
+    }
+
+    pub fn try_from_encoded_with_options(
+        value: &EncodedTransactionWithStatusMeta,
+        options: &ParseOptions,
+    ) -> Result<ParsedInstructionReport, ParseTransactionError> {
+        let meta = value
+            .meta
+            .as_ref()
+            .ok_or(ParseTransactionError::MissingMeta)?;
+        let mut diagnostics = Vec::new();
+        let instructions = match value.transaction {
             EncodedTransaction::Json(ref t) => match &t.message {
-                UiMessage::Parsed(_) => {
-                    error!("错误的Message类型: Parsed");
-                    return ParsedInstructionList(Vec::new());
+                UiMessage::Parsed(parsed) => {
+                    Self::from_parsed_message(parsed, meta, options, &mut diagnostics)
                 }
-                UiMessage::Raw(raw) => raw,
+                UiMessage::Raw(raw) => Self::from_raw_message(raw, meta, options, &mut diagnostics),
             },
-            LegacyBinary(_) => {
-                error!("错误的交易类型: LegacyBinary");
-                return ParsedInstructionList(Vec::new());
-            }
-            EncodedTransaction::Binary(_, _) => {
-                error!("错误的交易类型: Binary");
-                return ParsedInstructionList(Vec::new());
+            LegacyBinary(_) | EncodedTransaction::Binary(_, _) => {
+                // base58/base64编码的交易，先解码成VersionedTransaction
+                let transaction = value
+                    .transaction
+                    .decode()
+                    .ok_or(ParseTransactionError::DecodeTransaction)?;
+                Self::from_versioned_transaction(&transaction, meta, options, &mut diagnostics)
             }
             EncodedTransaction::Accounts(_) => {
-                error!("错误的交易类型: Accounts");
-                return ParsedInstructionList(Vec::new());
+                // Accounts格式只有签名和帐户列表，不包含指令
+                return Err(ParseTransactionError::UnsupportedEncoding("accounts"));
             }
         };
-        let meta = if let Some(meta) = &value.meta {
-            meta
-        } else {
-            error!("交易缺少meta数据");
-            return ParsedInstructionList(Vec::new());
-        };
-        // let (writable, readonly): (Vec<_>, Vec<_>) =
-        let mut writable = None;
-        let mut readonly = None;
-        let loaded = meta.loaded_addresses.as_ref();
-        let account_keys = message.account_keys.as_slice();
-        match loaded {
-            OptionSerializer::Some(r) => {
-                writable = Some(r.writable.as_slice());
-                readonly = Some(r.readonly.as_slice());
-            }
-            _ => {}
+
+        Ok(ParsedInstructionReport {
+            instructions,
+            diagnostics,
+        })
+    }
+
+    fn from_raw_message(
+        message: &UiRawMessage,
+        meta: &UiTransactionStatusMeta,
+        options: &ParseOptions,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) -> Self {
+        let (writable, readonly) = Self::loaded_addresses(meta);
+        let transaction_accounts = TransactionAccounts::from_accounts(
+            Some(message.account_keys.as_slice()),
+            writable,
+            readonly,
+        );
+        let raw_instructions = &message.instructions;
+
+        let mut instructions = Vec::with_capacity(raw_instructions.len());
+        for (index, raw) in raw_instructions.iter().enumerate() {
+            instructions.push(Self::collect(
+                Self::parse_ui_compiled_instruction(&transaction_accounts, raw, options),
+                InstructionLocation::TopLevel { index },
+                diagnostics,
+            ));
         }
 
+        Self::attach_inner_instructions(
+            &mut instructions,
+            &transaction_accounts,
+            meta,
+            options,
+            diagnostics,
+        );
+        ParsedInstructionList(instructions)
+    }
+
+    fn from_parsed_message(
+        message: &UiParsedMessage,
+        meta: &UiTransactionStatusMeta,
+        options: &ParseOptions,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) -> Self {
+        // jsonParsed格式的帐户列表已经包含了通过地址查找表加载的帐户
+        let account_keys = message
+            .account_keys
+            .iter()
+            .map(|a| a.pubkey.clone())
+            .collect::<Vec<_>>();
         let transaction_accounts =
-            TransactionAccounts::from_accounts(Some(account_keys), writable, readonly);
+            TransactionAccounts::from_accounts(Some(account_keys.as_slice()), None, None);
         let raw_instructions = &message.instructions;
 
         let mut instructions = Vec::with_capacity(raw_instructions.len());
-        for raw in raw_instructions {
-            if let Some(parsed) = Self::parse_ui_compiled_instruction(&transaction_accounts, raw) {
-                instructions.push(parsed);
-            }
+        for (index, raw) in raw_instructions.iter().enumerate() {
+            instructions.push(Self::collect(
+                Self::parse_ui_instruction(&transaction_accounts, raw, options),
+                InstructionLocation::TopLevel { index },
+                diagnostics,
+            ));
+        }
+
+        Self::attach_inner_instructions(
+            &mut instructions,
+            &transaction_accounts,
+            meta,
+            options,
+            diagnostics,
+        );
+        ParsedInstructionList(instructions)
+    }
+
+    fn from_versioned_transaction(
+        transaction: &VersionedTransaction,
+        meta: &UiTransactionStatusMeta,
+        options: &ParseOptions,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) -> Self {
+        // 与Json格式保持一致，帐户统一使用base58字符串
+        let account_keys = transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<_>>();
+        let (writable, readonly) = Self::loaded_addresses(meta);
+        let transaction_accounts =
+            TransactionAccounts::from_accounts(Some(account_keys.as_slice()), writable, readonly);
+        let mut instructions = Self::parse_versioned_instructions(
+            transaction,
+            &transaction_accounts,
+            options,
+            diagnostics,
+        );
+
+        Self::attach_inner_instructions(
+            &mut instructions,
+            &transaction_accounts,
+            meta,
+            options,
+            diagnostics,
+        );
+        ParsedInstructionList(instructions)
+    }
+
+    /// 解析没有meta数据的交易，如从交易池或网络直接收到的v0交易
+    ///
+    /// 通过地址查找表加载的帐户由`resolver`还原，交易未执行，所以只有顶层指令。
+    /// 完整的帐户列表同样可以用[AddressLookupTableResolver::resolve_message]还原
+    pub fn try_from_transaction_with_resolver(
+        transaction: &VersionedTransaction,
+        resolver: &dyn AddressLookupTableResolver,
+        options: &ParseOptions,
+    ) -> Result<ParsedInstructionReport, ParseTransactionError> {
+        let loaded = resolver.resolve_message(&transaction.message)?;
+        let account_keys = transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<_>>();
+        let mut diagnostics = Vec::new();
+        let instructions = Self::parse_versioned_instructions(
+            transaction,
+            &loaded.transaction_accounts(&account_keys),
+            options,
+            &mut diagnostics,
+        );
+
+        Ok(ParsedInstructionReport {
+            instructions: ParsedInstructionList(instructions),
+            diagnostics,
+        })
+    }
+
+    /// 解析交易的顶层指令
+    fn parse_versioned_instructions(
+        transaction: &VersionedTransaction,
+        transaction_accounts: &TransactionAccounts<String>,
+        options: &ParseOptions,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) -> Vec<ParsedInstruction> {
+        let raw_instructions = transaction.message.instructions();
+        let mut instructions = Vec::with_capacity(raw_instructions.len());
+        for (index, raw) in raw_instructions.iter().enumerate() {
+            instructions.push(Self::collect(
+                Self::parse_instruction(
+                    Self::program_id(transaction_accounts, raw.program_id_index),
+                    raw.program_id_index,
+                    &raw.accounts,
+                    InstructionDataFormat::Binary(&raw.data),
+                    options,
+                ),
+                InstructionLocation::TopLevel { index },
+                diagnostics,
+            ));
+        }
+
+        instructions
+    }
+
+    /// 直接从原生的交易及meta数据解析指令，如Geyser插件中拿到的数据
+    ///
+    /// 不需要先转换成UI/base58格式
+    pub fn from_native(transaction: &VersionedTransaction, meta: &TransactionStatusMeta) -> Self {
+        Self::from_native_with_options(transaction, meta, &ParseOptions::default())
+    }
+
+    pub fn from_native_with_options(
+        transaction: &VersionedTransaction,
+        meta: &TransactionStatusMeta,
+        options: &ParseOptions,
+    ) -> Self {
+        let mut diagnostics = Vec::new();
+        let transaction_accounts = TransactionAccounts::from_accounts(
+            Some(transaction.message.static_account_keys()),
+            Some(meta.loaded_addresses.writable.as_slice()),
+            Some(meta.loaded_addresses.readonly.as_slice()),
+        );
+        let raw_instructions = transaction.message.instructions();
+
+        let mut instructions = Vec::with_capacity(raw_instructions.len());
+        for (index, raw) in raw_instructions.iter().enumerate() {
+            instructions.push(Self::collect(
+                Self::parse_native_compiled_instruction(&transaction_accounts, raw, options),
+                InstructionLocation::TopLevel { index },
+                &mut diagnostics,
+            ));
         }
 
         // 处理子指令
+        if let Some(inner) = &meta.inner_instructions {
+            for inner_instructions in inner {
+                Self::attach_inner_group(
+                    &mut instructions,
+                    inner_instructions.index as usize,
+                    inner_instructions
+                        .instructions
+                        .iter()
+                        .map(|inner_instruction| {
+                            (
+                                inner_instruction.stack_height,
+                                Self::parse_native_compiled_instruction(
+                                    &transaction_accounts,
+                                    &inner_instruction.instruction,
+                                    options,
+                                ),
+                            )
+                        }),
+                    &mut diagnostics,
+                );
+            }
+        }
+
+        for diagnostic in &diagnostics {
+            error!("{diagnostic}");
+        }
+        ParsedInstructionList(instructions)
+    }
+
+    fn parse_native_compiled_instruction(
+        transaction_accounts: &TransactionAccounts<Pubkey>,
+        compiled: &CompiledInstruction,
+        options: &ParseOptions,
+    ) -> Result<ParsedInstruction, InstructionIssue> {
+        Self::parse_instruction(
+            transaction_accounts
+                .get(compiled.program_id_index as usize)
+                .map(InstructionProgramId::Pubkey),
+            compiled.program_id_index,
+            &compiled.accounts,
+            InstructionDataFormat::Binary(&compiled.data),
+            options,
+        )
+    }
+
+    /// 获取v0交易通过地址查找表加载的帐户(可写, 只读)
+    fn loaded_addresses(meta: &UiTransactionStatusMeta) -> (Option<&[String]>, Option<&[String]>) {
+        match meta.loaded_addresses.as_ref() {
+            OptionSerializer::Some(r) => (Some(r.writable.as_slice()), Some(r.readonly.as_slice())),
+            _ => (None, None),
+        }
+    }
+
+    /// 记录指令的解析问题
+    ///
+    /// 无法解析的指令用[ParsedInstruction::placeholder]占位，保证指令在列表中的位置与原始交易一致
+    fn collect(
+        result: Result<ParsedInstruction, InstructionIssue>,
+        location: InstructionLocation,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) -> ParsedInstruction {
+        match result {
+            Ok(instruction) => {
+                if let ParsedInstructionData::Error(e) = &instruction.instruction_data {
+                    diagnostics.push(InstructionDiagnostic {
+                        location,
+                        issue: InstructionIssue::InvalidData(e.clone()),
+                    });
+                }
+                instruction
+            }
+            Err(issue) => {
+                let program_id_index = match &issue {
+                    InstructionIssue::MissingProgram(index) => *index,
+                    _ => u8::MAX,
+                };
+                diagnostics.push(InstructionDiagnostic { location, issue });
+                ParsedInstruction::placeholder(program_id_index)
+            }
+        }
+    }
+
+    /// 处理子指令, 根据stack_height重建调用树, 挂到所属的顶层指令下
+    fn attach_inner_instructions(
+        instructions: &mut [ParsedInstruction],
+        transaction_accounts: &TransactionAccounts<String>,
+        meta: &UiTransactionStatusMeta,
+        options: &ParseOptions,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) {
         if let OptionSerializer::Some(inner) = &meta.inner_instructions {
             // 内部指令组
             for inner_instructions in inner {
-                if let Some(parent) = instructions.get_mut(inner_instructions.index as usize) {
-                    // 单个内部指令
-                    for inner_instruction in &inner_instructions.instructions {
-                        match inner_instruction {
-                            UiInstruction::Compiled(inner_instruction) => {
-                                if let Some(parsed) = Self::parse_ui_compiled_instruction(
-                                    &transaction_accounts,
+                Self::attach_inner_group(
+                    instructions,
+                    inner_instructions.index as usize,
+                    inner_instructions
+                        .instructions
+                        .iter()
+                        .map(|inner_instruction| {
+                            (
+                                Self::ui_instruction_stack_height(inner_instruction),
+                                Self::parse_ui_instruction(
+                                    transaction_accounts,
                                     inner_instruction,
-                                ) {
-                                    if let Some(old) = parent.inner_instructions.as_mut() {
-                                        old.push(parsed);
-                                    } else {
-                                        let mut inner_instructions = Vec::with_capacity(
-                                            inner_instructions.instructions.len(),
-                                        );
-                                        inner_instructions.push(parsed);
-                                        parent.inner_instructions = Some(inner_instructions);
-                                    }
-                                }
-                            }
-                            UiInstruction::Parsed(_) => {
-                                error!("意外的内部指令格式：Parsed");
+                                    options,
+                                ),
+                            )
+                        }),
+                    diagnostics,
+                );
+            }
+        }
+    }
+
+    /// 收集一组内部指令(stack_height, 解析结果)，按执行顺序重建调用树后挂到索引为`index`的顶层指令下
+    ///
+    /// UI格式与原生格式共用，保证两者的处理一致
+    fn attach_inner_group(
+        instructions: &mut [ParsedInstruction],
+        index: usize,
+        inner_instructions: impl Iterator<
+            Item = (Option<u32>, Result<ParsedInstruction, InstructionIssue>),
+        >,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) {
+        let parsed_inner_instructions = inner_instructions
+            .enumerate()
+            .map(|(position, (stack_height, result))| {
+                let location = InstructionLocation::Inner { index, position };
+                (stack_height, Self::collect(result, location, diagnostics))
+            })
+            .collect();
+        Self::attach_instruction_tree(instructions, index, parsed_inner_instructions, diagnostics);
+    }
+
+    fn ui_instruction_stack_height(instruction: &UiInstruction) -> Option<u32> {
+        match instruction {
+            UiInstruction::Compiled(compiled) => compiled.stack_height,
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => parsed.stack_height,
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(partially_decoded)) => {
+                partially_decoded.stack_height
+            }
+        }
+    }
+
+    /// 将按执行顺序排列的内部指令(stack_height, 指令)重建成调用树，挂到索引为`index`的顶层指令下
+    ///
+    /// 顶层指令的stack_height为1, 其直接调用的为2, 依此类推。
+    /// 较早的交易没有记录stack_height, 此时内部指令全部直接挂在顶层指令下
+    fn attach_instruction_tree(
+        instructions: &mut [ParsedInstruction],
+        index: usize,
+        inner_instructions: Vec<(Option<u32>, ParsedInstruction)>,
+        diagnostics: &mut Vec<InstructionDiagnostic>,
+    ) {
+        let parent = if let Some(parent) = instructions.get_mut(index) {
+            parent
+        } else {
+            diagnostics.push(InstructionDiagnostic {
+                location: InstructionLocation::TopLevel { index },
+                issue: InstructionIssue::MissingParent,
+            });
+            return;
+        };
+        let mut items = inner_instructions
+            .into_iter()
+            .map(|(stack_height, instruction)| (stack_height.unwrap_or(2), instruction))
+            .peekable();
+        let mut children = Self::build_instruction_tree(&mut items, 2);
+        // stack_height小于2的内部指令(数据异常)，直接挂在顶层指令下
+        children.extend(Self::build_instruction_tree(&mut items, 0));
+        if !children.is_empty() {
+            parent
+                .inner_instructions
+                .get_or_insert_with(Vec::new)
+                .append(&mut children);
+        }
+    }
+
+    fn build_instruction_tree(
+        items: &mut Peekable<impl Iterator<Item = (u32, ParsedInstruction)>>,
+        stack_height: u32,
+    ) -> Vec<ParsedInstruction> {
+        let mut ret = Vec::new();
+        while let Some((height, mut instruction)) = items.next_if(|(h, _)| *h >= stack_height) {
+            let children = Self::build_instruction_tree(items, height + 1);
+            if !children.is_empty() {
+                instruction.inner_instructions = Some(children);
+            }
+            ret.push(instruction);
+        }
+
+        ret
+    }
+
+    fn parse_ui_instruction(
+        transaction_accounts: &TransactionAccounts<String>,
+        instruction: &UiInstruction,
+        options: &ParseOptions,
+    ) -> Result<ParsedInstruction, InstructionIssue> {
+        match instruction {
+            UiInstruction::Compiled(compiled) => {
+                Self::parse_ui_compiled_instruction(transaction_accounts, compiled, options)
+            }
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(partially_decoded)) => {
+                Self::parse_partially_decoded_instruction(
+                    transaction_accounts,
+                    partially_decoded,
+                    options,
+                )
+            }
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => {
+                let program_id_index =
+                    Self::account_index(transaction_accounts, &parsed.program_id)?;
+                let program_id = Pubkey::from_str(&parsed.program_id)
+                    .map_err(|_| InstructionIssue::InvalidProgramId(parsed.program_id.clone()))?;
+                // 只有System/SplToken/SplToken2022/AddressLookupTable/BpfLoaderUpgradeable的指令能还原，其它程序没有原始数据
+                let (instruction_data, accounts) =
+                    match json_parsed::parse(&program_id, &parsed.parsed) {
+                        Some((instruction_data, keys)) => {
+                            let mut accounts = Vec::with_capacity(keys.len());
+                            for key in &keys {
+                                accounts.push(Self::account_index(transaction_accounts, key)?);
                             }
+                            (instruction_data, accounts)
                         }
-                    }
-                }
+                        // 无法还原的指令保留`info`中出现的帐户，顺序与原始指令无关
+                        None => (
+                            ParsedInstructionData::Unknown,
+                            json_parsed::info_accounts(&parsed.parsed)
+                                .iter()
+                                .filter_map(|key| transaction_accounts.position(key.as_str()))
+                                .map(|index| index as u8)
+                                .collect(),
+                        ),
+                    };
+                Ok(ParsedInstruction {
+                    program_id_index,
+                    program_id: options.keep_program_id.then_some(program_id),
+                    accounts,
+                    instruction_data,
+                    raw_data: None,
+                    inner_instructions: None,
+                })
             }
         }
+    }
 
-        ParsedInstructionList(instructions)
+    fn parse_partially_decoded_instruction(
+        transaction_accounts: &TransactionAccounts<String>,
+        partially_decoded: &UiPartiallyDecodedInstruction,
+        options: &ParseOptions,
+    ) -> Result<ParsedInstruction, InstructionIssue> {
+        let program_id_index =
+            Self::account_index(transaction_accounts, &partially_decoded.program_id)?;
+        let mut accounts = Vec::with_capacity(partially_decoded.accounts.len());
+        for account in &partially_decoded.accounts {
+            accounts.push(Self::account_index(transaction_accounts, account)?);
+        }
+        Self::parse_instruction(
+            Self::program_id(transaction_accounts, program_id_index),
+            program_id_index,
+            &accounts,
+            InstructionDataFormat::Base58(&partially_decoded.data),
+            options,
+        )
+    }
+
+    /// 将帐户地址转换为其在交易帐户列表中的索引
+    fn account_index(
+        transaction_accounts: &TransactionAccounts<String>,
+        account: &str,
+    ) -> Result<u8, InstructionIssue> {
+        transaction_accounts
+            .position(account)
+            .map(|index| index as u8)
+            .ok_or_else(|| InstructionIssue::UnknownAccount(account.to_string()))
     }
-}
 
-impl ParsedInstructionList {
     fn parse_ui_compiled_instruction(
         transaction_accounts: &TransactionAccounts<String>,
         compiled: &UiCompiledInstruction,
-    ) -> Option<ParsedInstruction> {
-        if let Some(program) = transaction_accounts.get(compiled.program_id_index as usize) {
-            match ParsedInstructionData::parse(
-                InstructionProgramId::Base58(program.as_str()),
-                InstructionDataFormat::Base58(&compiled.data),
-            ) {
-                Ok(parsed) => Some(ParsedInstruction {
-                    program_id_index: compiled.program_id_index,
-                    accounts: compiled.accounts.clone(),
-                    instruction_data: parsed,
-                    inner_instructions: None,
-                }),
-                Err(e) => {
-                    error!("解析指令数据出错: {e:?}");
-                    Some(ParsedInstruction {
-                        program_id_index: compiled.program_id_index,
-                        accounts: compiled.accounts.clone(),
-                        instruction_data: ParsedInstructionData::Error(format!("{}", e)),
-                        inner_instructions: None,
-                    })
-                }
+        options: &ParseOptions,
+    ) -> Result<ParsedInstruction, InstructionIssue> {
+        Self::parse_instruction(
+            Self::program_id(transaction_accounts, compiled.program_id_index),
+            compiled.program_id_index,
+            &compiled.accounts,
+            InstructionDataFormat::Base58(&compiled.data),
+            options,
+        )
+    }
+
+    /// 获取指令所属程序的id
+    fn program_id<'a>(
+        transaction_accounts: &TransactionAccounts<'a, String>,
+        program_id_index: u8,
+    ) -> Option<InstructionProgramId<'a>> {
+        transaction_accounts
+            .get(program_id_index as usize)
+            .map(|p| InstructionProgramId::Base58(p.as_str()))
+    }
+
+    /// 解析单条指令，指令数据无法解析时，保留指令，数据为[ParsedInstructionData::Error]
+    fn parse_instruction(
+        program_id: Option<InstructionProgramId>,
+        program_id_index: u8,
+        accounts: &[u8],
+        data: InstructionDataFormat,
+        options: &ParseOptions,
+    ) -> Result<ParsedInstruction, InstructionIssue> {
+        let program_id = program_id.ok_or(InstructionIssue::MissingProgram(program_id_index))?;
+        let raw_data = match data {
+            InstructionDataFormat::Binary(b) => Some(Cow::Borrowed(b)),
+            InstructionDataFormat::Base58(b) => bs58::decode(b).into_vec().ok().map(Cow::Owned),
+        };
+        let instruction_data = match &raw_data {
+            Some(raw_data) => ParsedInstructionData::parse_or_error(
+                program_id,
+                InstructionDataFormat::Binary(raw_data),
+            ),
+            // 数据无法解码，由parse_or_error生成对应的错误
+            None => ParsedInstructionData::parse_or_error(program_id, data),
+        };
+        let program_id = if options.keep_program_id {
+            match program_id {
+                InstructionProgramId::Pubkey(p) => Some(*p),
+                InstructionProgramId::Base58(s) => Pubkey::from_str(s).ok(),
             }
         } else {
-            error!("无法获取指令所属程序");
             None
-        }
+        };
+        Ok(ParsedInstruction {
+            program_id_index,
+            program_id,
+            accounts: accounts.to_vec(),
+            instruction_data,
+            raw_data: raw_data
+                .filter(|_| options.keep_raw_data)
+                .map(Cow::into_owned),
+            inner_instructions: None,
+        })
     }
 }
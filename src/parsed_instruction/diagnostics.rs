@@ -0,0 +1,78 @@
+use crate::parsed_instruction::{InstructionParseError, ParsedInstructionList};
+use crate::transaction::address_lookup_table::LookupTableError;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// 整个交易无法解析时的错误
+#[derive(Debug, Error)]
+pub enum ParseTransactionError {
+    #[error("交易缺少meta数据")]
+    MissingMeta,
+    #[error("不支持的交易编码: {0}")]
+    UnsupportedEncoding(&'static str),
+    #[error("解码交易出错")]
+    DecodeTransaction,
+    #[error("还原地址查找表帐户出错: {0}")]
+    LookupTable(#[from] LookupTableError),
+}
+
+/// 指令在交易中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionLocation {
+    /// 顶层指令
+    TopLevel { index: usize },
+    /// 内部指令，`index`为所属顶层指令的索引，`position`为其在内部指令组中的位置(按执行顺序)
+    Inner { index: usize, position: usize },
+}
+
+impl Display for InstructionLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstructionLocation::TopLevel { index } => write!(f, "指令#{index}"),
+            InstructionLocation::Inner { index, position } => {
+                write!(f, "指令#{index}的内部指令#{position}")
+            }
+        }
+    }
+}
+
+/// 单条指令解析时遇到的问题
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum InstructionIssue {
+    /// 程序索引不在交易帐户列表中，指令以[crate::parsed_instruction::ParsedInstruction::placeholder]占位
+    #[error("无法获取指令所属程序, program_id_index: {0}")]
+    MissingProgram(u8),
+    /// jsonParsed格式中的帐户不在交易帐户列表中，指令以占位指令保留
+    #[error("帐户不在交易帐户列表中: {0}")]
+    UnknownAccount(String),
+    /// jsonParsed格式中的程序id无法解析，指令以占位指令保留
+    #[error("解析程序id出错: {0}")]
+    InvalidProgramId(String),
+    /// 指令数据解析出错，指令保留，数据为[crate::parsed_instruction::ParsedInstructionData::Error]
+    #[error("解析指令数据出错: {0}")]
+    InvalidData(InstructionParseError),
+    /// 内部指令组所属的顶层指令不存在，整组内部指令被丢弃
+    #[error("内部指令所属的顶层指令不存在")]
+    MissingParent,
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{location}: {issue}")]
+pub struct InstructionDiagnostic {
+    pub location: InstructionLocation,
+    pub issue: InstructionIssue,
+}
+
+/// 解析结果，包含解析出的指令以及解析过程中每条指令的问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedInstructionReport {
+    pub instructions: ParsedInstructionList,
+    pub diagnostics: Vec<InstructionDiagnostic>,
+}
+
+impl ParsedInstructionReport {
+    /// 所有指令都完整解析
+    pub fn is_complete(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
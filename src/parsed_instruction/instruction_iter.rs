@@ -0,0 +1,122 @@
+use crate::parsed_instruction::ParsedInstruction;
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// 指令在调用树中的路径，每一层为该层中的索引(从0开始)
+///
+/// 如`2.1.3`表示第2条顶层指令调用的第1条内部指令所调用的第3条指令
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InstructionPath(pub Vec<usize>);
+
+impl InstructionPath {
+    /// 顶层指令的深度为0
+    pub fn depth(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    /// 所属顶层指令的索引
+    pub fn top_level_index(&self) -> Option<usize> {
+        self.0.first().copied()
+    }
+
+    /// 父指令的路径，顶层指令返回None
+    pub fn parent(&self) -> Option<Self> {
+        if self.0.len() > 1 {
+            Some(Self(self.0[..self.0.len() - 1].to_vec()))
+        } else {
+            None
+        }
+    }
+
+    /// 在指令树中查找此路径对应的指令
+    pub fn resolve<'a>(
+        &self,
+        instructions: &'a [ParsedInstruction],
+    ) -> Option<&'a ParsedInstruction> {
+        let (first, rest) = self.0.split_first()?;
+        let mut instruction = instructions.get(*first)?;
+        for index in rest {
+            instruction = instruction.inner_instructions.as_ref()?.get(*index)?;
+        }
+
+        Some(instruction)
+    }
+
+    pub fn child(&self, index: usize) -> Self {
+        let mut path = Vec::with_capacity(self.0.len() + 1);
+        path.extend_from_slice(&self.0);
+        path.push(index);
+        Self(path)
+    }
+
+    /// 是否是`other`的祖先(不包括自身)
+    pub fn is_ancestor_of(&self, other: &InstructionPath) -> bool {
+        self.0.len() < other.0.len() && other.0.starts_with(&self.0)
+    }
+}
+
+impl Display for InstructionPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, index) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{index}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 遍历时返回的指令节点
+#[derive(Debug, Clone)]
+pub struct InstructionNode<'a> {
+    pub path: InstructionPath,
+    pub instruction: &'a ParsedInstruction,
+}
+
+impl InstructionNode<'_> {
+    pub fn depth(&self) -> usize {
+        self.path.depth()
+    }
+}
+
+/// 深度优先(先序)遍历指令树
+pub struct InstructionIter<'a> {
+    stack: Vec<InstructionNode<'a>>,
+}
+
+impl<'a> InstructionIter<'a> {
+    pub fn new(instructions: &'a [ParsedInstruction]) -> Self {
+        let stack = instructions
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, instruction)| InstructionNode {
+                path: InstructionPath(vec![index]),
+                instruction,
+            })
+            .collect();
+        Self { stack }
+    }
+}
+
+impl<'a> Iterator for InstructionIter<'a> {
+    type Item = InstructionNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        if let Some(inner) = node.instruction.inner_instructions.as_ref() {
+            for (index, instruction) in inner.iter().enumerate().rev() {
+                self.stack.push(InstructionNode {
+                    path: node.path.child(index),
+                    instruction,
+                });
+            }
+        }
+
+        Some(node)
+    }
+}
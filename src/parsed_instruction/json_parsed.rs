@@ -0,0 +1,716 @@
+//! jsonParsed编码的指令解析
+//!
+//! 节点返回jsonParsed编码时，System/SplToken/SplToken2022等程序的指令只有json格式的`info`,
+//! 没有原始数据, 这里将其还原成[ParsedInstructionData]以及按指令顺序排列的帐户列表
+use crate::instructions::bpf_loader_upgradeable::UpgradeableLoaderInstruction;
+use crate::instructions::spl_token::{
+    AuthorityType as SplTokenAuthorityType, TokenInstruction as SplTokenInstruction,
+};
+use crate::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use crate::parsed_instruction::ParsedInstructionData;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Map, Value};
+use solana_pubkey::Pubkey;
+use solana_sdk::address_lookup_table::instruction::ProgramInstruction as AddressLookupTableInstruction;
+use solana_sdk::program_option::COption;
+use solana_sdk::system_instruction::SystemInstruction;
+use spl_token_2022::instruction::AuthorityType as SplToken2022AuthorityType;
+use std::str::FromStr;
+
+/// 解析jsonParsed格式的指令, 返回指令数据以及按指令顺序排列的帐户列表
+///
+/// 不支持的程序或指令类型返回None
+pub(crate) fn parse(
+    program_id: &Pubkey,
+    parsed: &Value,
+) -> Option<(ParsedInstructionData, Vec<String>)> {
+    let instruction_type = parsed.get("type")?.as_str()?;
+    let info = parsed.get("info")?.as_object()?;
+    if program_id == &solana_sdk::system_program::id() {
+        return parse_system(instruction_type, info)
+            .map(|(instruction, accounts)| (ParsedInstructionData::System(instruction), accounts));
+    }
+
+    if program_id == &spl_token::id() {
+        return parse_token(instruction_type, info).map(|(instruction, accounts)| {
+            (ParsedInstructionData::SplToken(instruction), accounts)
+        });
+    }
+
+    if program_id == &spl_token_2022::id() {
+        return parse_token_2022(instruction_type, info).map(|(instruction, accounts)| {
+            (ParsedInstructionData::SplToken2022(instruction), accounts)
+        });
+    }
+
+    if program_id == &solana_sdk::address_lookup_table::program::id() {
+        return parse_address_lookup_table(instruction_type, info).map(
+            |(instruction, accounts)| {
+                (
+                    ParsedInstructionData::AddressLookupTable(instruction),
+                    accounts,
+                )
+            },
+        );
+    }
+
+    if program_id == &solana_sdk::bpf_loader_upgradeable::id() {
+        return parse_bpf_upgradeable_loader(instruction_type, info).map(
+            |(instruction, accounts)| {
+                (
+                    ParsedInstructionData::BpfLoaderUpgradeable(instruction),
+                    accounts,
+                )
+            },
+        );
+    }
+
+    None
+}
+
+fn parse_system(
+    instruction_type: &str,
+    info: &Map<String, Value>,
+) -> Option<(SystemInstruction, Vec<String>)> {
+    let ret = match instruction_type {
+        "createAccount" => (
+            SystemInstruction::CreateAccount {
+                lamports: u64_field(info, "lamports")?,
+                space: u64_field(info, "space")?,
+                owner: pubkey_field(info, "owner")?,
+            },
+            accounts(info, &["source", "newAccount"])?,
+        ),
+        "assign" => (
+            SystemInstruction::Assign {
+                owner: pubkey_field(info, "owner")?,
+            },
+            accounts(info, &["account"])?,
+        ),
+        "transfer" => (
+            SystemInstruction::Transfer {
+                lamports: u64_field(info, "lamports")?,
+            },
+            accounts(info, &["source", "destination"])?,
+        ),
+        "createAccountWithSeed" => {
+            let mut keys = accounts(info, &["source", "newAccount"])?;
+            // base与source不同时, base作为第3个帐户签名
+            let base = string_field(info, "base")?;
+            if base != keys[0] {
+                keys.push(base.to_string());
+            }
+            (
+                SystemInstruction::CreateAccountWithSeed {
+                    base: Pubkey::from_str(base).ok()?,
+                    seed: string_field(info, "seed")?.to_string(),
+                    lamports: u64_field(info, "lamports")?,
+                    space: u64_field(info, "space")?,
+                    owner: pubkey_field(info, "owner")?,
+                },
+                keys,
+            )
+        }
+        "advanceNonce" => (
+            SystemInstruction::AdvanceNonceAccount,
+            accounts(
+                info,
+                &["nonceAccount", "recentBlockhashesSysvar", "nonceAuthority"],
+            )?,
+        ),
+        "withdrawFromNonce" => (
+            SystemInstruction::WithdrawNonceAccount(u64_field(info, "lamports")?),
+            accounts(
+                info,
+                &[
+                    "nonceAccount",
+                    "destination",
+                    "recentBlockhashesSysvar",
+                    "rentSysvar",
+                    "nonceAuthority",
+                ],
+            )?,
+        ),
+        "initializeNonce" => (
+            SystemInstruction::InitializeNonceAccount(pubkey_field(info, "nonceAuthority")?),
+            accounts(
+                info,
+                &["nonceAccount", "recentBlockhashesSysvar", "rentSysvar"],
+            )?,
+        ),
+        "authorizeNonce" => (
+            SystemInstruction::AuthorizeNonceAccount(pubkey_field(info, "newAuthorized")?),
+            accounts(info, &["nonceAccount", "nonceAuthority"])?,
+        ),
+        "upgradeNonce" => (
+            SystemInstruction::UpgradeNonceAccount,
+            accounts(info, &["nonceAccount"])?,
+        ),
+        "allocate" => (
+            SystemInstruction::Allocate {
+                space: u64_field(info, "space")?,
+            },
+            accounts(info, &["account"])?,
+        ),
+        "allocateWithSeed" => (
+            SystemInstruction::AllocateWithSeed {
+                base: pubkey_field(info, "base")?,
+                seed: string_field(info, "seed")?.to_string(),
+                space: u64_field(info, "space")?,
+                owner: pubkey_field(info, "owner")?,
+            },
+            accounts(info, &["account", "base"])?,
+        ),
+        "assignWithSeed" => (
+            SystemInstruction::AssignWithSeed {
+                base: pubkey_field(info, "base")?,
+                seed: string_field(info, "seed")?.to_string(),
+                owner: pubkey_field(info, "owner")?,
+            },
+            accounts(info, &["account", "base"])?,
+        ),
+        "transferWithSeed" => (
+            SystemInstruction::TransferWithSeed {
+                lamports: u64_field(info, "lamports")?,
+                from_seed: string_field(info, "sourceSeed")?.to_string(),
+                from_owner: pubkey_field(info, "sourceOwner")?,
+            },
+            accounts(info, &["source", "sourceBase", "destination"])?,
+        ),
+        _ => return None,
+    };
+
+    Some(ret)
+}
+
+fn parse_address_lookup_table(
+    instruction_type: &str,
+    info: &Map<String, Value>,
+) -> Option<(AddressLookupTableInstruction, Vec<String>)> {
+    let ret = match instruction_type {
+        "createLookupTable" => (
+            AddressLookupTableInstruction::CreateLookupTable {
+                recent_slot: u64_field(info, "recentSlot")?,
+                bump_seed: u64_field(info, "bumpSeed")? as u8,
+            },
+            accounts(
+                info,
+                &[
+                    "lookupTableAccount",
+                    "lookupTableAuthority",
+                    "payerAccount",
+                    "systemProgram",
+                ],
+            )?,
+        ),
+        "freezeLookupTable" => (
+            AddressLookupTableInstruction::FreezeLookupTable,
+            accounts(info, &["lookupTableAccount", "lookupTableAuthority"])?,
+        ),
+        "extendLookupTable" => {
+            let mut new_addresses = Vec::new();
+            for address in info.get("newAddresses")?.as_array()? {
+                new_addresses.push(Pubkey::from_str(address.as_str()?).ok()?);
+            }
+            let mut keys = accounts(info, &["lookupTableAccount", "lookupTableAuthority"])?;
+            // 只有需要为新地址支付租金时才有payer和system program
+            if info.contains_key("payerAccount") {
+                keys.extend(accounts(info, &["payerAccount", "systemProgram"])?);
+            }
+            (
+                AddressLookupTableInstruction::ExtendLookupTable { new_addresses },
+                keys,
+            )
+        }
+        "deactivateLookupTable" => (
+            AddressLookupTableInstruction::DeactivateLookupTable,
+            accounts(info, &["lookupTableAccount", "lookupTableAuthority"])?,
+        ),
+        "closeLookupTable" => (
+            AddressLookupTableInstruction::CloseLookupTable,
+            accounts(
+                info,
+                &["lookupTableAccount", "lookupTableAuthority", "recipient"],
+            )?,
+        ),
+        _ => return None,
+    };
+
+    Some(ret)
+}
+
+/// 只还原部署相关的指令，权限变更等指令返回None
+fn parse_bpf_upgradeable_loader(
+    instruction_type: &str,
+    info: &Map<String, Value>,
+) -> Option<(UpgradeableLoaderInstruction, Vec<String>)> {
+    let ret = match instruction_type {
+        "initializeBuffer" => {
+            let mut keys = accounts(info, &["account"])?;
+            if let Some(authority) = string_field(info, "authority") {
+                keys.push(authority.to_string());
+            }
+            (UpgradeableLoaderInstruction::InitializeBuffer, keys)
+        }
+        "write" => (
+            UpgradeableLoaderInstruction::Write {
+                offset: u64_field(info, "offset")? as u32,
+                bytes: STANDARD.decode(string_field(info, "bytes")?).ok()?,
+            },
+            accounts(info, &["account", "authority"])?,
+        ),
+        "deployWithMaxDataLen" => (
+            UpgradeableLoaderInstruction::DeployWithMaxDataLen {
+                max_data_len: u64_field(info, "maxDataLen")?,
+            },
+            accounts(
+                info,
+                &[
+                    "payerAccount",
+                    "programDataAccount",
+                    "programAccount",
+                    "bufferAccount",
+                    "rentSysvar",
+                    "clockSysvar",
+                    "systemProgram",
+                    "authority",
+                ],
+            )?,
+        ),
+        "upgrade" => (
+            UpgradeableLoaderInstruction::Upgrade,
+            accounts(
+                info,
+                &[
+                    "programDataAccount",
+                    "programAccount",
+                    "bufferAccount",
+                    "spillAccount",
+                    "rentSysvar",
+                    "clockSysvar",
+                    "authority",
+                ],
+            )?,
+        ),
+        "extendProgram" => {
+            let mut keys = accounts(info, &["programDataAccount", "programAccount"])?;
+            // 需要支付租金时才有system program和payer
+            if info.contains_key("payerAccount") {
+                keys.extend(accounts(info, &["systemProgram", "payerAccount"])?);
+            }
+            (
+                UpgradeableLoaderInstruction::ExtendProgram {
+                    additional_bytes: u64_field(info, "additionalBytes")? as u32,
+                },
+                keys,
+            )
+        }
+        _ => return None,
+    };
+
+    Some(ret)
+}
+
+/// 不支持的指令无法还原帐户顺序，从`info`中收集所有pubkey(包括数组中的)，按出现顺序去重
+///
+/// 返回的顺序与指令的帐户顺序无关，只能用于判断指令涉及哪些帐户
+pub(crate) fn info_accounts(parsed: &Value) -> Vec<String> {
+    let mut ret = Vec::new();
+    if let Some(info) = parsed.get("info") {
+        collect_pubkeys(info, &mut ret);
+    }
+
+    ret
+}
+
+fn collect_pubkeys(value: &Value, ret: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            if Pubkey::from_str(s).is_ok() && !ret.contains(s) {
+                ret.push(s.clone());
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_pubkeys(v, ret)),
+        Value::Object(map) => map.values().for_each(|v| collect_pubkeys(v, ret)),
+        _ => {}
+    }
+}
+
+/// SplToken与SplToken2022共有的指令
+fn parse_token(
+    instruction_type: &str,
+    info: &Map<String, Value>,
+) -> Option<(SplTokenInstruction, Vec<String>)> {
+    let ret = match instruction_type {
+        "initializeMint" => (
+            SplTokenInstruction::InitializeMint {
+                decimals: u64_field(info, "decimals")? as u8,
+                mint_authority: pubkey_field(info, "mintAuthority")?,
+                freeze_authority: coption_pubkey_field(info, "freezeAuthority")?,
+            },
+            accounts(info, &["mint", "rentSysvar"])?,
+        ),
+        "initializeMint2" => (
+            SplTokenInstruction::InitializeMint2 {
+                decimals: u64_field(info, "decimals")? as u8,
+                mint_authority: pubkey_field(info, "mintAuthority")?,
+                freeze_authority: coption_pubkey_field(info, "freezeAuthority")?,
+            },
+            accounts(info, &["mint"])?,
+        ),
+        "initializeAccount" => (
+            SplTokenInstruction::InitializeAccount,
+            accounts(info, &["account", "mint", "owner", "rentSysvar"])?,
+        ),
+        "initializeAccount2" => (
+            SplTokenInstruction::InitializeAccount2 {
+                owner: pubkey_field(info, "owner")?,
+            },
+            accounts(info, &["account", "mint", "rentSysvar"])?,
+        ),
+        "initializeAccount3" => (
+            SplTokenInstruction::InitializeAccount3 {
+                owner: pubkey_field(info, "owner")?,
+            },
+            accounts(info, &["account", "mint"])?,
+        ),
+        "initializeMultisig" => (
+            SplTokenInstruction::InitializeMultisig {
+                m: u64_field(info, "m")? as u8,
+            },
+            with_signers(info, accounts(info, &["multisig", "rentSysvar"])?)?,
+        ),
+        "initializeMultisig2" => (
+            SplTokenInstruction::InitializeMultisig2 {
+                m: u64_field(info, "m")? as u8,
+            },
+            with_signers(info, accounts(info, &["multisig"])?)?,
+        ),
+        "transfer" => (
+            SplTokenInstruction::Transfer {
+                amount: u64_field(info, "amount")?,
+            },
+            authority_accounts(
+                info,
+                &["source", "destination"],
+                "authority",
+                "multisigAuthority",
+            )?,
+        ),
+        "approve" => (
+            SplTokenInstruction::Approve {
+                amount: u64_field(info, "amount")?,
+            },
+            authority_accounts(info, &["source", "delegate"], "owner", "multisigOwner")?,
+        ),
+        "revoke" => (
+            SplTokenInstruction::Revoke,
+            authority_accounts(info, &["source"], "owner", "multisigOwner")?,
+        ),
+        "setAuthority" => {
+            let authority_type = match string_field(info, "authorityType")? {
+                "mintTokens" => SplTokenAuthorityType::MintTokens,
+                "freezeAccount" => SplTokenAuthorityType::FreezeAccount,
+                "accountOwner" => SplTokenAuthorityType::AccountOwner,
+                "closeAccount" => SplTokenAuthorityType::CloseAccount,
+                _ => return None,
+            };
+            (
+                SplTokenInstruction::SetAuthority {
+                    authority_type,
+                    new_authority: coption_pubkey_field(info, "newAuthority")?,
+                },
+                set_authority_accounts(info)?,
+            )
+        }
+        "mintTo" => (
+            SplTokenInstruction::MintTo {
+                amount: u64_field(info, "amount")?,
+            },
+            authority_accounts(
+                info,
+                &["mint", "account"],
+                "mintAuthority",
+                "multisigMintAuthority",
+            )?,
+        ),
+        "burn" => (
+            SplTokenInstruction::Burn {
+                amount: u64_field(info, "amount")?,
+            },
+            authority_accounts(info, &["account", "mint"], "authority", "multisigAuthority")?,
+        ),
+        "closeAccount" => (
+            SplTokenInstruction::CloseAccount,
+            authority_accounts(info, &["account", "destination"], "owner", "multisigOwner")?,
+        ),
+        "freezeAccount" => (
+            SplTokenInstruction::FreezeAccount,
+            authority_accounts(
+                info,
+                &["account", "mint"],
+                "freezeAuthority",
+                "multisigFreezeAuthority",
+            )?,
+        ),
+        "thawAccount" => (
+            SplTokenInstruction::ThawAccount,
+            authority_accounts(
+                info,
+                &["account", "mint"],
+                "freezeAuthority",
+                "multisigFreezeAuthority",
+            )?,
+        ),
+        "transferChecked" => {
+            let (amount, decimals) = token_amount_field(info, "tokenAmount")?;
+            (
+                SplTokenInstruction::TransferChecked { amount, decimals },
+                authority_accounts(
+                    info,
+                    &["source", "mint", "destination"],
+                    "authority",
+                    "multisigAuthority",
+                )?,
+            )
+        }
+        "approveChecked" => {
+            let (amount, decimals) = token_amount_field(info, "tokenAmount")?;
+            (
+                SplTokenInstruction::ApproveChecked { amount, decimals },
+                authority_accounts(
+                    info,
+                    &["source", "mint", "delegate"],
+                    "owner",
+                    "multisigOwner",
+                )?,
+            )
+        }
+        "mintToChecked" => {
+            let (amount, decimals) = token_amount_field(info, "tokenAmount")?;
+            (
+                SplTokenInstruction::MintToChecked { amount, decimals },
+                authority_accounts(
+                    info,
+                    &["mint", "account"],
+                    "mintAuthority",
+                    "multisigMintAuthority",
+                )?,
+            )
+        }
+        "burnChecked" => {
+            let (amount, decimals) = token_amount_field(info, "tokenAmount")?;
+            (
+                SplTokenInstruction::BurnChecked { amount, decimals },
+                authority_accounts(info, &["account", "mint"], "authority", "multisigAuthority")?,
+            )
+        }
+        "syncNative" => (
+            SplTokenInstruction::SyncNative,
+            accounts(info, &["account"])?,
+        ),
+        "getAccountDataSize" => {
+            // 带扩展类型的只有SplToken2022才有, 这里不处理
+            if info.contains_key("extensionTypes") {
+                return None;
+            }
+            (
+                SplTokenInstruction::GetAccountDataSize,
+                accounts(info, &["mint"])?,
+            )
+        }
+        "initializeImmutableOwner" => (
+            SplTokenInstruction::InitializeImmutableOwner,
+            accounts(info, &["account"])?,
+        ),
+        "amountToUiAmount" => (
+            SplTokenInstruction::AmountToUiAmount {
+                amount: u64_field(info, "amount")?,
+            },
+            accounts(info, &["mint"])?,
+        ),
+        "uiAmountToAmount" => (
+            SplTokenInstruction::UiAmountToAmount {
+                ui_amount: string_field(info, "uiAmount")?.to_string(),
+            },
+            accounts(info, &["mint"])?,
+        ),
+        _ => return None,
+    };
+
+    Some(ret)
+}
+
+fn parse_token_2022(
+    instruction_type: &str,
+    info: &Map<String, Value>,
+) -> Option<(SplToken2022Instruction, Vec<String>)> {
+    let ret = match instruction_type {
+        "setAuthority" => {
+            let authority_type = match string_field(info, "authorityType")? {
+                "mintTokens" => SplToken2022AuthorityType::MintTokens,
+                "freezeAccount" => SplToken2022AuthorityType::FreezeAccount,
+                "accountOwner" => SplToken2022AuthorityType::AccountOwner,
+                "closeAccount" => SplToken2022AuthorityType::CloseAccount,
+                "transferFeeConfig" => SplToken2022AuthorityType::TransferFeeConfig,
+                "withheldWithdraw" => SplToken2022AuthorityType::WithheldWithdraw,
+                "closeMint" => SplToken2022AuthorityType::CloseMint,
+                "interestRate" => SplToken2022AuthorityType::InterestRate,
+                "permanentDelegate" => SplToken2022AuthorityType::PermanentDelegate,
+                "confidentialTransferMint" => SplToken2022AuthorityType::ConfidentialTransferMint,
+                "transferHookProgramId" => SplToken2022AuthorityType::TransferHookProgramId,
+                "confidentialTransferFeeConfig" => {
+                    SplToken2022AuthorityType::ConfidentialTransferFeeConfig
+                }
+                "metadataPointer" => SplToken2022AuthorityType::MetadataPointer,
+                "groupPointer" => SplToken2022AuthorityType::GroupPointer,
+                "groupMemberPointer" => SplToken2022AuthorityType::GroupMemberPointer,
+                "scaledUiAmount" => SplToken2022AuthorityType::ScaledUiAmount,
+                "pause" => SplToken2022AuthorityType::Pause,
+                _ => return None,
+            };
+            (
+                SplToken2022Instruction::SetAuthority {
+                    authority_type,
+                    new_authority: coption_pubkey_field(info, "newAuthority")?,
+                },
+                set_authority_accounts(info)?,
+            )
+        }
+        "initializeMintCloseAuthority" => (
+            SplToken2022Instruction::InitializeMintCloseAuthority {
+                close_authority: coption_pubkey_field(info, "newAuthority")?,
+            },
+            accounts(info, &["mint"])?,
+        ),
+        "initializePermanentDelegate" => (
+            SplToken2022Instruction::InitializePermanentDelegate {
+                delegate: pubkey_field(info, "delegate")?,
+            },
+            accounts(info, &["mint"])?,
+        ),
+        "createNativeMint" => (
+            SplToken2022Instruction::CreateNativeMint,
+            accounts(info, &["payer", "nativeMint", "systemProgram"])?,
+        ),
+        "initializeNonTransferableMint" => (
+            SplToken2022Instruction::InitializeNonTransferableMint,
+            accounts(info, &["mint"])?,
+        ),
+        "withdrawExcessLamports" => (
+            SplToken2022Instruction::WithdrawExcessLamports,
+            authority_accounts(
+                info,
+                &["source", "destination"],
+                "authority",
+                "multisigAuthority",
+            )?,
+        ),
+        "transferCheckedWithFee" => {
+            let (amount, decimals) = token_amount_field(info, "tokenAmount")?;
+            let (fee, _) = token_amount_field(info, "feeAmount")?;
+            (
+                SplToken2022Instruction::TransferCheckedWithFee {
+                    amount,
+                    decimals,
+                    fee,
+                },
+                authority_accounts(
+                    info,
+                    &["source", "mint", "destination"],
+                    "authority",
+                    "multisigAuthority",
+                )?,
+            )
+        }
+        _ => {
+            let (instruction, accounts) = parse_token(instruction_type, info)?;
+            (SplToken2022Instruction::from(instruction), accounts)
+        }
+    };
+
+    Some(ret)
+}
+
+/// SetAuthority的第一个帐户根据权限类型不同，可能是mint或account
+fn set_authority_accounts(info: &Map<String, Value>) -> Option<Vec<String>> {
+    let owned = if info.contains_key("mint") {
+        "mint"
+    } else {
+        "account"
+    };
+    authority_accounts(info, &[owned], "authority", "multisigAuthority")
+}
+
+/// 按顺序取出帐户字段
+fn accounts(info: &Map<String, Value>, names: &[&str]) -> Option<Vec<String>> {
+    let mut ret = Vec::with_capacity(names.len());
+    for name in names {
+        ret.push(string_field(info, name)?.to_string());
+    }
+
+    Some(ret)
+}
+
+/// 按顺序取出帐户字段，并追加权限帐户
+///
+/// 单签时为`owner_field`, 多签时为`multisig_field`加上`signers`列表
+fn authority_accounts(
+    info: &Map<String, Value>,
+    names: &[&str],
+    owner_field: &str,
+    multisig_field: &str,
+) -> Option<Vec<String>> {
+    let mut ret = accounts(info, names)?;
+    if let Some(multisig) = string_field(info, multisig_field) {
+        ret.push(multisig.to_string());
+        with_signers(info, ret)
+    } else {
+        ret.push(string_field(info, owner_field)?.to_string());
+        Some(ret)
+    }
+}
+
+/// 追加`signers`列表中的帐户
+fn with_signers(info: &Map<String, Value>, mut accounts: Vec<String>) -> Option<Vec<String>> {
+    for signer in info.get("signers")?.as_array()? {
+        accounts.push(signer.as_str()?.to_string());
+    }
+
+    Some(accounts)
+}
+
+fn string_field<'a>(info: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    info.get(name)?.as_str()
+}
+
+/// 数量字段，jsonParsed中可能是数字也可能是字符串
+fn u64_field(info: &Map<String, Value>, name: &str) -> Option<u64> {
+    match info.get(name)? {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn pubkey_field(info: &Map<String, Value>, name: &str) -> Option<Pubkey> {
+    Pubkey::from_str(string_field(info, name)?).ok()
+}
+
+/// 可选的pubkey字段，字段不存在或为null时为COption::None
+fn coption_pubkey_field(info: &Map<String, Value>, name: &str) -> Option<COption<Pubkey>> {
+    match info.get(name) {
+        None | Some(Value::Null) => Some(COption::None),
+        Some(Value::String(s)) => Pubkey::from_str(s).ok().map(COption::Some),
+        _ => None,
+    }
+}
+
+/// 解析`tokenAmount`字段，返回(amount, decimals)
+fn token_amount_field(info: &Map<String, Value>, name: &str) -> Option<(u64, u8)> {
+    let token_amount = info.get(name)?.as_object()?;
+    let amount = u64_field(token_amount, "amount")?;
+    let decimals = u64_field(token_amount, "decimals")? as u8;
+    Some((amount, decimals))
+}
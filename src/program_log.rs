@@ -0,0 +1,189 @@
+use crate::parsed_instruction::ParsedInstruction;
+use crate::parsed_instruction::instruction_iter::{
+    InstructionIter, InstructionNode, InstructionPath,
+};
+use crate::utils::TransactionAccounts;
+
+/// 程序在一次调用中直接输出的日志
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEntry {
+    /// `Program log: `
+    Log(String),
+    /// `Program data: `, 空格分隔的base64数据
+    Data(Vec<String>),
+    /// `Program return: `, 返回数据为base64
+    Return { program_id: String, data: String },
+    /// 无法识别的日志
+    Other(String),
+}
+
+/// 调用的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationStatus {
+    Success,
+    Failed(String),
+    /// 日志被截断或交易中途失败，没有看到结束日志
+    Incomplete,
+}
+
+/// 从日志中还原的一次程序调用
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramInvocation {
+    /// 与指令树中对应指令的路径一致
+    pub path: InstructionPath,
+    pub program_id: String,
+    pub logs: Vec<LogEntry>,
+    /// 包括子调用在内消耗的CU
+    pub compute_units_consumed: Option<u64>,
+    /// 调用开始时剩余的CU
+    pub compute_units_budget: Option<u64>,
+    pub status: InvocationStatus,
+}
+
+/// 交易日志还原出的调用栈，按调用顺序(先序)排列
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramLogs {
+    pub invocations: Vec<ProgramInvocation>,
+    /// 日志是否被节点截断
+    pub truncated: bool,
+}
+
+/// 指令及其对应的调用日志
+#[derive(Debug, Clone)]
+pub struct InstructionLogs<'a> {
+    pub node: InstructionNode<'a>,
+    /// 日志缺失或程序不一致时为None
+    pub invocation: Option<&'a ProgramInvocation>,
+}
+
+impl ProgramLogs {
+    pub fn parse(log_messages: &[String]) -> Self {
+        let mut logs = Self::default();
+        // 调用栈，元素为invocations中的索引
+        let mut stack: Vec<usize> = vec![];
+        // 每一层下一个调用的索引
+        let mut next_child: Vec<usize> = vec![0];
+        for line in log_messages {
+            if line == "Log truncated" {
+                logs.truncated = true;
+                continue;
+            }
+            if let Some((program_id, depth)) = parse_invoke(line) {
+                let depth = depth.max(1) - 1;
+                stack.truncate(depth);
+                next_child.resize(depth + 1, 0);
+                let index = next_child[depth];
+                next_child[depth] += 1;
+                next_child.push(0);
+                let path = match stack.last() {
+                    Some(parent) => logs.invocations[*parent].path.child(index),
+                    None => InstructionPath(vec![index]),
+                };
+                stack.push(logs.invocations.len());
+                logs.invocations.push(ProgramInvocation {
+                    path,
+                    program_id: program_id.to_string(),
+                    logs: vec![],
+                    compute_units_consumed: None,
+                    compute_units_budget: None,
+                    status: InvocationStatus::Incomplete,
+                });
+                continue;
+            }
+
+            let Some(current) = stack.last().map(|i| &mut logs.invocations[*i]) else {
+                continue;
+            };
+            if let Some(message) = line.strip_prefix("Program log: ") {
+                current.logs.push(LogEntry::Log(message.to_string()));
+            } else if let Some(data) = line.strip_prefix("Program data: ") {
+                current.logs.push(LogEntry::Data(
+                    data.split_whitespace().map(|d| d.to_string()).collect(),
+                ));
+            } else if let Some(rest) = line.strip_prefix("Program return: ") {
+                let (program_id, data) = rest.split_once(' ').unwrap_or((rest, ""));
+                current.logs.push(LogEntry::Return {
+                    program_id: program_id.to_string(),
+                    data: data.to_string(),
+                });
+            } else if let Some((consumed, budget)) = parse_consumed(line, &current.program_id) {
+                current.compute_units_consumed = Some(consumed);
+                current.compute_units_budget = Some(budget);
+            } else if line == &format!("Program {} success", current.program_id) {
+                current.status = InvocationStatus::Success;
+                stack.pop();
+            } else if let Some(error) =
+                line.strip_prefix(&format!("Program {} failed: ", current.program_id))
+            {
+                current.status = InvocationStatus::Failed(error.to_string());
+                stack.pop();
+            } else {
+                current.logs.push(LogEntry::Other(line.clone()));
+            }
+        }
+
+        logs
+    }
+
+    pub fn get(&self, path: &InstructionPath) -> Option<&ProgramInvocation> {
+        self.invocations.iter().find(|i| &i.path == path)
+    }
+
+    /// 调用自身消耗的CU，不包括子调用
+    pub fn exclusive_compute_units(&self, path: &InstructionPath) -> Option<u64> {
+        let consumed = self.get(path)?.compute_units_consumed?;
+        let children: u64 = self
+            .invocations
+            .iter()
+            .filter(|i| i.path.parent().as_ref() == Some(path))
+            .filter_map(|i| i.compute_units_consumed)
+            .sum();
+        Some(consumed.saturating_sub(children))
+    }
+
+    /// 把调用日志对应到指令树的每个节点上
+    ///
+    /// 按路径匹配，并检查程序id一致，避免指令解析丢失时错位
+    pub fn attach<'a>(
+        &'a self,
+        instructions: &'a [ParsedInstruction],
+        transaction_accounts: &TransactionAccounts<'_, String>,
+    ) -> Vec<InstructionLogs<'a>> {
+        InstructionIter::new(instructions)
+            .map(|node| {
+                let program_id = node
+                    .instruction
+                    .program_id
+                    .map(|p| p.to_string())
+                    .or_else(|| {
+                        transaction_accounts
+                            .get(node.instruction.program_id_index as usize)
+                            .cloned()
+                    });
+                let invocation = self
+                    .get(&node.path)
+                    .filter(|i| program_id.as_deref().is_none_or(|p| p == i.program_id));
+                InstructionLogs { node, invocation }
+            })
+            .collect()
+    }
+}
+
+/// 解析`Program <id> invoke [<depth>]`
+fn parse_invoke(line: &str) -> Option<(&str, usize)> {
+    let rest = line.strip_prefix("Program ")?;
+    let (program_id, rest) = rest.split_once(" invoke [")?;
+    let depth = rest.strip_suffix(']')?.parse().ok()?;
+    Some((program_id, depth))
+}
+
+/// 解析`Program <id> consumed <n> of <m> compute units`
+fn parse_consumed(line: &str, program_id: &str) -> Option<(u64, u64)> {
+    let rest = line
+        .strip_prefix("Program ")?
+        .strip_prefix(program_id)?
+        .strip_prefix(" consumed ")?
+        .strip_suffix(" compute units")?;
+    let (consumed, budget) = rest.split_once(" of ")?;
+    Some((consumed.parse().ok()?, budget.parse().ok()?))
+}
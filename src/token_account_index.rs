@@ -0,0 +1,113 @@
+use crate::token_transfer_data::TokenTransferData;
+use crate::utils::TransactionAccounts;
+use solana_transaction_status_client_types::{TransactionTokenBalance, UiTransactionTokenBalance};
+use std::collections::HashMap;
+
+/// 从交易token余额中得到的token帐户信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAccountInfo {
+    pub mint: String,
+    pub decimals: u8,
+    /// 老的节点不返回owner
+    pub owner: Option<String>,
+    pub program_id: Option<String>,
+}
+
+/// 交易中token帐户的索引，以帐户在交易帐户列表中的索引为键
+///
+/// 由`pre_token_balances`和`post_token_balances`构建，每笔交易构建一次即可
+#[derive(Debug, Clone, Default)]
+pub struct TokenAccountIndex {
+    accounts: HashMap<u8, TokenAccountInfo>,
+}
+
+/// 补全了mint、精度和双方owner的token转帐
+#[derive(Debug)]
+pub struct ResolvedTokenTransfer<'a> {
+    pub data: TokenTransferData,
+    pub mint: Option<&'a str>,
+    pub decimals: Option<u8>,
+    pub source_owner: Option<&'a str>,
+    pub destination_owner: Option<&'a str>,
+}
+
+impl TokenAccountIndex {
+    /// 交易中创建的帐户只出现在post中，关闭的帐户只出现在pre中，两者合并
+    pub fn from_token_balances(
+        pre_token_balances: &[TransactionTokenBalance],
+        post_token_balances: &[TransactionTokenBalance],
+    ) -> Self {
+        let mut accounts = HashMap::new();
+        for balance in pre_token_balances.iter().chain(post_token_balances) {
+            accounts
+                .entry(balance.account_index)
+                .or_insert_with(|| TokenAccountInfo {
+                    mint: balance.mint.clone(),
+                    decimals: balance.ui_token_amount.decimals,
+                    owner: Some(balance.owner.clone()).filter(|o| !o.is_empty()),
+                    program_id: Some(balance.program_id.clone()).filter(|p| !p.is_empty()),
+                });
+        }
+
+        Self { accounts }
+    }
+
+    /// 从RPC返回的token余额构建
+    pub fn from_ui_token_balances(
+        pre_token_balances: &[UiTransactionTokenBalance],
+        post_token_balances: &[UiTransactionTokenBalance],
+    ) -> Self {
+        let mut accounts = HashMap::new();
+        for balance in pre_token_balances.iter().chain(post_token_balances) {
+            accounts
+                .entry(balance.account_index)
+                .or_insert_with(|| TokenAccountInfo {
+                    mint: balance.mint.clone(),
+                    decimals: balance.ui_token_amount.decimals,
+                    owner: balance.owner.clone().into(),
+                    program_id: balance.program_id.clone().into(),
+                });
+        }
+
+        Self { accounts }
+    }
+
+    /// 根据帐户在交易帐户列表中的索引获取
+    pub fn get(&self, account_index: u8) -> Option<&TokenAccountInfo> {
+        self.accounts.get(&account_index)
+    }
+
+    /// 根据帐户地址获取
+    pub fn get_by_address(
+        &self,
+        transaction_accounts: &TransactionAccounts<'_, String>,
+        address: &str,
+    ) -> Option<&TokenAccountInfo> {
+        let index = transaction_accounts.position(address)?;
+        self.get(u8::try_from(index).ok()?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// 补全转帐的mint、精度和双方owner
+    ///
+    /// 未检查的Transfer中没有mint, 从source或destination的余额信息中获取
+    pub fn resolve_transfer(&self, data: TokenTransferData) -> ResolvedTokenTransfer<'_> {
+        let source = self.get(data.source);
+        let destination = self.get(data.destination);
+        let info = source.or(destination);
+        ResolvedTokenTransfer {
+            mint: info.map(|i| i.mint.as_str()),
+            decimals: data.decimal.or(info.map(|i| i.decimals)),
+            source_owner: source.and_then(|i| i.owner.as_deref()),
+            destination_owner: destination.and_then(|i| i.owner.as_deref()),
+            data,
+        }
+    }
+}
@@ -1 +1,13 @@
-pub mod transaction_filter;
\ No newline at end of file
+pub mod transaction_filter;
+pub mod account_meta;
+pub mod address_lookup_table;
+pub mod arbitrage;
+pub mod balance_change;
+pub mod classifier;
+pub mod encoded_transaction;
+pub mod failure;
+pub mod fee;
+pub mod profit;
+pub mod sandwich;
+pub mod swap_detector;
+pub mod token_flow;
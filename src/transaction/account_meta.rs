@@ -0,0 +1,132 @@
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use crate::utils::TransactionAccounts;
+use solana_sdk::message::MessageHeader;
+
+/// 帐户在交易中的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountSource {
+    /// 交易消息中的静态帐户
+    Static,
+    /// 通过地址查找表加载的可写帐户
+    LookupWritable,
+    /// 通过地址查找表加载的只读帐户
+    LookupReadonly,
+}
+
+/// 单个帐户在交易中的权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub index: usize,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub is_fee_payer: bool,
+    pub source: AccountSource,
+}
+
+/// 根据消息头和地址查找表的加载结果计算每个帐户的权限
+///
+/// 可写性是消息声明的值，运行时还会把被调用的程序和sysvar降级为只读，这里不做处理。
+/// jsonParsed格式没有消息头，只能根据降级后的可写标记还原，区域末尾被降级的帐户会被当作只读
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountPermissions {
+    header: MessageHeader,
+    static_accounts_num: usize,
+    loaded_writable_accounts_num: usize,
+    loaded_readonly_accounts_num: usize,
+}
+
+impl AccountPermissions {
+    pub fn new(
+        header: MessageHeader,
+        static_accounts_num: usize,
+        loaded_writable_accounts_num: usize,
+        loaded_readonly_accounts_num: usize,
+    ) -> Self {
+        Self {
+            header,
+            static_accounts_num,
+            loaded_writable_accounts_num,
+            loaded_readonly_accounts_num,
+        }
+    }
+
+    pub fn from_accounts<T>(header: MessageHeader, accounts: &TransactionAccounts<'_, T>) -> Self {
+        let static_accounts_num = accounts.static_accounts_num();
+        let loaded_writable_accounts_num = accounts.loaded_writable_accounts_num();
+        Self::new(
+            header,
+            static_accounts_num,
+            loaded_writable_accounts_num,
+            accounts.accounts_num() - static_accounts_num - loaded_writable_accounts_num,
+        )
+    }
+
+    pub fn accounts_num(&self) -> usize {
+        self.static_accounts_num
+            + self.loaded_writable_accounts_num
+            + self.loaded_readonly_accounts_num
+    }
+
+    pub fn source(&self, index: usize) -> Option<AccountSource> {
+        if index < self.static_accounts_num {
+            Some(AccountSource::Static)
+        } else if index < self.static_accounts_num + self.loaded_writable_accounts_num {
+            Some(AccountSource::LookupWritable)
+        } else if index < self.accounts_num() {
+            Some(AccountSource::LookupReadonly)
+        } else {
+            None
+        }
+    }
+
+    /// 签名者只能是静态帐户中的前`num_required_signatures`个
+    pub fn is_signer(&self, index: usize) -> bool {
+        index < self.static_accounts_num && index < self.header.num_required_signatures as usize
+    }
+
+    pub fn is_fee_payer(&self, index: usize) -> bool {
+        index == 0 && self.is_signer(index)
+    }
+
+    pub fn is_writable(&self, index: usize) -> bool {
+        let num_signers = self.header.num_required_signatures as usize;
+        match self.source(index) {
+            Some(AccountSource::Static) if index < num_signers => {
+                index
+                    < num_signers.saturating_sub(self.header.num_readonly_signed_accounts as usize)
+            }
+            Some(AccountSource::Static) => {
+                index
+                    < self
+                        .static_accounts_num
+                        .saturating_sub(self.header.num_readonly_unsigned_accounts as usize)
+            }
+            Some(AccountSource::LookupWritable) => true,
+            Some(AccountSource::LookupReadonly) | None => false,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<AccountMeta> {
+        Some(AccountMeta {
+            index,
+            is_signer: self.is_signer(index),
+            is_writable: self.is_writable(index),
+            is_fee_payer: self.is_fee_payer(index),
+            source: self.source(index)?,
+        })
+    }
+
+    /// 按索引顺序返回所有帐户的权限
+    pub fn iter(&self) -> impl Iterator<Item = AccountMeta> + '_ {
+        (0..self.accounts_num()).filter_map(|index| self.get(index))
+    }
+}
+
+/// 获取交易中所有帐户的权限，没有消息头时返回None
+pub fn account_permissions(obj: &dyn TransactionPropsProvider) -> Option<AccountPermissions> {
+    let header = obj.get_message_header()?;
+    Some(AccountPermissions::from_accounts(
+        header,
+        &obj.get_accounts(),
+    ))
+}
@@ -0,0 +1,238 @@
+use crate::parsed_instruction::instruction_iter::InstructionIter;
+use crate::parsed_instruction::{ParsedInstruction, ParsedInstructionData};
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use crate::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+use solana_sdk::address_lookup_table::instruction::ProgramInstruction;
+use solana_sdk::address_lookup_table::program;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::message::v0::MessageAddressTableLookup;
+use solana_transaction_status_client_types::UiAddressTableLookup;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LookupTableError {
+    #[error("地址查找表{0}不存在")]
+    TableNotFound(String),
+    #[error("地址查找表{table}中不存在索引为{index}的地址")]
+    InvalidIndex { table: String, index: u8 },
+    #[error("无法解析地址查找表{table}的数据: {message}")]
+    InvalidData { table: String, message: String },
+}
+
+/// 通过地址查找表加载的帐户，先是所有表的可写帐户，然后是所有表的只读帐户
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadedAccounts {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+impl LoadedAccounts {
+    /// 和静态帐户一起组成完整的交易帐户列表
+    pub fn transaction_accounts<'a>(
+        &'a self,
+        account_keys: &'a [String],
+    ) -> TransactionAccounts<'a, String> {
+        TransactionAccounts::from_accounts(
+            Some(account_keys),
+            Some(self.writable.as_slice()),
+            Some(self.readonly.as_slice()),
+        )
+    }
+}
+
+/// 地址查找表的数据来源，用于在缺少`meta.loaded_addresses`时还原v0交易的帐户
+pub trait AddressLookupTableResolver {
+    /// 查找表中的全部地址，表不存在时返回None
+    fn addresses(&self, table: &str) -> Option<&[String]>;
+
+    fn lookup(&self, table: &str, indexes: &[u8]) -> Result<Vec<String>, LookupTableError> {
+        let addresses = self
+            .addresses(table)
+            .ok_or_else(|| LookupTableError::TableNotFound(table.to_string()))?;
+        indexes
+            .iter()
+            .map(|index| {
+                addresses.get(*index as usize).cloned().ok_or_else(|| {
+                    LookupTableError::InvalidIndex {
+                        table: table.to_string(),
+                        index: *index,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn resolve(
+        &self,
+        lookups: &[MessageAddressTableLookup],
+    ) -> Result<LoadedAccounts, LookupTableError> {
+        let mut loaded = LoadedAccounts::default();
+        for lookup in lookups {
+            let table = lookup.account_key.to_string();
+            loaded
+                .writable
+                .extend(self.lookup(&table, &lookup.writable_indexes)?);
+            loaded
+                .readonly
+                .extend(self.lookup(&table, &lookup.readonly_indexes)?);
+        }
+
+        Ok(loaded)
+    }
+
+    /// 解析RPC返回的`addressTableLookups`
+    fn resolve_ui(
+        &self,
+        lookups: &[UiAddressTableLookup],
+    ) -> Result<LoadedAccounts, LookupTableError> {
+        let mut loaded = LoadedAccounts::default();
+        for lookup in lookups {
+            loaded
+                .writable
+                .extend(self.lookup(&lookup.account_key, &lookup.writable_indexes)?);
+            loaded
+                .readonly
+                .extend(self.lookup(&lookup.account_key, &lookup.readonly_indexes)?);
+        }
+
+        Ok(loaded)
+    }
+
+    /// legacy交易没有地址查找表，返回空列表
+    fn resolve_message(
+        &self,
+        message: &VersionedMessage,
+    ) -> Result<LoadedAccounts, LookupTableError> {
+        match message.address_table_lookups() {
+            Some(lookups) => self.resolve(lookups),
+            None => Ok(LoadedAccounts::default()),
+        }
+    }
+}
+
+/// 内存中的地址查找表，可以从帐户数据或已执行的ExtendLookupTable指令构建
+///
+/// 不跟踪表的停用状态，也不检查地址的激活slot
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryLookupTables {
+    tables: HashMap<String, Vec<String>>,
+}
+
+impl InMemoryLookupTables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, table: impl Into<String>, addresses: Vec<String>) {
+        self.tables.insert(table.into(), addresses);
+    }
+
+    pub fn remove(&mut self, table: &str) -> Option<Vec<String>> {
+        self.tables.remove(table)
+    }
+
+    /// 追加地址，表不存在时创建
+    pub fn extend(&mut self, table: &str, addresses: impl IntoIterator<Item = String>) {
+        self.tables
+            .entry(table.to_string())
+            .or_default()
+            .extend(addresses);
+    }
+
+    /// 从链上的地址查找表帐户数据导入，覆盖已有的表
+    pub fn insert_account_data(
+        &mut self,
+        table: &str,
+        data: &[u8],
+    ) -> Result<(), LookupTableError> {
+        let lookup_table =
+            AddressLookupTable::deserialize(data).map_err(|e| LookupTableError::InvalidData {
+                table: table.to_string(),
+                message: e.to_string(),
+            })?;
+        self.insert(
+            table,
+            lookup_table
+                .addresses
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+        );
+
+        Ok(())
+    }
+
+    /// 应用地址查找表程序的指令，返回指令是否修改了查找表
+    ///
+    /// 使用解析时解码的[ParsedInstructionData::AddressLookupTable]，不依赖[ParsedInstruction::raw_data]，
+    /// 所以[crate::parsed_instruction::ParseOptions::keep_raw_data]关闭或jsonParsed格式的交易同样适用
+    pub fn apply_instruction(
+        &mut self,
+        instruction: &ParsedInstruction,
+        accounts: &TransactionAccounts<'_, String>,
+    ) -> bool {
+        let is_lookup_table_program = match instruction.program_id {
+            Some(program_id) => program_id == program::id(),
+            None => accounts
+                .get(instruction.program_id_index as usize)
+                .and_then(|p| p.parse::<Pubkey>().ok())
+                .is_some_and(|p| p == program::id()),
+        };
+        if !is_lookup_table_program {
+            return false;
+        }
+        let ParsedInstructionData::AddressLookupTable(lookup_instruction) =
+            &instruction.instruction_data
+        else {
+            return false;
+        };
+        // 所有指令的第0个帐户都是查找表
+        let Some(table) = instruction
+            .accounts
+            .first()
+            .and_then(|index| accounts.get(*index as usize))
+        else {
+            return false;
+        };
+
+        match lookup_instruction {
+            ProgramInstruction::CreateLookupTable { .. } => {
+                self.insert(table.clone(), vec![]);
+                true
+            }
+            ProgramInstruction::ExtendLookupTable { new_addresses } => {
+                self.extend(table, new_addresses.iter().map(|a| a.to_string()));
+                true
+            }
+            ProgramInstruction::CloseLookupTable => self.remove(table).is_some(),
+            ProgramInstruction::FreezeLookupTable | ProgramInstruction::DeactivateLookupTable => {
+                false
+            }
+        }
+    }
+
+    /// 应用成功交易中的所有地址查找表指令(包括CPI)
+    ///
+    /// 没有meta的交易无法确认是否成功，不会被应用
+    pub fn apply_transaction(&mut self, obj: &dyn TransactionPropsProvider) {
+        if !obj.get_meta().is_some_and(|meta| meta.err.is_none()) {
+            return;
+        }
+        let Some(instructions) = obj.get_parsed_instructions() else {
+            return;
+        };
+        let accounts = obj.get_accounts();
+        for node in InstructionIter::new(instructions) {
+            self.apply_instruction(node.instruction, &accounts);
+        }
+    }
+}
+
+impl AddressLookupTableResolver for InMemoryLookupTables {
+    fn addresses(&self, table: &str) -> Option<&[String]> {
+        self.tables.get(table).map(|a| a.as_slice())
+    }
+}
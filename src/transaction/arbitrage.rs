@@ -0,0 +1,250 @@
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::transaction::token_flow::{TokenFlow, signer_accounts, token_flows};
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 循环最多包含的边数，每跳兑换由转入、池内兑换、转出三条边组成
+const MAX_CYCLE_EDGES: usize = 24;
+/// 图中的边超过该数量时不搜索
+const MAX_GRAPH_EDGES: usize = 512;
+/// 一笔交易中最多报告的循环数量
+const MAX_CYCLES: usize = 16;
+/// 一次搜索最多检查的边数，用完后返回已找到的循环
+const MAX_SEARCH_STEPS: usize = 100_000;
+
+/// token流动图的节点
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TokenFlowNode {
+    pub owner: String,
+    pub mint: String,
+}
+
+/// 一次循环套利
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbitrageCycle {
+    pub owner: String,
+    /// 依次经过的节点，首尾都是起点
+    pub path: Vec<TokenFlowNode>,
+    /// 依次经过的mint(去重)，第一个是起点的mint
+    pub mints: Vec<String>,
+    /// 组成循环的转帐指令
+    pub instruction_paths: Vec<InstructionPath>,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    /// 以起点mint计的净收益，可能为负
+    pub profit: i128,
+}
+
+/// 以(owner, mint)为节点的token流动图
+///
+/// 转帐是同一mint在不同owner之间的边；池子在同一场所指令中收入一种mint后付出另一种mint,
+/// 是同一owner在不同mint之间的边
+#[derive(Debug, Default)]
+pub struct TokenFlowGraph {
+    nodes: Vec<TokenFlowNode>,
+    edges: Vec<FlowEdge>,
+    /// 每个节点出发的边，按发生顺序排列
+    outgoing: Vec<Vec<usize>>,
+}
+
+#[derive(Debug)]
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    /// 边的先后顺序，循环中的边必须依次发生
+    order: (usize, u8),
+    sent: u64,
+    received: u64,
+    /// 池子内部的兑换边没有对应的转帐指令
+    path: Option<InstructionPath>,
+}
+
+/// 检测交易中回到起始mint的循环套利，起点为交易的签名者
+pub fn detect_arbitrage_cycles(obj: &dyn TransactionPropsProvider) -> Vec<ArbitrageCycle> {
+    let signers = signer_accounts(obj);
+    let graph = TokenFlowGraph::new(&token_flows(obj));
+    graph.find_cycles(|node| signers.is_empty() || signers.contains(&node.owner))
+}
+
+impl TokenFlowGraph {
+    pub fn new(flows: &[TokenFlow]) -> Self {
+        let mut graph = Self::default();
+        let mut node_ids = HashMap::new();
+        let mut venues: BTreeMap<InstructionPath, Vec<usize>> = BTreeMap::new();
+        for (order, flow) in flows.iter().enumerate() {
+            let from = graph.node(&mut node_ids, &flow.source_owner, &flow.mint);
+            let to = graph.node(&mut node_ids, &flow.destination_owner, &flow.mint);
+            graph.edges.push(FlowEdge {
+                from,
+                to,
+                order: (order, 1),
+                sent: flow.sent,
+                received: flow.received,
+                path: Some(flow.path.clone()),
+            });
+            if let Some(venue) = flow.path.parent() {
+                venues.entry(venue).or_default().push(order);
+            }
+        }
+
+        // 同一场所中，owner先收入mint X再付出mint Y, 视为在池子内把X换成了Y
+        let mut conversions = HashSet::new();
+        for orders in venues.values() {
+            for (i, inflow_order) in orders.iter().enumerate() {
+                for outflow_order in &orders[i + 1..] {
+                    let (inflow, outflow) = (&flows[*inflow_order], &flows[*outflow_order]);
+                    if inflow.destination_owner != outflow.source_owner
+                        || inflow.mint == outflow.mint
+                    {
+                        continue;
+                    }
+                    let from = node_ids[&(inflow.destination_owner.as_str(), inflow.mint.as_str())];
+                    let to = node_ids[&(outflow.source_owner.as_str(), outflow.mint.as_str())];
+                    if conversions.insert((from, to, *outflow_order)) {
+                        graph.edges.push(FlowEdge {
+                            from,
+                            to,
+                            order: (*outflow_order, 0),
+                            sent: 0,
+                            received: 0,
+                            path: None,
+                        });
+                    }
+                }
+            }
+        }
+        graph.edges.sort_by_key(|e| e.order);
+        graph.outgoing = vec![vec![]; graph.nodes.len()];
+        for (i, edge) in graph.edges.iter().enumerate() {
+            graph.outgoing[edge.from].push(i);
+        }
+
+        graph
+    }
+
+    pub fn nodes(&self) -> &[TokenFlowNode] {
+        &self.nodes
+    }
+
+    /// 查找从满足`is_start`的节点出发、依次经过至少两种mint并回到起点的循环
+    ///
+    /// 每条转帐最多属于一个循环。搜索有上限: 边数超过`MAX_GRAPH_EDGES`时返回空，
+    /// 最多返回`MAX_CYCLES`个循环，检查的边数超过`MAX_SEARCH_STEPS`时返回已找到的循环
+    pub fn find_cycles(&self, is_start: impl Fn(&TokenFlowNode) -> bool) -> Vec<ArbitrageCycle> {
+        let mut cycles = vec![];
+        if self.edges.len() > MAX_GRAPH_EDGES {
+            return cycles;
+        }
+        let mut used = HashSet::new();
+        let mut steps = 0;
+        for (i, edge) in self.edges.iter().enumerate() {
+            if cycles.len() >= MAX_CYCLES || steps >= MAX_SEARCH_STEPS {
+                break;
+            }
+            if edge.path.is_none() || used.contains(&i) || !is_start(&self.nodes[edge.from]) {
+                continue;
+            }
+            let mut visited = vec![edge.from, edge.to];
+            let mut trail = vec![i];
+            if self.search(
+                edge.to,
+                edge.order,
+                &mut visited,
+                &mut trail,
+                &used,
+                &mut steps,
+            ) {
+                used.extend(trail.iter().copied());
+                cycles.push(self.cycle(&trail));
+            }
+        }
+
+        cycles
+    }
+
+    fn node<'a>(
+        &mut self,
+        node_ids: &mut HashMap<(&'a str, &'a str), usize>,
+        owner: &'a str,
+        mint: &'a str,
+    ) -> usize {
+        *node_ids.entry((owner, mint)).or_insert_with(|| {
+            self.nodes.push(TokenFlowNode {
+                owner: owner.to_string(),
+                mint: mint.to_string(),
+            });
+            self.nodes.len() - 1
+        })
+    }
+
+    /// 从trail末尾的节点继续搜索回到trail起点的路径
+    fn search(
+        &self,
+        node: usize,
+        after: (usize, u8),
+        visited: &mut Vec<usize>,
+        trail: &mut Vec<usize>,
+        used: &HashSet<usize>,
+        steps: &mut usize,
+    ) -> bool {
+        if trail.len() >= MAX_CYCLE_EDGES {
+            return false;
+        }
+        for &i in &self.outgoing[node] {
+            *steps += 1;
+            if *steps > MAX_SEARCH_STEPS {
+                return false;
+            }
+            let edge = &self.edges[i];
+            if edge.order <= after || used.contains(&i) {
+                continue;
+            }
+            trail.push(i);
+            if edge.to == self.edges[trail[0]].from {
+                if self.mints(trail).len() > 1 {
+                    return true;
+                }
+            } else if !visited.contains(&edge.to) {
+                visited.push(edge.to);
+                if self.search(edge.to, edge.order, visited, trail, used, steps) {
+                    return true;
+                }
+                visited.pop();
+            }
+            trail.pop();
+        }
+
+        false
+    }
+
+    fn mints(&self, trail: &[usize]) -> Vec<String> {
+        let mut mints: Vec<String> = vec![];
+        for edge in trail {
+            let mint = &self.nodes[self.edges[*edge].to].mint;
+            if !mints.contains(mint) {
+                mints.push(mint.clone());
+            }
+        }
+        mints
+    }
+
+    fn cycle(&self, trail: &[usize]) -> ArbitrageCycle {
+        let first = &self.edges[trail[0]];
+        let last = &self.edges[trail[trail.len() - 1]];
+        let start = self.nodes[first.from].clone();
+        let mut path = vec![start.clone()];
+        path.extend(trail.iter().map(|e| self.nodes[self.edges[*e].to].clone()));
+        ArbitrageCycle {
+            owner: start.owner,
+            mints: self.mints(trail),
+            instruction_paths: trail
+                .iter()
+                .filter_map(|e| self.edges[*e].path.clone())
+                .collect(),
+            input_amount: first.sent,
+            output_amount: last.received,
+            profit: last.received as i128 - first.sent as i128,
+            path,
+        }
+    }
+}
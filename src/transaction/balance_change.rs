@@ -0,0 +1,115 @@
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use solana_transaction_status_client_types::TransactionTokenBalance;
+use std::collections::BTreeMap;
+
+/// 某个owner持有的某种token在交易前后的净变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBalanceChange {
+    /// 余额信息中没有owner时使用token帐户地址
+    pub owner: String,
+    pub mint: String,
+    pub decimals: u8,
+    pub pre_amount: u128,
+    pub post_amount: u128,
+    /// 净变化，单位为最小精度
+    pub change: i128,
+}
+
+impl TokenBalanceChange {
+    /// 按精度换算后的净变化
+    pub fn ui_change(&self) -> f64 {
+        self.change as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// 单个帐户在交易前后的SOL变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolBalanceChange {
+    pub account: String,
+    pub pre_balance: u64,
+    pub post_balance: u64,
+    /// 不包括手续费的净变化，单位为lamports
+    pub change: i128,
+    /// 支付的交易手续费，只有手续费支付者(第0个帐户)不为0
+    pub fee: u64,
+}
+
+/// 计算每个(owner, mint)的token净变化，忽略没有变化的条目
+///
+/// 结果按(owner, mint)排序
+pub fn token_balance_changes(obj: &dyn TransactionPropsProvider) -> Vec<TokenBalanceChange> {
+    let Some(meta) = obj.get_meta() else {
+        return vec![];
+    };
+    let accounts = obj.get_accounts();
+    let mut changes: BTreeMap<(String, String), TokenBalanceChange> = BTreeMap::new();
+    let mut apply = |balance: &TransactionTokenBalance, is_pre: bool| {
+        let owner = if balance.owner.is_empty() {
+            match accounts.get(balance.account_index as usize) {
+                Some(account) => account.clone(),
+                None => return,
+            }
+        } else {
+            balance.owner.clone()
+        };
+        let amount = balance.ui_token_amount.amount.parse::<u128>().unwrap_or(0);
+        let change = changes
+            .entry((owner.clone(), balance.mint.clone()))
+            .or_insert_with(|| TokenBalanceChange {
+                owner,
+                mint: balance.mint.clone(),
+                decimals: balance.ui_token_amount.decimals,
+                pre_amount: 0,
+                post_amount: 0,
+                change: 0,
+            });
+        if is_pre {
+            change.pre_amount += amount;
+        } else {
+            change.post_amount += amount;
+        }
+    };
+    for balance in meta.pre_token_balances.unwrap_or_default() {
+        apply(balance, true);
+    }
+    for balance in meta.post_token_balances.unwrap_or_default() {
+        apply(balance, false);
+    }
+
+    changes
+        .into_values()
+        .filter_map(|mut change| {
+            change.change = change.post_amount as i128 - change.pre_amount as i128;
+            (change.change != 0).then_some(change)
+        })
+        .collect()
+}
+
+/// 计算每个帐户的SOL变化，忽略没有变化的帐户
+///
+/// 手续费从手续费支付者的变化中剔除，单独记录在`fee`中
+pub fn sol_balance_changes(obj: &dyn TransactionPropsProvider) -> Vec<SolBalanceChange> {
+    let Some(meta) = obj.get_meta() else {
+        return vec![];
+    };
+    let accounts = obj.get_accounts();
+    meta.pre_balances
+        .iter()
+        .zip(meta.post_balances)
+        .enumerate()
+        .filter_map(|(index, (pre, post))| {
+            let fee = if index == 0 { meta.fee } else { 0 };
+            let change = *post as i128 - *pre as i128 + fee as i128;
+            if change == 0 && fee == 0 {
+                return None;
+            }
+            Some(SolBalanceChange {
+                account: accounts.get(index)?.clone(),
+                pre_balance: *pre,
+                post_balance: *post,
+                change,
+                fee,
+            })
+        })
+        .collect()
+}
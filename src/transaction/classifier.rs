@@ -0,0 +1,611 @@
+use crate::asset_flow::{AssetFlowKind, FlowAsset, extract_asset_flows};
+use crate::events::builtin::{METEORA_DLMM_PROGRAM, PUMP_FUN_PROGRAM, RAYDIUM_CLMM_PROGRAM};
+use crate::instructions::bpf_loader_upgradeable::UpgradeableLoaderInstruction;
+use crate::instructions::spl_token::TokenInstruction as SplTokenInstruction;
+use crate::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use crate::parsed_instruction::instruction_iter::{InstructionIter, InstructionPath};
+use crate::parsed_instruction::{ParsedInstruction, ParsedInstructionData};
+use crate::program_log::{LogEntry, ProgramLogs};
+use crate::pubkeys::{
+    ASSOCIATED_TOKEN_PROGRAM, METEORA_POOLS_PROGRAM, ORCA_WHIRLPOOL_PROGRAM,
+    RAYDIUM_AMM_V4_PROGRAM, RAYDIUM_CPMM_PROGRAM, TOKEN_METADATA_PROGRAM,
+};
+use crate::transaction::arbitrage::detect_arbitrage_cycles;
+use crate::transaction::failure::{ErrorRegistry, diagnose_failure};
+use crate::transaction::swap_detector::SwapDetector;
+use crate::transaction::token_flow::{signer_accounts, token_flows};
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use crate::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 创建这么多个不属于签名者的ATA时视为刷帐户
+const ATA_SPAM_THRESHOLD: usize = 5;
+
+/// 各程序创建池子(或bonding curve)的Anchor指令名
+const POOL_INIT_INSTRUCTIONS: [(&str, &[&str]); 6] = [
+    (PUMP_FUN_PROGRAM, &["create"]),
+    (RAYDIUM_CLMM_PROGRAM, &["create_pool"]),
+    (RAYDIUM_CPMM_PROGRAM, &["initialize"]),
+    (
+        METEORA_DLMM_PROGRAM,
+        &[
+            "initialize_lb_pair",
+            "initialize_permission_lb_pair",
+            "initialize_customizable_permissionless_lb_pair",
+        ],
+    ),
+    (
+        METEORA_POOLS_PROGRAM,
+        &[
+            "initialize_permissionless_pool",
+            "initialize_permissionless_pool_with_fee_tier",
+            "initialize_customizable_permissionless_constant_product_pool",
+        ],
+    ),
+    (
+        ORCA_WHIRLPOOL_PROGRAM,
+        &["initialize_pool", "initialize_pool_v2"],
+    ),
+];
+
+/// Raydium AMM v4不是Anchor程序，创建池子的Initialize2指令tag为1
+const RAYDIUM_AMM_V4_INITIALIZE2: u8 = 1;
+
+/// 交易的类别，声明顺序即作为单一标签时的优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TransactionCategory {
+    Failed,
+    Vote,
+    ProgramDeploy,
+    TokenLaunch,
+    NftMint,
+    Arbitrage,
+    LiquidityAdd,
+    LiquidityRemove,
+    Swap,
+    StakeAction,
+    NftTransfer,
+    AtaCreationSpam,
+    TokenTransfer,
+    SolTransfer,
+}
+
+/// 支持分类结果的证据
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evidence {
+    /// 相关的指令，来自余额变化等整体信息时为None
+    pub path: Option<InstructionPath>,
+    pub description: String,
+}
+
+/// 一个分类结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    pub category: TransactionCategory,
+    /// 0到1之间，取所有证据中最高的置信度
+    pub confidence: f32,
+    pub evidence: Vec<Evidence>,
+}
+
+/// 收集各类别的证据
+#[derive(Default)]
+struct Classifications(BTreeMap<TransactionCategory, Classification>);
+
+impl Classifications {
+    fn add(
+        &mut self,
+        category: TransactionCategory,
+        confidence: f32,
+        path: Option<InstructionPath>,
+        description: String,
+    ) {
+        let classification = self.0.entry(category).or_insert(Classification {
+            category,
+            confidence: 0.0,
+            evidence: vec![],
+        });
+        classification.confidence = classification.confidence.max(confidence);
+        classification.evidence.push(Evidence { path, description });
+    }
+}
+
+/// 初始化的mint
+struct MintInit {
+    path: InstructionPath,
+    mint: String,
+    decimals: u8,
+}
+
+/// 创建池子的指令
+struct PoolInit {
+    path: InstructionPath,
+    program_id: String,
+    instruction: &'static str,
+    accounts: Vec<String>,
+}
+
+/// 基于启发式规则的交易分类器
+pub struct TransactionClassifier {
+    swap_detector: SwapDetector,
+    error_registry: ErrorRegistry,
+    min_confidence: f32,
+}
+
+impl Default for TransactionClassifier {
+    fn default() -> Self {
+        Self {
+            swap_detector: SwapDetector::with_builtin(),
+            error_registry: ErrorRegistry::with_builtin(),
+            min_confidence: 0.5,
+        }
+    }
+}
+
+impl TransactionClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_swap_detector(mut self, swap_detector: SwapDetector) -> Self {
+        self.swap_detector = swap_detector;
+        self
+    }
+
+    pub fn with_error_registry(mut self, error_registry: ErrorRegistry) -> Self {
+        self.error_registry = error_registry;
+        self
+    }
+
+    /// 低于此置信度的分类不会出现在结果中，默认0.5
+    ///
+    /// 只有间接证据的分类(如内部指令中的转帐)置信度低于默认值
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// 交易的所有分类，按优先级排序
+    pub fn classify(&self, obj: &dyn TransactionPropsProvider) -> Vec<Classification> {
+        let mut classifications = Classifications::default();
+        if let Some(failure) = diagnose_failure(obj, &self.error_registry) {
+            classifications.add(
+                TransactionCategory::Failed,
+                1.0,
+                Some(failure.path.clone()),
+                failure.to_string(),
+            );
+        } else if obj.get_meta().is_some_and(|meta| meta.err.is_some()) {
+            classifications.add(
+                TransactionCategory::Failed,
+                1.0,
+                None,
+                "transaction failed".to_string(),
+            );
+        }
+
+        // 失败交易的指令没有生效，只保留投票等不依赖执行结果的类别
+        let failed = !classifications.0.is_empty();
+        self.classify_instructions(obj, failed, &mut classifications);
+        if !failed {
+            self.classify_flows(obj, &mut classifications);
+        }
+
+        classifications
+            .0
+            .into_values()
+            .filter(|c| c.confidence >= self.min_confidence)
+            .collect()
+    }
+
+    /// 优先级最高的分类，用作交易的单一标签
+    pub fn label(&self, obj: &dyn TransactionPropsProvider) -> Option<Classification> {
+        self.classify(obj).into_iter().next()
+    }
+
+    fn classify_instructions(
+        &self,
+        obj: &dyn TransactionPropsProvider,
+        failed: bool,
+        classifications: &mut Classifications,
+    ) {
+        let Some(instructions) = obj.get_parsed_instructions() else {
+            return;
+        };
+        let accounts = obj.get_accounts();
+        let signers = signer_accounts(obj);
+
+        let program_logs = obj
+            .get_meta()
+            .and_then(|meta| meta.log_messages)
+            .map(ProgramLogs::parse)
+            .unwrap_or_default();
+
+        let mut mint_inits = vec![];
+        let mut mint_to = vec![];
+        let mut pool_inits = vec![];
+        let mut has_metadata = false;
+        let mut spam_atas = vec![];
+        for node in InstructionIter::new(instructions) {
+            let Some(program_id) = program_id(node.instruction, &accounts) else {
+                continue;
+            };
+            if program_id == solana_sdk::vote::program::id() {
+                classifications.add(
+                    TransactionCategory::Vote,
+                    1.0,
+                    Some(node.path.clone()),
+                    "vote program instruction".to_string(),
+                );
+            }
+            if failed {
+                continue;
+            }
+            if program_id == solana_sdk::stake::program::id() {
+                classifications.add(
+                    TransactionCategory::StakeAction,
+                    0.95,
+                    Some(node.path.clone()),
+                    "stake program instruction".to_string(),
+                );
+            }
+            if let Some((confidence, description)) = loader_instruction(node.instruction) {
+                classifications.add(
+                    TransactionCategory::ProgramDeploy,
+                    confidence,
+                    Some(node.path.clone()),
+                    description.to_string(),
+                );
+            }
+
+            let program_id = program_id.to_string();
+            match program_id.as_str() {
+                ASSOCIATED_TOKEN_PROGRAM => {
+                    // 帐户: 0.付款人 1.ATA 2.钱包 3.mint
+                    let wallet = node
+                        .instruction
+                        .accounts
+                        .get(2)
+                        .and_then(|index| accounts.get(*index as usize));
+                    if wallet.is_some_and(|wallet| !signers.contains(wallet)) {
+                        spam_atas.push(node.path.clone());
+                    }
+                }
+                TOKEN_METADATA_PROGRAM => has_metadata = true,
+                _ => {
+                    // 日志中的调用与指令对应时才使用其日志
+                    let logs = program_logs
+                        .get(&node.path)
+                        .filter(|invocation| invocation.program_id == program_id)
+                        .map(|invocation| invocation.logs.as_slice())
+                        .unwrap_or_default();
+                    if let Some(instruction) =
+                        pool_init_instruction(&program_id, node.instruction, logs)
+                    {
+                        pool_inits.push(PoolInit {
+                            path: node.path.clone(),
+                            accounts: node
+                                .instruction
+                                .accounts
+                                .iter()
+                                .filter_map(|index| accounts.get(*index as usize).cloned())
+                                .collect(),
+                            program_id,
+                            instruction,
+                        });
+                    }
+                }
+            }
+
+            let (decimals, amount) = match &node.instruction.instruction_data {
+                ParsedInstructionData::SplToken(
+                    SplTokenInstruction::InitializeMint { decimals, .. }
+                    | SplTokenInstruction::InitializeMint2 { decimals, .. },
+                )
+                | ParsedInstructionData::SplToken2022(
+                    SplToken2022Instruction::InitializeMint { decimals, .. }
+                    | SplToken2022Instruction::InitializeMint2 { decimals, .. },
+                ) => (Some(*decimals), None),
+                ParsedInstructionData::SplToken(
+                    SplTokenInstruction::MintTo { amount }
+                    | SplTokenInstruction::MintToChecked { amount, .. },
+                )
+                | ParsedInstructionData::SplToken2022(
+                    SplToken2022Instruction::MintTo { amount }
+                    | SplToken2022Instruction::MintToChecked { amount, .. },
+                ) => (None, Some(*amount)),
+                _ => continue,
+            };
+            // InitializeMint和MintTo的第0个帐户都是mint
+            let Some(mint) = node
+                .instruction
+                .accounts
+                .first()
+                .and_then(|index| accounts.get(*index as usize))
+                .cloned()
+            else {
+                continue;
+            };
+            match (decimals, amount) {
+                (Some(decimals), _) => mint_inits.push(MintInit {
+                    path: node.path.clone(),
+                    mint,
+                    decimals,
+                }),
+                (_, Some(amount)) => mint_to.push((node.path.clone(), mint, amount)),
+                _ => {}
+            }
+        }
+
+        if spam_atas.len() >= ATA_SPAM_THRESHOLD {
+            let confidence = (0.5 + 0.05 * spam_atas.len() as f32).min(0.95);
+            for path in spam_atas {
+                classifications.add(
+                    TransactionCategory::AtaCreationSpam,
+                    confidence,
+                    Some(path),
+                    "creates associated token account for a non-signer wallet".to_string(),
+                );
+            }
+        }
+
+        for init in &mint_inits {
+            let minted = mint_to
+                .iter()
+                .filter(|(_, mint, _)| *mint == init.mint)
+                .collect::<Vec<_>>();
+            if init.decimals == 0 && minted.iter().any(|(_, _, amount)| *amount == 1) {
+                let confidence = if has_metadata { 0.95 } else { 0.85 };
+                classifications.add(
+                    TransactionCategory::NftMint,
+                    confidence,
+                    Some(init.path.clone()),
+                    format!("initializes mint {} with 0 decimals and mints 1", init.mint),
+                );
+                continue;
+            }
+
+            // 发射代币需要同时创建池子，只初始化mint不算
+            if pool_inits.is_empty() {
+                continue;
+            }
+            // 池子的帐户中包含该mint时可以确定是同一个代币
+            let related: Vec<_> = pool_inits
+                .iter()
+                .filter(|pool| pool.accounts.contains(&init.mint))
+                .collect();
+            let (confidence, pools) = if related.is_empty() {
+                (0.7, pool_inits.iter().collect())
+            } else {
+                (0.95, related)
+            };
+            classifications.add(
+                TransactionCategory::TokenLaunch,
+                confidence,
+                Some(init.path.clone()),
+                format!(
+                    "initializes mint {} with {} decimals",
+                    init.mint, init.decimals
+                ),
+            );
+            for pool in pools {
+                classifications.add(
+                    TransactionCategory::TokenLaunch,
+                    confidence,
+                    Some(pool.path.clone()),
+                    format!("creates pool with {} {}", pool.program_id, pool.instruction),
+                );
+            }
+        }
+    }
+
+    fn classify_flows(
+        &self,
+        obj: &dyn TransactionPropsProvider,
+        classifications: &mut Classifications,
+    ) {
+        let Some(instructions) = obj.get_parsed_instructions() else {
+            return;
+        };
+        let accounts = obj.get_accounts();
+        let signers = signer_accounts(obj);
+        let decimals = mint_decimals(obj);
+
+        let mut mint_to_depth = false;
+        let mut burned = false;
+        for flow in extract_asset_flows(instructions, &accounts) {
+            // 内部的转帐通常是其他操作(如兑换)的一部分，置信度低于默认阈值
+            let confidence = if flow.path.depth() == 0 { 0.9 } else { 0.3 };
+            match (flow.kind, &flow.asset) {
+                (AssetFlowKind::Transfer | AssetFlowKind::TransferWithFee, FlowAsset::Sol) => {
+                    classifications.add(
+                        TransactionCategory::SolTransfer,
+                        confidence,
+                        Some(flow.path.clone()),
+                        format!("transfers {} lamports", flow.amount.unwrap_or_default()),
+                    )
+                }
+                (
+                    AssetFlowKind::Transfer | AssetFlowKind::TransferWithFee,
+                    FlowAsset::Token { .. },
+                ) => classifications.add(
+                    TransactionCategory::TokenTransfer,
+                    confidence,
+                    Some(flow.path.clone()),
+                    format!("transfers {} tokens", flow.amount.unwrap_or_default()),
+                ),
+                (AssetFlowKind::MintTo, _) if flow.path.depth() > 0 => mint_to_depth = true,
+                (AssetFlowKind::Burn, _) => burned = true,
+                _ => {}
+            }
+        }
+
+        for flow in token_flows(obj) {
+            if flow.received == 1 && decimals.get(&flow.mint) == Some(&0) {
+                classifications.add(
+                    TransactionCategory::NftTransfer,
+                    0.8,
+                    Some(flow.path.clone()),
+                    format!(
+                        "transfers 1 of 0-decimal mint {} to {}",
+                        flow.mint, flow.destination_owner
+                    ),
+                );
+            }
+        }
+
+        let swaps = self.swap_detector.detect(obj);
+        for swap in &swaps {
+            classifications.add(
+                TransactionCategory::Swap,
+                0.85,
+                Some(swap.instruction_path.clone()),
+                format!(
+                    "swaps {} {} for {} {}",
+                    swap.input_amount, swap.input_mint, swap.output_amount, swap.output_mint
+                ),
+            );
+        }
+
+        for cycle in detect_arbitrage_cycles(obj) {
+            classifications.add(
+                TransactionCategory::Arbitrage,
+                if cycle.profit > 0 { 0.95 } else { 0.7 },
+                cycle.instruction_paths.first().cloned(),
+                format!(
+                    "cycle through {} mints starting with {}, profit {}",
+                    cycle.mints.len(),
+                    cycle.mints.first().map(String::as_str).unwrap_or_default(),
+                    cycle.profit
+                ),
+            );
+        }
+
+        // 同时付出两种token并得到LP token视为添加流动性，反之为移除流动性
+        let mut sent = HashSet::new();
+        let mut received = HashSet::new();
+        for flow in token_flows(obj) {
+            let from_signer = signers.contains(&flow.source_owner);
+            let to_signer = signers.contains(&flow.destination_owner);
+            if from_signer && !to_signer {
+                sent.insert(flow.mint);
+            } else if to_signer && !from_signer {
+                received.insert(flow.mint);
+            }
+        }
+        if swaps.is_empty() && sent.len() >= 2 && received.len() <= 1 {
+            classifications.add(
+                TransactionCategory::LiquidityAdd,
+                if mint_to_depth { 0.8 } else { 0.4 },
+                None,
+                format!("signer deposits {} different tokens", sent.len()),
+            );
+        }
+        if swaps.is_empty() && received.len() >= 2 && sent.len() <= 1 {
+            classifications.add(
+                TransactionCategory::LiquidityRemove,
+                if burned { 0.8 } else { 0.4 },
+                None,
+                format!("signer withdraws {} different tokens", received.len()),
+            );
+        }
+    }
+}
+
+fn program_id(
+    instruction: &ParsedInstruction,
+    accounts: &TransactionAccounts<'_, String>,
+) -> Option<Pubkey> {
+    instruction.program_id.or_else(|| {
+        accounts
+            .get(instruction.program_id_index as usize)?
+            .parse()
+            .ok()
+    })
+}
+
+/// BPF Upgradeable Loader的部署相关指令，使用解析时解码的指令数据
+fn loader_instruction(instruction: &ParsedInstruction) -> Option<(f32, &'static str)> {
+    let ParsedInstructionData::BpfLoaderUpgradeable(loader_instruction) =
+        &instruction.instruction_data
+    else {
+        return None;
+    };
+    match loader_instruction {
+        UpgradeableLoaderInstruction::InitializeBuffer => Some((0.6, "initializes program buffer")),
+        UpgradeableLoaderInstruction::Write { .. } => Some((0.6, "writes program buffer")),
+        UpgradeableLoaderInstruction::DeployWithMaxDataLen { .. } => {
+            Some((0.95, "deploys program"))
+        }
+        UpgradeableLoaderInstruction::Upgrade => Some((0.95, "upgrades program")),
+        UpgradeableLoaderInstruction::ExtendProgram { .. }
+        | UpgradeableLoaderInstruction::ExtendProgramChecked { .. } => {
+            Some((0.8, "extends program data"))
+        }
+        _ => None,
+    }
+}
+
+/// 创建池子的指令名，不是创建池子的指令时返回None
+///
+/// 保留了原始数据时按Anchor的指令discriminator判断，否则根据该调用的日志中的指令名判断
+fn pool_init_instruction(
+    program_id: &str,
+    instruction: &ParsedInstruction,
+    logs: &[LogEntry],
+) -> Option<&'static str> {
+    let data = instruction.raw_data.as_deref();
+    if program_id == RAYDIUM_AMM_V4_PROGRAM {
+        let is_initialize2 = data.and_then(|data| data.first())
+            == Some(&RAYDIUM_AMM_V4_INITIALIZE2)
+            || logs
+                .iter()
+                .any(|log| matches!(log, LogEntry::Log(log) if log.starts_with("initialize2")));
+        return is_initialize2.then_some("initialize2");
+    }
+
+    let (_, names) = POOL_INIT_INSTRUCTIONS
+        .iter()
+        .find(|(pool_program, _)| *pool_program == program_id)?;
+    names.iter().copied().find(|name| {
+        data.is_some_and(|data| data.starts_with(&anchor_discriminator(name)))
+            || logs.contains(&LogEntry::Log(format!(
+                "Instruction: {}",
+                pascal_case(name)
+            )))
+    })
+}
+
+/// Anchor指令的discriminator，为`sha256("global:<name>")`的前8字节
+fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let hash = solana_sdk::hash::hashv(&[b"global:", name.as_bytes()]);
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+/// Anchor日志中的指令名为PascalCase，如`create_pool`输出为`Instruction: CreatePool`
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// 交易前后token余额中各mint的精度
+fn mint_decimals(obj: &dyn TransactionPropsProvider) -> HashMap<String, u8> {
+    let Some(meta) = obj.get_meta() else {
+        return HashMap::new();
+    };
+    meta.pre_token_balances
+        .unwrap_or_default()
+        .iter()
+        .chain(meta.post_token_balances.unwrap_or_default())
+        .map(|b| (b.mint.clone(), b.ui_token_amount.decimals))
+        .collect()
+}
@@ -0,0 +1,233 @@
+use crate::parsed_instruction::diagnostics::ParseTransactionError;
+use crate::parsed_instruction::{ParsedInstruction, ParsedInstructionList};
+use crate::transaction::transaction_filter::{TransactionMeta, TransactionPropsProvider};
+use crate::utils::TransactionAccounts;
+use solana_sdk::message::MessageHeader;
+use solana_transaction_error::TransactionResult;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, ParsedAccount, ParsedAccountSource,
+    TransactionTokenBalance, UiMessage, UiParsedMessage, UiTransaction, UiTransactionTokenBalance,
+};
+use tracing::error;
+
+/// RPC返回的编码交易，已解析好帐户、指令和meta数据
+///
+/// 实现了[TransactionPropsProvider]，可以直接交给交易过滤器和各种分析使用
+#[derive(Debug, Clone)]
+pub struct EncodedTransactionProps {
+    pub signatures: Vec<String>,
+    pub parsed_instructions: ParsedInstructionList,
+    account_keys: Vec<String>,
+    loaded_writable_accounts: Vec<String>,
+    loaded_readonly_accounts: Vec<String>,
+    header: MessageHeader,
+    status: TransactionResult<()>,
+    fee: u64,
+    pre_balances: Vec<u64>,
+    post_balances: Vec<u64>,
+    pre_token_balances: Option<Vec<TransactionTokenBalance>>,
+    post_token_balances: Option<Vec<TransactionTokenBalance>>,
+    log_messages: Option<Vec<String>>,
+    compute_units_consumed: Option<u64>,
+}
+
+impl TryFrom<&EncodedTransactionWithStatusMeta> for EncodedTransactionProps {
+    type Error = ParseTransactionError;
+
+    fn try_from(value: &EncodedTransactionWithStatusMeta) -> Result<Self, Self::Error> {
+        let meta = value
+            .meta
+            .as_ref()
+            .ok_or(ParseTransactionError::MissingMeta)?;
+        let (signatures, account_keys, header) = match &value.transaction {
+            EncodedTransaction::Json(t) => {
+                let (account_keys, header) = match &t.message {
+                    UiMessage::Raw(raw) => (raw.account_keys.clone(), raw.header),
+                    UiMessage::Parsed(parsed) => (
+                        parsed
+                            .account_keys
+                            .iter()
+                            .filter(|a| !is_lookup_account(a.source.as_ref()))
+                            .map(|a| a.pubkey.clone())
+                            .collect(),
+                        parsed_message_header(parsed),
+                    ),
+                };
+                (t.signatures.clone(), account_keys, header)
+            }
+            EncodedTransaction::LegacyBinary(_) | EncodedTransaction::Binary(_, _) => {
+                let transaction = value
+                    .transaction
+                    .decode()
+                    .ok_or(ParseTransactionError::DecodeTransaction)?;
+                (
+                    transaction
+                        .signatures
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                    transaction
+                        .message
+                        .static_account_keys()
+                        .iter()
+                        .map(|k| k.to_string())
+                        .collect(),
+                    *transaction.message.header(),
+                )
+            }
+            EncodedTransaction::Accounts(_) => {
+                return Err(ParseTransactionError::UnsupportedEncoding("accounts"));
+            }
+        };
+        let (loaded_writable_accounts, loaded_readonly_accounts) =
+            match (&value.transaction, &meta.loaded_addresses) {
+                // jsonParsed格式的帐户列表已经包含了通过地址查找表加载的帐户，并标明了来源
+                (
+                    EncodedTransaction::Json(UiTransaction {
+                        message: UiMessage::Parsed(parsed),
+                        ..
+                    }),
+                    loaded_addresses,
+                ) => {
+                    let lookup_accounts: Vec<_> = parsed
+                        .account_keys
+                        .iter()
+                        .filter(|a| is_lookup_account(a.source.as_ref()))
+                        .collect();
+                    // 优先使用meta中的加载地址数量，否则只能根据降级后的可写标记推断
+                    let writable_num = match loaded_addresses {
+                        OptionSerializer::Some(loaded) => loaded.writable.len(),
+                        _ => writable_prefix_len(&lookup_accounts),
+                    };
+                    let (writable, readonly) =
+                        lookup_accounts.split_at(writable_num.min(lookup_accounts.len()));
+                    (
+                        writable.iter().map(|a| a.pubkey.clone()).collect(),
+                        readonly.iter().map(|a| a.pubkey.clone()).collect(),
+                    )
+                }
+                (_, OptionSerializer::Some(loaded)) => {
+                    (loaded.writable.clone(), loaded.readonly.clone())
+                }
+                _ => (vec![], vec![]),
+            };
+
+        let report = ParsedInstructionList::try_from_encoded(value)?;
+        for diagnostic in &report.diagnostics {
+            error!("{diagnostic}");
+        }
+
+        Ok(Self {
+            signatures,
+            parsed_instructions: report.instructions,
+            account_keys,
+            loaded_writable_accounts,
+            loaded_readonly_accounts,
+            header,
+            status: meta.status.clone(),
+            fee: meta.fee,
+            pre_balances: meta.pre_balances.clone(),
+            post_balances: meta.post_balances.clone(),
+            pre_token_balances: token_balances(&meta.pre_token_balances),
+            post_token_balances: token_balances(&meta.post_token_balances),
+            log_messages: meta.log_messages.clone().into(),
+            compute_units_consumed: meta.compute_units_consumed.clone().into(),
+        })
+    }
+}
+
+impl TransactionPropsProvider for EncodedTransactionProps {
+    fn get_accounts(&self) -> TransactionAccounts<'_, String> {
+        TransactionAccounts::from_accounts(
+            Some(self.account_keys.as_slice()),
+            Some(self.loaded_writable_accounts.as_slice()),
+            Some(self.loaded_readonly_accounts.as_slice()),
+        )
+    }
+
+    fn get_signatures(&self) -> Option<&[String]> {
+        Some(self.signatures.as_slice())
+    }
+
+    fn get_parsed_instructions(&self) -> Option<&[ParsedInstruction]> {
+        Some(self.parsed_instructions.as_slice())
+    }
+
+    fn get_meta(&self) -> Option<TransactionMeta<'_>> {
+        Some(
+            TransactionMeta::new(
+                &self.status,
+                self.fee,
+                self.pre_balances.as_slice(),
+                self.post_balances.as_slice(),
+            )
+            .with_token_balances(
+                self.pre_token_balances.as_deref(),
+                self.post_token_balances.as_deref(),
+            )
+            .with_log_messages(self.log_messages.as_deref())
+            .with_compute_units_consumed(self.compute_units_consumed),
+        )
+    }
+
+    /// jsonParsed格式没有消息头，返回的是根据帐户列表还原的结果，见`parsed_message_header`
+    fn get_message_header(&self) -> Option<MessageHeader> {
+        Some(self.header)
+    }
+}
+
+/// UI格式的token余额转换成原生格式，缺少的owner/program_id使用空字符串
+fn token_balances(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+) -> Option<Vec<TransactionTokenBalance>> {
+    match balances {
+        OptionSerializer::Some(balances) => Some(
+            balances
+                .iter()
+                .map(|b| TransactionTokenBalance {
+                    account_index: b.account_index,
+                    mint: b.mint.clone(),
+                    ui_token_amount: b.ui_token_amount.clone(),
+                    owner: Option::<String>::from(b.owner.clone()).unwrap_or_default(),
+                    program_id: Option::<String>::from(b.program_id.clone()).unwrap_or_default(),
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn is_lookup_account(source: Option<&ParsedAccountSource>) -> bool {
+    matches!(source, Some(ParsedAccountSource::LookupTable))
+}
+
+/// jsonParsed格式没有消息头，根据静态帐户的签名和可写标记还原
+///
+/// jsonParsed中的可写标记是运行时降级之后的结果，被调用的程序和sysvar即使声明为可写也标记为只读，
+/// 所以不能直接按标记计数，而是按帐户的排列顺序(签名可写、签名只读、非签名可写、非签名只读)
+/// 取每个区域中最后一个可写帐户作为边界。
+/// 区域末尾的帐户被降级时无法与只读帐户区分，此时还原出的只读帐户数会偏多
+fn parsed_message_header(message: &UiParsedMessage) -> MessageHeader {
+    let static_accounts: Vec<_> = message
+        .account_keys
+        .iter()
+        .filter(|a| !is_lookup_account(a.source.as_ref()))
+        .collect();
+    let num_signers = static_accounts.iter().take_while(|a| a.signer).count();
+    let (signers, unsigned) = static_accounts.split_at(num_signers);
+
+    MessageHeader {
+        num_required_signatures: num_signers as u8,
+        num_readonly_signed_accounts: (signers.len() - writable_prefix_len(signers)) as u8,
+        num_readonly_unsigned_accounts: (unsigned.len() - writable_prefix_len(unsigned)) as u8,
+    }
+}
+
+/// 可写帐户排在只读帐户之前，最后一个标记为可写的帐户及之前的帐户都是声明为可写的
+fn writable_prefix_len(accounts: &[&ParsedAccount]) -> usize {
+    accounts
+        .iter()
+        .rposition(|a| a.writable)
+        .map_or(0, |i| i + 1)
+}
@@ -0,0 +1,330 @@
+mod dex_errors;
+
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::program_log::{InvocationStatus, LogEntry, ProgramLogs};
+use crate::transaction::failure::dex_errors::DEX_ERRORS;
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use num_traits::FromPrimitive;
+use serde_json::Value;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// 附带的出错程序日志的最大条数
+const MAX_FAILURE_LOGS: usize = 5;
+
+/// 交易失败的诊断信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureDiagnostics {
+    /// 出错的顶层指令索引
+    pub instruction_index: u8,
+    /// 实际出错的(可能是CPI中的)指令路径，日志缺失时为顶层指令
+    pub path: InstructionPath,
+    pub program_id: Option<String>,
+    pub program_name: Option<String>,
+    pub error: InstructionError,
+    /// 错误名，未知的自定义错误为None
+    pub error_name: Option<String>,
+    /// 出错程序没有注册，`error_name`是按Anchor框架错误码猜测的
+    pub error_name_guessed: bool,
+    /// 出错程序最后输出的日志
+    pub logs: Vec<String>,
+}
+
+impl Display for FailureDiagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.error_name {
+            Some(name) if self.error_name_guessed => write!(f, "{name}(?)")?,
+            Some(name) => write!(f, "{name}")?,
+            None => write!(f, "{:?}", self.error)?,
+        }
+        match (&self.program_name, &self.program_id) {
+            (Some(name), _) => write!(f, " in {name}"),
+            (None, Some(program_id)) => write!(f, " in {program_id}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// 程序的名称及其自定义错误码
+#[derive(Debug, Clone, Default)]
+struct ProgramErrors {
+    name: Option<String>,
+    /// Anchor程序，自定义错误码之外还可能返回Anchor框架错误码
+    anchor: bool,
+    codes: HashMap<u32, String>,
+}
+
+/// 以程序id为键的自定义错误码注册表
+#[derive(Debug, Clone, Default)]
+pub struct ErrorRegistry {
+    programs: HashMap<String, ProgramErrors>,
+}
+
+impl ErrorRegistry {
+    /// 空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 包含System, SPL Token, Token-2022以及常见DEX错误码的注册表
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        let system = solana_sdk::system_program::id().to_string();
+        registry.register_program_name(&system, "System Program");
+        for (code, name) in SYSTEM_ERRORS.iter().enumerate() {
+            registry.register(&system, code as u32, *name);
+        }
+
+        let token = spl_token::ID.to_string();
+        registry.register_program_name(&token, "Token Program");
+        for code in 0..u8::MAX as u32 {
+            if let Some(error) = spl_token::error::TokenError::from_u32(code) {
+                registry.register(&token, code, format!("{error:?}"));
+            }
+        }
+        let token_2022 = spl_token_2022::ID.to_string();
+        registry.register_program_name(&token_2022, "Token-2022 Program");
+        for code in 0..u8::MAX as u32 {
+            if let Some(error) = spl_token_2022::error::TokenError::from_u32(code) {
+                registry.register(&token_2022, code, format!("{error:?}"));
+            }
+        }
+
+        for dex in DEX_ERRORS {
+            registry.register_program_name(dex.program_id, dex.name);
+            if dex.anchor {
+                registry.register_anchor_program(dex.program_id);
+            }
+            for (code, name) in dex.codes {
+                registry.register(dex.program_id, *code, *name);
+            }
+        }
+
+        registry
+    }
+
+    pub fn register_program_name(&mut self, program_id: &str, name: impl Into<String>) {
+        self.programs
+            .entry(program_id.to_string())
+            .or_default()
+            .name = Some(name.into());
+    }
+
+    /// 标记为Anchor程序，查找错误名时包括Anchor框架错误码
+    pub fn register_anchor_program(&mut self, program_id: &str) {
+        self.programs
+            .entry(program_id.to_string())
+            .or_default()
+            .anchor = true;
+    }
+
+    pub fn register(&mut self, program_id: &str, code: u32, name: impl Into<String>) {
+        self.programs
+            .entry(program_id.to_string())
+            .or_default()
+            .codes
+            .insert(code, name.into());
+    }
+
+    /// 注册Anchor IDL中`errors`定义的错误码，并标记为Anchor程序
+    pub fn register_idl_errors(&mut self, program_id: &str, idl: &Value) {
+        self.register_anchor_program(program_id);
+        for error in idl
+            .get("errors")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let code = error.get("code").and_then(Value::as_u64);
+            let name = error.get("name").and_then(Value::as_str);
+            if let (Some(code), Some(name)) = (code, name) {
+                self.register(program_id, code as u32, name);
+            }
+        }
+    }
+
+    pub fn program_name(&self, program_id: &str) -> Option<&str> {
+        self.programs.get(program_id)?.name.as_deref()
+    }
+
+    /// 查找自定义错误名，Anchor程序还会按Anchor框架错误码查找
+    ///
+    /// 没有注册的程序返回None, 可以用[Self::guess_error_name]猜测
+    pub fn error_name(&self, program_id: Option<&str>, code: u32) -> Option<&str> {
+        let program = self.programs.get(program_id?)?;
+        if let Some(name) = program.codes.get(&code) {
+            return Some(name);
+        }
+        if program.anchor {
+            anchor_error_name(code)
+        } else {
+            None
+        }
+    }
+
+    /// 没有注册的程序按Anchor框架错误码猜测错误名，结果不一定正确
+    pub fn guess_error_name(&self, program_id: Option<&str>, code: u32) -> Option<&'static str> {
+        if program_id.is_some_and(|p| self.programs.contains_key(p)) {
+            return None;
+        }
+        anchor_error_name(code)
+    }
+}
+
+/// 诊断交易失败的原因，交易成功或错误不属于某条指令时返回None
+pub fn diagnose_failure(
+    obj: &dyn TransactionPropsProvider,
+    registry: &ErrorRegistry,
+) -> Option<FailureDiagnostics> {
+    let meta = obj.get_meta()?;
+    let TransactionError::InstructionError(instruction_index, error) = meta.err? else {
+        return None;
+    };
+    let accounts = obj.get_accounts();
+    let logs = ProgramLogs::parse(meta.log_messages.unwrap_or_default());
+
+    // 错误由最深一层失败的调用产生，CPI中的错误会原样向上传递
+    let top_level = InstructionPath(vec![*instruction_index as usize]);
+    let failed = logs
+        .invocations
+        .iter()
+        .filter(|i| i.path == top_level || top_level.is_ancestor_of(&i.path))
+        .filter(|i| matches!(i.status, InvocationStatus::Failed(_)))
+        .max_by_key(|i| i.path.0.len());
+    let (path, program_id) = match failed {
+        Some(invocation) => (invocation.path.clone(), Some(invocation.program_id.clone())),
+        None => {
+            let program_id =
+                obj.get_parsed_instructions()
+                    .and_then(|instructions| top_level.resolve(instructions))
+                    .and_then(|instruction| {
+                        instruction.program_id.map(|p| p.to_string()).or_else(|| {
+                            accounts.get(instruction.program_id_index as usize).cloned()
+                        })
+                    });
+            (top_level, program_id)
+        }
+    };
+
+    let (error_name, error_name_guessed) = match error {
+        InstructionError::Custom(code) => match registry.error_name(program_id.as_deref(), *code) {
+            Some(name) => (Some(name.to_string()), false),
+            None => {
+                let guess = registry.guess_error_name(program_id.as_deref(), *code);
+                (guess.map(|name| name.to_string()), guess.is_some())
+            }
+        },
+        other => (Some(format!("{other:?}")), false),
+    };
+    let failure_logs = failed
+        .map(|invocation| {
+            let lines = invocation
+                .logs
+                .iter()
+                .filter_map(|entry| match entry {
+                    LogEntry::Log(message) | LogEntry::Other(message) => Some(message.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            lines[lines.len().saturating_sub(MAX_FAILURE_LOGS)..].to_vec()
+        })
+        .unwrap_or_default();
+
+    Some(FailureDiagnostics {
+        instruction_index: *instruction_index,
+        path,
+        program_name: program_id
+            .as_deref()
+            .and_then(|p| registry.program_name(p))
+            .map(|name| name.to_string()),
+        program_id,
+        error: error.clone(),
+        error_name,
+        error_name_guessed,
+        logs: failure_logs,
+    })
+}
+
+const SYSTEM_ERRORS: [&str; 9] = [
+    "AccountAlreadyInUse",
+    "ResultWithNegativeLamports",
+    "InvalidProgramId",
+    "InvalidAccountDataLength",
+    "MaxSeedLengthExceeded",
+    "AddressWithSeedMismatch",
+    "NonceNoRecentBlockhashes",
+    "NonceBlockhashNotExpired",
+    "NonceUnexpectedBlockhashValue",
+];
+
+/// Anchor框架的错误码
+fn anchor_error_name(code: u32) -> Option<&'static str> {
+    let name = match code {
+        100 => "InstructionMissing",
+        101 => "InstructionFallbackNotFound",
+        102 => "InstructionDidNotDeserialize",
+        103 => "InstructionDidNotSerialize",
+        1000 => "IdlInstructionStub",
+        1001 => "IdlInstructionInvalidProgram",
+        1002 => "IdlAccountNotEmpty",
+        1500 => "EventInstructionStub",
+        2000 => "ConstraintMut",
+        2001 => "ConstraintHasOne",
+        2002 => "ConstraintSigner",
+        2003 => "ConstraintRaw",
+        2004 => "ConstraintOwner",
+        2005 => "ConstraintRentExempt",
+        2006 => "ConstraintSeeds",
+        2007 => "ConstraintExecutable",
+        2008 => "ConstraintState",
+        2009 => "ConstraintAssociated",
+        2010 => "ConstraintAssociatedInit",
+        2011 => "ConstraintClose",
+        2012 => "ConstraintAddress",
+        2013 => "ConstraintZero",
+        2014 => "ConstraintTokenMint",
+        2015 => "ConstraintTokenOwner",
+        2016 => "ConstraintMintMintAuthority",
+        2017 => "ConstraintMintFreezeAuthority",
+        2018 => "ConstraintMintDecimals",
+        2019 => "ConstraintSpace",
+        2020 => "ConstraintAccountIsNone",
+        2021 => "ConstraintTokenTokenProgram",
+        2022 => "ConstraintMintTokenProgram",
+        2023 => "ConstraintAssociatedTokenTokenProgram",
+        2500 => "RequireViolated",
+        2501 => "RequireEqViolated",
+        2502 => "RequireKeysEqViolated",
+        2503 => "RequireNeqViolated",
+        2504 => "RequireKeysNeqViolated",
+        2505 => "RequireGtViolated",
+        2506 => "RequireGteViolated",
+        3000 => "AccountDiscriminatorAlreadySet",
+        3001 => "AccountDiscriminatorNotFound",
+        3002 => "AccountDiscriminatorMismatch",
+        3003 => "AccountDidNotDeserialize",
+        3004 => "AccountDidNotSerialize",
+        3005 => "AccountNotEnoughKeys",
+        3006 => "AccountNotMutable",
+        3007 => "AccountOwnedByWrongProgram",
+        3008 => "InvalidProgramId",
+        3009 => "InvalidProgramExecutable",
+        3010 => "AccountNotSigner",
+        3011 => "AccountNotSystemOwned",
+        3012 => "AccountNotInitialized",
+        3013 => "AccountNotProgramData",
+        3014 => "AccountNotAssociatedTokenAccount",
+        3015 => "AccountSysvarMismatch",
+        3016 => "AccountReallocExceedsLimit",
+        3017 => "AccountDuplicateReallocs",
+        4100 => "DeclaredProgramIdMismatch",
+        4101 => "TryingToInitPayerAsProgramAccount",
+        4102 => "InvalidNumericConversion",
+        5000 => "Deprecated",
+        _ => return None,
+    };
+
+    Some(name)
+}
@@ -0,0 +1,141 @@
+use crate::events::builtin::{METEORA_DLMM_PROGRAM, PUMP_FUN_PROGRAM, RAYDIUM_CLMM_PROGRAM};
+use crate::pubkeys::{RAYDIUM_AMM_V4_PROGRAM, RAYDIUM_CPMM_PROGRAM};
+
+/// 一个DEX程序的名称及自定义错误码
+pub(super) struct DexErrors {
+    pub program_id: &'static str,
+    pub name: &'static str,
+    /// 是否为Anchor程序，Anchor程序还会返回框架错误码
+    pub anchor: bool,
+    pub codes: &'static [(u32, &'static str)],
+}
+
+/// 常见DEX的错误码
+pub(super) const DEX_ERRORS: &[DexErrors] = &[
+    DexErrors {
+        program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4",
+        name: "Jupiter v6",
+        anchor: true,
+        codes: &[(6001, "SlippageToleranceExceeded")],
+    },
+    DexErrors {
+        program_id: PUMP_FUN_PROGRAM,
+        name: "pump.fun",
+        anchor: true,
+        codes: &[
+            (6002, "TooMuchSolRequired"),
+            (6003, "TooLittleSolReceived"),
+            (6005, "BondingCurveComplete"),
+        ],
+    },
+    DexErrors {
+        program_id: RAYDIUM_AMM_V4_PROGRAM,
+        name: "Raydium AMM v4",
+        anchor: false,
+        codes: &[(30, "ExceededSlippage")],
+    },
+    DexErrors {
+        program_id: RAYDIUM_CPMM_PROGRAM,
+        name: "Raydium CPMM",
+        anchor: true,
+        codes: &[(6005, "ExceededSlippage")],
+    },
+    DexErrors {
+        program_id: RAYDIUM_CLMM_PROGRAM,
+        name: "Raydium CLMM",
+        anchor: true,
+        codes: &[
+            (6000, "LOK"),
+            (6001, "NotApproved"),
+            (6002, "InvalidUpdateConfigFlag"),
+            (6003, "AccountLack"),
+            (6004, "ClosePositionErr"),
+            (6005, "ZeroMintAmount"),
+            (6006, "InvaildTickIndex"),
+            (6007, "TickInvaildOrder"),
+            (6008, "TickLowerOverflow"),
+            (6009, "TickUpperOverflow"),
+            (6010, "TickAndSpacingNotMatch"),
+            (6011, "InvalidTickArray"),
+            (6012, "InvalidTickArrayBoundary"),
+            (6013, "SqrtPriceLimitOverflow"),
+            (6014, "SqrtPriceX64"),
+            (6015, "LiquiditySubValueErr"),
+            (6016, "LiquidityAddValueErr"),
+            (6017, "InvaildLiquidity"),
+            (6018, "ForbidBothZeroForSupplyLiquidity"),
+            (6019, "LiquidityInsufficient"),
+            (6020, "TransactionTooOld"),
+            (6021, "PriceSlippageCheck"),
+            (6022, "TooLittleOutputReceived"),
+            (6023, "TooMuchInputPaid"),
+            (6024, "ZeroAmountSpecified"),
+            (6025, "InvalidInputPoolVault"),
+            (6026, "TooSmallInputOrOutputAmount"),
+            (6027, "NotEnoughTickArrayAccount"),
+            (6028, "InvalidFirstTickArrayAccount"),
+            (6029, "InvalidRewardIndex"),
+            (6030, "FullRewardInfo"),
+            (6031, "RewardTokenAlreadyInUse"),
+            (6032, "ExceptPoolVaultMint"),
+            (6033, "InvalidRewardInitParam"),
+            (6034, "InvalidRewardDesiredAmount"),
+            (6035, "InvalidRewardInputAccountNumber"),
+            (6036, "InvalidRewardPeriod"),
+            (6037, "NotApproveUpdateRewardEmissiones"),
+            (6038, "UnInitializedRewardInfo"),
+            (6039, "NotSupportMint"),
+            (6040, "MissingTickArrayBitmapExtensionAccount"),
+            (6041, "InsufficientLiquidityForDirection"),
+            (6042, "MaxTokenOverflow"),
+            (6043, "CalculateOverflow"),
+        ],
+    },
+    DexErrors {
+        program_id: METEORA_DLMM_PROGRAM,
+        name: "Meteora DLMM",
+        anchor: true,
+        codes: &[
+            (6000, "InvalidStartBinIndex"),
+            (6001, "InvalidBinId"),
+            (6002, "InvalidInput"),
+            (6003, "ExceededAmountSlippageTolerance"),
+            (6004, "ExceededBinSlippageTolerance"),
+            (6005, "CompositionFactorFlawed"),
+            (6006, "NonPresetBinStep"),
+            (6007, "ZeroLiquidity"),
+            (6008, "InvalidPosition"),
+            (6009, "BinArrayNotFound"),
+            (6010, "InvalidTokenMint"),
+            (6011, "InvalidAccountForSingleDeposit"),
+            (6012, "PairInsufficientLiquidity"),
+            (6013, "InvalidFeeOwner"),
+            (6014, "InvalidFeeWithdrawAmount"),
+            (6015, "InvalidAdmin"),
+            (6016, "IdenticalFeeOwner"),
+            (6017, "InvalidBps"),
+            (6018, "MathOverflow"),
+            (6019, "TypeCastFailed"),
+            (6020, "InvalidRewardIndex"),
+            (6021, "InvalidRewardDuration"),
+            (6022, "RewardInitialized"),
+            (6023, "RewardUninitialized"),
+            (6024, "IdenticalFunder"),
+            (6025, "RewardCampaignInProgress"),
+            (6026, "IdenticalRewardDuration"),
+            (6027, "InvalidBinArray"),
+            (6028, "NonContinuousBinArrays"),
+            (6029, "InvalidRewardVault"),
+            (6030, "NonEmptyPosition"),
+            (6031, "UnauthorizedAccess"),
+            (6032, "InvalidFeeParameter"),
+            (6033, "MissingOracle"),
+            (6034, "InsufficientSample"),
+            (6035, "InvalidLookupTimestamp"),
+            (6036, "BitmapExtensionAccountIsNotProvided"),
+            (6037, "CannotFindNonZeroLiquidityBinArrayId"),
+            (6038, "BinIdOutOfBound"),
+            (6039, "InsufficientOutAmount"),
+        ],
+    },
+];
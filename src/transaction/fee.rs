@@ -0,0 +1,185 @@
+use crate::asset_flow::{AssetFlowKind, FlowAsset, extract_asset_flows};
+use crate::instructions::compute_budget::ComputeBudgetInstruction;
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::parsed_instruction::{ParsedInstruction, ParsedInstructionData};
+use crate::pubkeys::is_jito_tip_account;
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use crate::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+
+/// 每个签名的基础手续费(lamports)
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+/// 没有SetComputeUnitLimit时每条非内置程序指令的默认CU上限
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+/// 没有SetComputeUnitLimit时每条内置程序指令的默认CU上限
+const MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT: u32 = 3_000;
+/// 交易的最大CU上限
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// 交易手续费的组成
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// meta中的手续费总额
+    pub fee: u64,
+    /// 签名数量 × 每个签名的手续费，不超过手续费总额
+    pub base_fee: u64,
+    /// 优先费，为手续费总额减去基础手续费
+    pub priority_fee: u64,
+    /// 按CU价格和CU上限估算的优先费，没有SetComputeUnitPrice时为None
+    ///
+    /// CU上限为估算值时与实际的优先费可能不一致，只用于对照
+    pub estimated_priority_fee: Option<u64>,
+    /// SetComputeUnitPrice设置的价格(微lamports/CU)
+    pub compute_unit_price: Option<u64>,
+    /// SetComputeUnitLimit设置的CU上限，没有时按指令数量估算
+    pub compute_unit_limit: u32,
+    pub compute_units_consumed: Option<u64>,
+    pub tips: Vec<Tip>,
+}
+
+impl FeeBreakdown {
+    pub fn total_tip(&self) -> u64 {
+        self.tips.iter().map(|t| t.lamports).sum()
+    }
+
+    /// 手续费加小费
+    pub fn total_cost(&self) -> u64 {
+        self.fee + self.total_tip()
+    }
+
+    /// 为被打包支付的实际单价(微lamports/CU): (优先费 + 小费) / 实际消耗的CU
+    pub fn effective_price_per_cu(&self) -> Option<f64> {
+        let consumed = self.compute_units_consumed.filter(|c| *c > 0)?;
+        let paid = (self.priority_fee + self.total_tip()) as f64;
+        Some(paid * MICRO_LAMPORTS_PER_LAMPORT as f64 / consumed as f64)
+    }
+}
+
+/// 按默认的每签名手续费拆分交易手续费
+pub fn fee_breakdown(obj: &dyn TransactionPropsProvider) -> Option<FeeBreakdown> {
+    fee_breakdown_with_lamports_per_signature(obj, LAMPORTS_PER_SIGNATURE)
+}
+
+pub fn fee_breakdown_with_lamports_per_signature(
+    obj: &dyn TransactionPropsProvider,
+    lamports_per_signature: u64,
+) -> Option<FeeBreakdown> {
+    let meta = obj.get_meta()?;
+    let signatures = obj.get_signatures().map(|s| s.len()).unwrap_or(1) as u64;
+    // lamports_per_signature与交易执行时的费率不一致时，基础手续费不应超过实际收取的总额
+    let base_fee = (signatures * lamports_per_signature).min(meta.fee);
+    let (compute_unit_price, compute_unit_limit) = obj
+        .get_parsed_instructions()
+        .map(|instructions| compute_budget(instructions, &obj.get_accounts()))
+        .unwrap_or((None, DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT));
+    let estimated_priority_fee = compute_unit_price.map(|price| {
+        let micro_lamports = price as u128 * compute_unit_limit as u128;
+        u64::try_from(micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT)).unwrap_or(u64::MAX)
+    });
+
+    Some(FeeBreakdown {
+        fee: meta.fee,
+        base_fee,
+        priority_fee: meta.fee.saturating_sub(base_fee),
+        estimated_priority_fee,
+        compute_unit_price,
+        compute_unit_limit,
+        compute_units_consumed: meta.compute_units_consumed,
+        tips: tips(obj),
+    })
+}
+
+/// 从顶层的ComputeBudget指令中获取(CU价格, CU上限)
+///
+/// 使用解析时解码的[ParsedInstructionData::ComputeBudget], 不依赖[ParsedInstruction::raw_data]。
+/// 没有SetComputeUnitLimit时按运行时的规则估算：内置程序(包括ComputeBudget本身)的指令3_000CU，
+/// 其它指令200_000CU
+fn compute_budget(
+    instructions: &[ParsedInstruction],
+    accounts: &TransactionAccounts<'_, String>,
+) -> (Option<u64>, u32) {
+    let mut price = None;
+    let mut limit = None;
+    let mut default_limit = 0u32;
+    for instruction in instructions {
+        let program_id = instruction.program_id.or_else(|| {
+            accounts
+                .get(instruction.program_id_index as usize)?
+                .parse()
+                .ok()
+        });
+        default_limit = default_limit.saturating_add(match program_id {
+            Some(program_id) if is_builtin_program(&program_id) => {
+                MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT
+            }
+            _ => DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT,
+        });
+        match &instruction.instruction_data {
+            ParsedInstructionData::ComputeBudget(
+                ComputeBudgetInstruction::SetComputeUnitLimit(units),
+            ) => limit = Some(*units),
+            ParsedInstructionData::ComputeBudget(
+                ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports),
+            ) => price = Some(*micro_lamports),
+            _ => {}
+        }
+    }
+
+    (
+        price,
+        limit.unwrap_or(default_limit).min(MAX_COMPUTE_UNIT_LIMIT),
+    )
+}
+
+/// 运行时按内置程序计算默认CU上限的程序
+fn is_builtin_program(program_id: &Pubkey) -> bool {
+    [
+        solana_sdk::system_program::id(),
+        solana_sdk::compute_budget::id(),
+        solana_sdk::vote::program::id(),
+        solana_sdk::stake::program::id(),
+        solana_sdk::address_lookup_table::program::id(),
+        solana_sdk::bpf_loader_deprecated::id(),
+        solana_sdk::bpf_loader::id(),
+        solana_sdk::bpf_loader_upgradeable::id(),
+        solana_sdk::loader_v4::id(),
+    ]
+    .contains(program_id)
+}
+
+/// 协议外给出块者的小费，即向小费帐户的SOL转帐
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tip {
+    pub from: String,
+    pub tip_account: String,
+    pub lamports: u64,
+    pub path: InstructionPath,
+}
+
+/// 提取交易中给Jito小费帐户的转帐
+pub fn tips(obj: &dyn TransactionPropsProvider) -> Vec<Tip> {
+    let Some(instructions) = obj.get_parsed_instructions() else {
+        return vec![];
+    };
+    let accounts = obj.get_accounts();
+    extract_asset_flows(instructions, &accounts)
+        .into_iter()
+        .filter_map(|flow| {
+            if flow.kind != AssetFlowKind::Transfer || !matches!(flow.asset, FlowAsset::Sol) {
+                return None;
+            }
+            let tip_account = flow.destination?;
+            if !is_jito_tip_account(tip_account) {
+                return None;
+            }
+            Some(Tip {
+                from: flow.source?.clone(),
+                tip_account: tip_account.clone(),
+                lamports: flow.amount?,
+                path: flow.path,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,167 @@
+use crate::transaction::balance_change::token_balance_changes;
+use crate::transaction::fee::fee_breakdown;
+use crate::transaction::transaction_filter::{TransactionMeta, TransactionPropsProvider};
+use std::collections::{BTreeMap, HashMap};
+
+/// SOL的精度
+const SOL_DECIMALS: u8 = 9;
+
+/// 某种mint的净变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintProfit {
+    pub mint: String,
+    pub decimals: u8,
+    /// 单位为最小精度
+    pub change: i128,
+}
+
+impl MintProfit {
+    pub fn ui_change(&self) -> f64 {
+        self.change as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// 交易签名者(手续费支付者)的收益
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionProfit {
+    pub signer: String,
+    /// 各mint的净变化，已扣除手续费和小费
+    ///
+    /// SOL与wSOL合并，使用wSOL的mint表示，不包括`rent`
+    pub changes: Vec<MintProfit>,
+    /// 签名者的token帐户中租金的净变化(lamports)，创建帐户存入的租金为正，关闭帐户退回的租金为负
+    ///
+    /// 租金仍属于签名者，不计入SOL的收益。只统计出现在token余额中的帐户
+    pub rent: i128,
+    /// 交易手续费总额，包括基础手续费和优先费
+    pub fee: u64,
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    /// 签名者支付的Jito小费
+    pub tip: u64,
+}
+
+impl TransactionProfit {
+    /// SOL(含wSOL)的净变化
+    pub fn sol_change(&self) -> i128 {
+        let native_mint = spl_token::native_mint::ID.to_string();
+        self.changes
+            .iter()
+            .find(|c| c.mint == native_mint)
+            .map(|c| c.change)
+            .unwrap_or(0)
+    }
+
+    /// 扣除手续费和小费前的SOL变化，不包括租金
+    pub fn gross_sol_change(&self) -> i128 {
+        self.sol_change() + self.fee as i128 + self.tip as i128
+    }
+
+    /// 支付给出块者的部分: 优先费和小费
+    pub fn paid_to_leader(&self) -> u64 {
+        self.priority_fee + self.tip
+    }
+
+    /// 按调用方提供的价格(每个UI单位的报价资产数量)换算成报价资产
+    ///
+    /// 有变化的mint缺少价格时返回None
+    pub fn quote_value(&self, prices: &HashMap<String, f64>) -> Option<f64> {
+        self.changes
+            .iter()
+            .map(|c| prices.get(&c.mint).map(|price| c.ui_change() * price))
+            .sum()
+    }
+}
+
+/// 计算交易签名者在所有mint上的净收益
+pub fn transaction_profit(obj: &dyn TransactionPropsProvider) -> Option<TransactionProfit> {
+    let meta = obj.get_meta()?;
+    let signer = obj.get_accounts().get(0)?.clone();
+    let native_mint = spl_token::native_mint::ID.to_string();
+
+    // 余额变化已经包含了手续费和小费，存入token帐户的租金不算支出
+    let rent = token_account_rent(&meta, &signer);
+    let lamports =
+        *meta.post_balances.first()? as i128 - *meta.pre_balances.first()? as i128 + rent;
+    let mut changes: BTreeMap<String, MintProfit> = BTreeMap::new();
+    changes.insert(
+        native_mint.clone(),
+        MintProfit {
+            mint: native_mint,
+            decimals: SOL_DECIMALS,
+            change: lamports,
+        },
+    );
+    for change in token_balance_changes(obj)
+        .into_iter()
+        .filter(|c| c.owner == signer)
+    {
+        changes
+            .entry(change.mint.clone())
+            .or_insert_with(|| MintProfit {
+                mint: change.mint,
+                decimals: change.decimals,
+                change: 0,
+            })
+            .change += change.change;
+    }
+
+    let fees = fee_breakdown(obj)?;
+    let tip = fees
+        .tips
+        .iter()
+        .filter(|t| t.from == signer)
+        .map(|t| t.lamports)
+        .sum();
+
+    Some(TransactionProfit {
+        signer,
+        changes: changes.into_values().filter(|c| c.change != 0).collect(),
+        rent,
+        fee: fees.fee,
+        base_fee: fees.base_fee,
+        priority_fee: fees.priority_fee,
+        tip,
+    })
+}
+
+/// 签名者的token帐户中不属于token数量的lamports(即租金)的净变化
+///
+/// wSOL帐户的lamports包括token数量，需要减去token数量的变化
+fn token_account_rent(meta: &TransactionMeta<'_>, signer: &str) -> i128 {
+    let native_mint = spl_token::native_mint::ID.to_string();
+    // 帐户索引 -> 交易前后wSOL的数量
+    let mut accounts: BTreeMap<u8, (i128, i128)> = BTreeMap::new();
+    let pre = meta.pre_token_balances.unwrap_or_default();
+    let post = meta.post_token_balances.unwrap_or_default();
+    for (balance, is_pre) in pre
+        .iter()
+        .map(|b| (b, true))
+        .chain(post.iter().map(|b| (b, false)))
+    {
+        if balance.owner != signer {
+            continue;
+        }
+        let amounts = accounts.entry(balance.account_index).or_default();
+        if balance.mint == native_mint {
+            let amount = balance.ui_token_amount.amount.parse::<i128>().unwrap_or(0);
+            if is_pre {
+                amounts.0 = amount;
+            } else {
+                amounts.1 = amount;
+            }
+        }
+    }
+
+    accounts
+        .into_iter()
+        .map(|(index, (pre_amount, post_amount))| {
+            let index = index as usize;
+            let lamports = match (meta.pre_balances.get(index), meta.post_balances.get(index)) {
+                (Some(pre), Some(post)) => *post as i128 - *pre as i128,
+                _ => 0,
+            };
+            lamports - (post_amount - pre_amount)
+        })
+        .sum()
+}
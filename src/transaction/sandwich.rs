@@ -0,0 +1,180 @@
+use crate::api::transaction::BlockTransaction;
+use crate::transaction::encoded_transaction::EncodedTransactionProps;
+use crate::transaction::swap_detector::{Swap, SwapDetector};
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use tracing::error;
+
+/// 夹子中的一笔交易
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandwichTransaction {
+    /// 交易在传入列表中的位置
+    pub index: usize,
+    pub signature: Option<String>,
+    pub swap: Swap,
+}
+
+/// 一次三明治攻击: 攻击者在受害者兑换前买入、之后在同一池子卖出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandwich {
+    pub attacker: String,
+    pub victim: String,
+    pub venue_program: Option<String>,
+    /// 三笔兑换所在的池子
+    pub pool: String,
+    /// 攻击者投入并最终换回的mint
+    pub base_mint: String,
+    /// 被抬价的mint
+    pub target_mint: String,
+    pub front_run: SandwichTransaction,
+    pub victim_transaction: SandwichTransaction,
+    pub back_run: SandwichTransaction,
+    /// 受害者按抢跑交易的价格本可多得的target_mint数量，只是估计值
+    pub victim_loss: u64,
+    /// 攻击者以base_mint计的收益，可能为负
+    ///
+    /// 卖出数量与抢跑买入数量不同时(部分卖出或卖出了原有持仓), 抢跑成本按卖出数量的比例分摊
+    pub attacker_profit: i128,
+}
+
+/// 参与检测的交易及其兑换
+struct SwapTransaction {
+    index: usize,
+    signer: Option<String>,
+    signature: Option<String>,
+    swaps: Vec<Swap>,
+}
+
+/// 检测同一slot中按执行顺序排列的交易里的三明治攻击
+///
+/// 三笔兑换必须在同一池子中，不知道池子的兑换(多跳路由合并后的兑换)不参与检测
+pub fn detect_sandwiches(transactions: &[&dyn TransactionPropsProvider]) -> Vec<Sandwich> {
+    let detector = SwapDetector::with_builtin();
+    let transactions = transactions
+        .iter()
+        .enumerate()
+        .map(|(index, transaction)| SwapTransaction {
+            index,
+            signer: transaction.get_accounts().get(0).cloned(),
+            signature: transaction
+                .get_signatures()
+                .and_then(|s| s.first())
+                .cloned(),
+            swaps: detector.detect(*transaction),
+        })
+        .collect::<Vec<_>>();
+
+    let mut sandwiches = vec![];
+    for (i, front) in transactions.iter().enumerate() {
+        let Some(attacker) = front.signer.as_ref() else {
+            continue;
+        };
+        for front_swap in &front.swaps {
+            if let Some(sandwich) =
+                find_sandwich(&transactions[i + 1..], attacker, front, front_swap)
+            {
+                sandwiches.push(sandwich);
+            }
+        }
+    }
+
+    sandwiches
+}
+
+impl BlockTransaction {
+    /// 检测区块中的三明治攻击，无法解析的交易会被跳过
+    pub fn sandwiches(&self) -> Vec<Sandwich> {
+        let transactions = self
+            .transactions
+            .iter()
+            .flatten()
+            .filter_map(|t| match EncodedTransactionProps::try_from(t) {
+                Ok(props) => Some(props),
+                Err(e) => {
+                    error!("slot {}中的交易解析出错: {e}", self.slot);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let providers = transactions
+            .iter()
+            .map(|t| t as &dyn TransactionPropsProvider)
+            .collect::<Vec<_>>();
+        detect_sandwiches(&providers)
+    }
+}
+
+/// 在抢跑交易之后查找同一池子中最近的反向兑换，以及两者之间同方向的受害者兑换
+fn find_sandwich(
+    after: &[SwapTransaction],
+    attacker: &String,
+    front: &SwapTransaction,
+    front_swap: &Swap,
+) -> Option<Sandwich> {
+    let pool = front_swap.pool.as_ref()?;
+    let same_pool = |swap: &Swap| swap.pool.as_ref() == Some(pool);
+    let is_back_run = |s: &&Swap| {
+        same_pool(s)
+            && s.input_mint == front_swap.output_mint
+            && s.output_mint == front_swap.input_mint
+    };
+    let (back_position, back_swap) = after.iter().enumerate().find_map(|(position, t)| {
+        if t.signer.as_ref() != Some(attacker) {
+            return None;
+        }
+        t.swaps.iter().find(is_back_run).map(|s| (position, s))
+    })?;
+    let back = &after[back_position];
+    let (victim, victim_swap) = after[..back_position].iter().find_map(|t| {
+        if t.signer.as_ref() == Some(attacker) {
+            return None;
+        }
+        t.swaps
+            .iter()
+            .find(|s| {
+                same_pool(s)
+                    && s.input_mint == front_swap.input_mint
+                    && s.output_mint == front_swap.output_mint
+            })
+            .map(|s| (t, s))
+    })?;
+
+    // 按抢跑交易的成交价格估算受害者应得的数量
+    let expected_output = if front_swap.input_amount == 0 {
+        victim_swap.output_amount as u128
+    } else {
+        victim_swap.input_amount as u128 * front_swap.output_amount as u128
+            / front_swap.input_amount as u128
+    };
+    let victim_loss = expected_output.saturating_sub(victim_swap.output_amount as u128);
+    // 按卖出数量分摊抢跑的成本
+    let cost = if front_swap.output_amount == 0 {
+        front_swap.input_amount as u128
+    } else {
+        front_swap.input_amount as u128 * back_swap.input_amount as u128
+            / front_swap.output_amount as u128
+    };
+
+    Some(Sandwich {
+        attacker: attacker.clone(),
+        victim: victim.signer.clone().unwrap_or_default(),
+        venue_program: front_swap.venue_program.clone(),
+        pool: pool.clone(),
+        base_mint: front_swap.input_mint.clone(),
+        target_mint: front_swap.output_mint.clone(),
+        front_run: SandwichTransaction::new(front, front_swap),
+        victim_transaction: SandwichTransaction::new(victim, victim_swap),
+        back_run: SandwichTransaction::new(back, back_swap),
+        victim_loss: u64::try_from(victim_loss).unwrap_or(u64::MAX),
+        attacker_profit: back_swap.output_amount as i128 - cost as i128,
+    })
+}
+
+impl SandwichTransaction {
+    fn new(transaction: &SwapTransaction, swap: &Swap) -> Self {
+        Self {
+            index: transaction.index,
+            signature: transaction.signature.clone(),
+            swap: swap.clone(),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+pub mod pump_fun;
+pub mod raydium_amm_v4;
+
+use crate::parsed_instruction::ParsedInstruction;
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::token_account_index::TokenAccountIndex;
+use crate::transaction::swap_detector::pump_fun::PumpFunDecoder;
+use crate::transaction::swap_detector::raydium_amm_v4::RaydiumAmmV4Decoder;
+use crate::transaction::token_flow::{TokenFlow, signer_accounts, token_flows};
+use crate::transaction::transaction_filter::{TransactionMeta, TransactionPropsProvider};
+use crate::utils::TransactionAccounts;
+use std::collections::{BTreeMap, HashSet};
+
+/// 一次兑换
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Swap {
+    pub trader: String,
+    pub input_mint: String,
+    pub input_amount: u64,
+    pub output_mint: String,
+    pub output_amount: u64,
+    /// 执行兑换的程序，多跳路由合并后为路由程序
+    pub venue_program: Option<String>,
+    /// 兑换所用的池子: 解码器取自指令帐户，推断时为池子vault的owner，多跳路由合并后为None
+    pub pool: Option<String>,
+    pub instruction_path: InstructionPath,
+}
+
+/// 解码兑换指令时可以使用的交易数据
+pub struct SwapContext<'a> {
+    pub accounts: &'a TransactionAccounts<'a, String>,
+    /// 由交易前后的token余额构建，用于获取token帐户的mint和owner
+    pub token_accounts: &'a TokenAccountIndex,
+    /// 没有meta时为None
+    pub meta: Option<&'a TransactionMeta<'a>>,
+}
+
+impl SwapContext<'_> {
+    /// 帐户在交易前后的lamports变化，没有meta或帐户不在交易中时返回None
+    pub fn lamports_change(&self, account: &str) -> Option<i128> {
+        let meta = self.meta?;
+        let index = self.accounts.position(account)?;
+        Some(*meta.post_balances.get(index)? as i128 - *meta.pre_balances.get(index)? as i128)
+    }
+}
+
+/// 已知DEX指令的解码器
+pub trait SwapDecoder: Send + Sync + 'static {
+    /// 解码兑换指令，无法识别时返回None, 此时根据转帐推断
+    fn decode(
+        &self,
+        instruction: &ParsedInstruction,
+        path: &InstructionPath,
+        context: &SwapContext<'_>,
+    ) -> Option<Swap>;
+}
+
+/// 从交易的资产流动中推断兑换
+///
+/// 未知的DEX根据转帐模式推断，注册了解码器的DEX优先使用解码结果。
+/// 程序直接修改lamports产生的原生SOL变化没有转帐指令，只有解码器能从余额变化中还原
+#[derive(Default)]
+pub struct SwapDetector {
+    decoders: Vec<Box<dyn SwapDecoder>>,
+}
+
+impl SwapDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 包含内置解码器(Raydium AMM v4, pump.fun)的检测器
+    pub fn with_builtin() -> Self {
+        Self::new()
+            .with_decoder(RaydiumAmmV4Decoder)
+            .with_decoder(PumpFunDecoder)
+    }
+
+    pub fn with_decoder(mut self, decoder: impl SwapDecoder) -> Self {
+        self.decoders.push(Box::new(decoder));
+        self
+    }
+
+    pub fn detect(&self, obj: &dyn TransactionPropsProvider) -> Vec<Swap> {
+        let Some(instructions) = obj.get_parsed_instructions() else {
+            return vec![];
+        };
+        let accounts = obj.get_accounts();
+        let signers = signer_accounts(obj);
+        let meta = obj.get_meta();
+        let token_accounts = meta
+            .as_ref()
+            .map(|meta| meta.token_account_index())
+            .unwrap_or_default();
+        let context = SwapContext {
+            accounts: &accounts,
+            token_accounts: &token_accounts,
+            meta: meta.as_ref(),
+        };
+
+        // 按发起转帐的指令(场所)分组，顶层的转帐不是兑换
+        let mut venues: BTreeMap<InstructionPath, Vec<TokenFlow>> = BTreeMap::new();
+        for flow in token_flows(obj) {
+            if let Some(venue) = flow.path.parent() {
+                venues.entry(venue).or_default().push(flow);
+            }
+        }
+
+        let mut hops = vec![];
+        for (path, legs) in venues {
+            let Some(venue) = path.resolve(instructions) else {
+                continue;
+            };
+            let decoded = self
+                .decoders
+                .iter()
+                .find_map(|decoder| decoder.decode(venue, &path, &context));
+            let swap = decoded.or_else(|| {
+                infer_swap(&legs, &signers).map(|inferred| Swap {
+                    trader: inferred.trader,
+                    input_mint: inferred.input.0,
+                    input_amount: inferred.input.1,
+                    output_mint: inferred.output.0,
+                    output_amount: inferred.output.1,
+                    venue_program: program_id(venue, &accounts),
+                    pool: Some(inferred.pool),
+                    instruction_path: path.clone(),
+                })
+            });
+            if let Some(swap) = swap {
+                hops.push(swap);
+            }
+        }
+
+        merge_routes(hops, instructions, &accounts)
+    }
+}
+
+/// 根据转帐推断出的兑换，数量为(mint, 数量)
+struct InferredSwap {
+    trader: String,
+    pool: String,
+    input: (String, u64),
+    output: (String, u64),
+}
+
+/// 根据场所下的转帐推断兑换
+///
+/// 池子是同时收入一种mint并付出另一种mint的一方，优先选择非签名者；
+/// 向池子付款的一方是trader
+fn infer_swap(legs: &[TokenFlow], signers: &HashSet<String>) -> Option<InferredSwap> {
+    let mut net: BTreeMap<(&str, &str), i128> = BTreeMap::new();
+    for leg in legs {
+        *net.entry((leg.source_owner.as_str(), leg.mint.as_str()))
+            .or_default() -= leg.sent as i128;
+        *net.entry((leg.destination_owner.as_str(), leg.mint.as_str()))
+            .or_default() += leg.received as i128;
+    }
+    // owner的(最大净流入, 最大净流出)
+    let extremes = |owner: &str| {
+        let changes = net
+            .iter()
+            .filter(|((o, _), _)| *o == owner)
+            .map(|((_, mint), change)| (*mint, *change));
+        let inflow = changes
+            .clone()
+            .filter(|(_, c)| *c > 0)
+            .max_by_key(|(_, c)| *c)?;
+        let outflow = changes.filter(|(_, c)| *c < 0).min_by_key(|(_, c)| *c)?;
+        Some((inflow, outflow))
+    };
+
+    let mut candidates = legs
+        .iter()
+        .map(|leg| leg.destination_owner.as_str())
+        .filter(|owner| extremes(*owner).is_some());
+    let first = candidates.next()?;
+    let pool = if signers.contains(first) {
+        candidates
+            .find(|owner| !signers.contains(*owner))
+            .unwrap_or(first)
+    } else {
+        first
+    };
+    let ((input_mint, input), (output_mint, output)) = extremes(pool)?;
+    let trader = legs
+        .iter()
+        .find(|leg| leg.destination_owner == pool && leg.mint == input_mint)?
+        .source_owner
+        .clone();
+
+    Some(InferredSwap {
+        trader,
+        pool: pool.to_string(),
+        input: (input_mint.to_string(), u64::try_from(input).ok()?),
+        output: (output_mint.to_string(), u64::try_from(-output).ok()?),
+    })
+}
+
+/// 把同一顶层指令下首尾相连的多跳兑换合并成一次兑换
+fn merge_routes(
+    hops: Vec<Swap>,
+    instructions: &[ParsedInstruction],
+    accounts: &TransactionAccounts<'_, String>,
+) -> Vec<Swap> {
+    let mut routes: Vec<Vec<Swap>> = vec![];
+    for hop in hops {
+        if let Some(route) = routes.last_mut() {
+            let last = &route[route.len() - 1];
+            // 下一跳的输入不能超过上一跳的输出，否则不是同一笔资金
+            if last.instruction_path.top_level_index() == hop.instruction_path.top_level_index()
+                && last.trader == hop.trader
+                && last.output_mint == hop.input_mint
+                && last.output_amount >= hop.input_amount
+            {
+                route.push(hop);
+                continue;
+            }
+        }
+        routes.push(vec![hop]);
+    }
+
+    routes
+        .into_iter()
+        .map(|mut route| {
+            if route.len() == 1 {
+                return route.remove(0);
+            }
+            let last = route.pop().unwrap();
+            let first = route.swap_remove(0);
+            let instruction_path = InstructionPath(first.instruction_path.0[..1].to_vec());
+            let venue_program = instruction_path
+                .resolve(instructions)
+                .and_then(|router| program_id(router, accounts));
+            Swap {
+                trader: first.trader,
+                input_mint: first.input_mint,
+                input_amount: first.input_amount,
+                output_mint: last.output_mint,
+                output_amount: last.output_amount,
+                venue_program,
+                pool: None,
+                instruction_path,
+            }
+        })
+        .collect()
+}
+
+fn program_id(
+    instruction: &ParsedInstruction,
+    accounts: &TransactionAccounts<'_, String>,
+) -> Option<String> {
+    instruction
+        .program_id
+        .map(|p| p.to_string())
+        .or_else(|| accounts.get(instruction.program_id_index as usize).cloned())
+}
@@ -0,0 +1,89 @@
+use crate::account_roles::InstructionAccounts;
+use crate::asset_flow::{AssetFlowKind, FlowAsset, extract_asset_flows};
+use crate::events::builtin::PUMP_FUN_PROGRAM;
+use crate::parsed_instruction::ParsedInstruction;
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::transaction::swap_detector::{Swap, SwapContext, SwapDecoder, program_id};
+
+/// pump.fun bonding curve的买卖解码器
+///
+/// buy/sell的前7个帐户相同: global, fee recipient, mint, bonding curve,
+/// bonding curve的token帐户, 用户的token帐户, 用户。
+/// 根据CPI中token转帐的方向区分买卖，不依赖原始指令数据。
+///
+/// 买入时SOL通过System转帐付给bonding curve；卖出时程序直接扣减bonding curve的lamports，
+/// 只能从余额变化得到，同一交易中对同一bonding curve的多次卖出会被合并计算
+pub struct PumpFunDecoder;
+
+impl SwapDecoder for PumpFunDecoder {
+    fn decode(
+        &self,
+        instruction: &ParsedInstruction,
+        path: &InstructionPath,
+        context: &SwapContext<'_>,
+    ) -> Option<Swap> {
+        if program_id(instruction, context.accounts)? != PUMP_FUN_PROGRAM {
+            return None;
+        }
+        let a = InstructionAccounts::new(&instruction.accounts, context.accounts);
+        let mint = a.get(2).ok()?;
+        let bonding_curve = a.get(3).ok()?;
+        let curve_token_account = a.get(4).ok()?;
+        let user_token_account = a.get(5).ok()?;
+        let user = a.get(6).ok()?;
+
+        let flows = extract_asset_flows(
+            instruction
+                .inner_instructions
+                .as_deref()
+                .unwrap_or_default(),
+            context.accounts,
+        );
+        let token_transfer = |source: &String, destination: &String| {
+            flows
+                .iter()
+                .find(|flow| {
+                    matches!(flow.asset, FlowAsset::Token { .. })
+                        && flow.source == Some(source)
+                        && flow.destination == Some(destination)
+                })
+                .and_then(|flow| flow.amount)
+        };
+        let native_mint = spl_token::native_mint::ID.to_string();
+
+        let (input_mint, input_amount, output_mint, output_amount) =
+            if let Some(token_amount) = token_transfer(curve_token_account, user_token_account) {
+                let paid = flows
+                    .iter()
+                    .filter(|flow| {
+                        flow.kind == AssetFlowKind::Transfer
+                            && matches!(flow.asset, FlowAsset::Sol)
+                            && flow.source == Some(user)
+                            && flow.destination == Some(bonding_curve)
+                    })
+                    .filter_map(|flow| flow.amount)
+                    .sum::<u64>();
+                let sol_amount = if paid > 0 {
+                    paid
+                } else {
+                    u64::try_from(context.lamports_change(bonding_curve)?).ok()?
+                };
+                (native_mint, sol_amount, mint.clone(), token_amount)
+            } else {
+                let token_amount = token_transfer(user_token_account, curve_token_account)?;
+                let sol_amount = u64::try_from(-context.lamports_change(bonding_curve)?).ok()?;
+                (mint.clone(), token_amount, native_mint, sol_amount)
+            };
+
+        Some(Swap {
+            trader: user.clone(),
+            input_mint,
+            input_amount,
+            output_mint,
+            output_amount,
+            venue_program: Some(PUMP_FUN_PROGRAM.to_string()),
+            pool: Some(bonding_curve.clone()),
+            instruction_path: path.clone(),
+        })
+    }
+}
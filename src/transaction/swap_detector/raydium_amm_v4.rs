@@ -0,0 +1,80 @@
+use crate::account_roles::InstructionAccounts;
+use crate::asset_flow::{AssetFlowKind, extract_asset_flows};
+use crate::parsed_instruction::ParsedInstruction;
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::pubkeys::RAYDIUM_AMM_V4_PROGRAM;
+use crate::transaction::swap_detector::{Swap, SwapContext, SwapDecoder, program_id};
+
+/// SwapBaseIn/SwapBaseOut的帐户数量，较新的版本省略了amm target orders
+const SWAP_ACCOUNTS_NUM: [usize; 2] = [17, 18];
+
+/// Raydium AMM v4的兑换解码器
+///
+/// 根据帐户布局和CPI中的token转帐识别SwapBaseIn/SwapBaseOut，不依赖原始指令数据。
+/// 第二个帐户是池子(amm id), 从末尾数起: 用户源token帐户、用户目标token帐户、用户
+pub struct RaydiumAmmV4Decoder;
+
+impl SwapDecoder for RaydiumAmmV4Decoder {
+    fn decode(
+        &self,
+        instruction: &ParsedInstruction,
+        path: &InstructionPath,
+        context: &SwapContext<'_>,
+    ) -> Option<Swap> {
+        if program_id(instruction, context.accounts)? != RAYDIUM_AMM_V4_PROGRAM
+            || !SWAP_ACCOUNTS_NUM.contains(&instruction.accounts.len())
+        {
+            return None;
+        }
+        let accounts = InstructionAccounts::new(&instruction.accounts, context.accounts);
+        let account = |position_from_end: usize| {
+            accounts
+                .get(instruction.accounts.len() - position_from_end)
+                .ok()
+        };
+        let user_source = account(3)?;
+        let user_destination = account(2)?;
+        let user = account(1)?;
+        let pool = accounts.get(1).ok()?;
+
+        let flows = extract_asset_flows(
+            instruction
+                .inner_instructions
+                .as_deref()
+                .unwrap_or_default(),
+            context.accounts,
+        );
+        let transfer = |source: Option<&String>, destination: Option<&String>| {
+            flows.iter().find(|flow| {
+                flow.kind == AssetFlowKind::Transfer
+                    && source.is_none_or(|s| flow.source == Some(s))
+                    && destination.is_none_or(|d| flow.destination == Some(d))
+            })
+        };
+        let input = transfer(Some(user_source), None)?;
+        let output = transfer(None, Some(user_destination))?;
+        // 用户的临时wSOL帐户在同一交易中创建并关闭，不在token余额中，mint从池子的vault获取
+        let mint = |user_account: &String, vault: Option<&String>| {
+            let token_account = |address: &String| {
+                context
+                    .token_accounts
+                    .get_by_address(context.accounts, address)
+            };
+            vault
+                .and_then(token_account)
+                .or_else(|| token_account(user_account))
+                .map(|info| info.mint.clone())
+        };
+
+        Some(Swap {
+            trader: user.clone(),
+            input_mint: mint(user_source, input.destination)?,
+            input_amount: input.amount?,
+            output_mint: mint(user_destination, output.source)?,
+            output_amount: output.amount?,
+            venue_program: Some(RAYDIUM_AMM_V4_PROGRAM.to_string()),
+            pool: Some(pool.clone()),
+            instruction_path: path.clone(),
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use crate::asset_flow::{AssetFlow, AssetFlowKind, FlowAsset, extract_asset_flows};
+use crate::parsed_instruction::instruction_iter::InstructionPath;
+use crate::token_account_index::{TokenAccountIndex, TokenAccountInfo};
+use crate::transaction::transaction_filter::TransactionPropsProvider;
+use crate::utils::TransactionAccounts;
+use std::collections::HashSet;
+
+/// 帐户解析到owner的转帐，原生SOL使用wSOL的mint表示
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenFlow {
+    pub path: InstructionPath,
+    pub mint: String,
+    pub source_owner: String,
+    pub destination_owner: String,
+    /// 转出的数量
+    pub sent: u64,
+    /// 转入的数量，Token-2022 transfer-with-fee扣除了手续费
+    pub received: u64,
+}
+
+/// 提取交易中所有owner之间的转帐
+///
+/// owner通过token余额解析，解析不到时使用帐户地址本身；
+/// 包装/解包wSOL等同一owner内部的转帐会被忽略
+pub fn token_flows(obj: &dyn TransactionPropsProvider) -> Vec<TokenFlow> {
+    let Some(instructions) = obj.get_parsed_instructions() else {
+        return vec![];
+    };
+    let accounts = obj.get_accounts();
+    let index = obj
+        .get_meta()
+        .map(|meta| meta.token_account_index())
+        .unwrap_or_default();
+    extract_asset_flows(instructions, &accounts)
+        .iter()
+        .filter_map(|flow| TokenFlow::from_asset_flow(flow, &accounts, &index))
+        .collect()
+}
+
+/// 交易的签名者地址，前num_required_signatures个帐户是签名者，数量与签名数量一致
+pub fn signer_accounts(obj: &dyn TransactionPropsProvider) -> HashSet<String> {
+    let accounts = obj.get_accounts();
+    obj.get_signatures()
+        .map(|signatures| {
+            (0..signatures.len())
+                .filter_map(|i| accounts.get(i).cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl TokenFlow {
+    fn from_asset_flow(
+        flow: &AssetFlow<'_, String>,
+        accounts: &TransactionAccounts<'_, String>,
+        index: &TokenAccountIndex,
+    ) -> Option<Self> {
+        let amount = flow.amount?;
+        let (source, destination) = (flow.source?, flow.destination?);
+        let (mint, source_owner, destination_owner) = match (&flow.kind, &flow.asset) {
+            (AssetFlowKind::Transfer, FlowAsset::Sol) => (
+                spl_token::native_mint::ID.to_string(),
+                source.clone(),
+                destination.clone(),
+            ),
+            (
+                AssetFlowKind::Transfer | AssetFlowKind::TransferWithFee,
+                FlowAsset::Token { mint, .. },
+            ) => {
+                let source_info = index.get_by_address(accounts, source);
+                let destination_info = index.get_by_address(accounts, destination);
+                let mint = mint
+                    .cloned()
+                    .or_else(|| source_info.or(destination_info).map(|i| i.mint.clone()))?;
+                let owner = |info: Option<&TokenAccountInfo>, address: &String| {
+                    info.and_then(|i| i.owner.clone())
+                        .unwrap_or_else(|| address.clone())
+                };
+                (
+                    mint,
+                    owner(source_info, source),
+                    owner(destination_info, destination),
+                )
+            }
+            _ => return None,
+        };
+        if source_owner == destination_owner {
+            return None;
+        }
+
+        Some(Self {
+            path: flow.path.clone(),
+            mint,
+            source_owner,
+            destination_owner,
+            sent: amount,
+            received: amount.saturating_sub(flow.fee.unwrap_or(0)),
+        })
+    }
+}
@@ -5,24 +5,85 @@ pub mod circle_swap_filter;
 pub mod status_filter;
 
 use std::any::TypeId;
+use solana_sdk::message::MessageHeader;
 use solana_sdk::transaction::TransactionError;
 use solana_transaction_error::TransactionResult;
+use solana_transaction_status_client_types::TransactionTokenBalance;
 use crate::parsed_instruction::ParsedInstruction;
+use crate::token_account_index::TokenAccountIndex;
 use crate::utils::TransactionAccounts;
 
 #[derive(Default)]
 pub struct TransactionFilterContext {}
 
+/// 交易的meta数据
+///
+/// 之后可能继续增加字段，crate外请使用[TransactionMeta::new]及`with_*`方法构建
+#[non_exhaustive]
 pub struct TransactionMeta<'a> {
     pub err: Option<&'a TransactionError>,
     pub status: &'a TransactionResult<()>, // This field is deprecated.  See https://github.com/solana-labs/solana/issues/9302
     pub fee: u64,
     pub pre_balances: &'a [u64],
     pub post_balances: &'a [u64],
+    pub pre_token_balances: Option<&'a [TransactionTokenBalance]>,
+    pub post_token_balances: Option<&'a [TransactionTokenBalance]>,
     pub log_messages: Option<&'a [String]>,
     pub compute_units_consumed: Option<u64>,
 }
 
+impl<'a> TransactionMeta<'a> {
+    /// `err`从`status`中获取，其余可选字段为None
+    pub fn new(
+        status: &'a TransactionResult<()>,
+        fee: u64,
+        pre_balances: &'a [u64],
+        post_balances: &'a [u64],
+    ) -> Self {
+        Self {
+            err: status.as_ref().err(),
+            status,
+            fee,
+            pre_balances,
+            post_balances,
+            pre_token_balances: None,
+            post_token_balances: None,
+            log_messages: None,
+            compute_units_consumed: None,
+        }
+    }
+
+    pub fn with_token_balances(
+        mut self,
+        pre_token_balances: Option<&'a [TransactionTokenBalance]>,
+        post_token_balances: Option<&'a [TransactionTokenBalance]>,
+    ) -> Self {
+        self.pre_token_balances = pre_token_balances;
+        self.post_token_balances = post_token_balances;
+        self
+    }
+
+    pub fn with_log_messages(mut self, log_messages: Option<&'a [String]>) -> Self {
+        self.log_messages = log_messages;
+        self
+    }
+
+    pub fn with_compute_units_consumed(mut self, compute_units_consumed: Option<u64>) -> Self {
+        self.compute_units_consumed = compute_units_consumed;
+        self
+    }
+}
+
+impl TransactionMeta<'_> {
+    /// 根据token余额构建token帐户索引
+    pub fn token_account_index(&self) -> TokenAccountIndex {
+        TokenAccountIndex::from_token_balances(
+            self.pre_token_balances.unwrap_or_default(),
+            self.post_token_balances.unwrap_or_default(),
+        )
+    }
+}
+
 pub trait TransactionPropsProvider {
     fn get_accounts(&self) -> TransactionAccounts<'_, String>;
 
@@ -31,6 +92,11 @@ pub trait TransactionPropsProvider {
 
     fn get_parsed_instructions(&self) -> Option<&[ParsedInstruction]>;
     fn get_meta(&self) -> Option<TransactionMeta<'_>>;
+
+    /// 交易消息头，用于计算帐户的签名和可写权限
+    fn get_message_header(&self) -> Option<MessageHeader> {
+        None
+    }
 }
 
 pub trait TransactionFilter: Send + Sync + 'static {
@@ -120,6 +120,15 @@ impl<'a, AccountType> TransactionAccounts<'a, AccountType> {
         }
     }
 
+    /// 获取帐户在交易帐户列表中的索引
+    pub fn position<Q>(&self, account: &Q) -> Option<usize>
+    where
+        Q: ?Sized,
+        AccountType: PartialEq<Q>,
+    {
+        self.all_accounts().into_iter().position(|a| a == account)
+    }
+
     pub fn from_accounts(
         account_keys: Option<&'a [AccountType]>,
         loaded_writable_accounts: Option<&'a [AccountType]>,
@@ -201,6 +210,24 @@ impl<'a, AccountType> TransactionAccounts<'a, AccountType> {
         }
     }
 
+    /// 交易消息中静态帐户的数量
+    pub fn static_accounts_num(&self) -> usize {
+        match self {
+            TransactionAccounts::Static(s) => s.map(|s| s.len()).unwrap_or(0),
+            TransactionAccounts::Dynamic(d) => d.account_keys.map(|k| k.len()).unwrap_or(0),
+        }
+    }
+
+    /// 通过地址查找表加载的可写帐户数量
+    pub fn loaded_writable_accounts_num(&self) -> usize {
+        match self {
+            TransactionAccounts::Static(_) => 0,
+            TransactionAccounts::Dynamic(d) => {
+                d.loaded_writable_accounts.map(|k| k.len()).unwrap_or(0)
+            }
+        }
+    }
+
     pub fn get(&self, index: usize) -> Option<&'a AccountType> {
         match self {
             TransactionAccounts::Static(s) => s.map(|s| s.get(index)).flatten(),
@@ -0,0 +1,136 @@
+mod common;
+
+use block_insight_cross::transaction::account_meta::{
+    AccountMeta, AccountSource, account_permissions,
+};
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::transaction_filter::TransactionPropsProvider;
+use common::{encoded_transaction, success_meta};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+
+/// 静态帐户: 0 付款人, 1 签名只读, 2 可写, 3 声明可写的被调用程序, 4 可写, 5 只读
+/// 查找表帐户: 6 可写, 7 声明可写但被降级, 8 只读
+fn keys() -> Vec<String> {
+    (0..9).map(|_| Pubkey::new_unique().to_string()).collect()
+}
+
+/// jsonParsed格式中降级后的帐户标记
+fn json_parsed_account_keys(keys: &[String]) -> Vec<Value> {
+    keys.iter()
+        .enumerate()
+        .map(|(i, key)| {
+            json!({
+                "pubkey": key,
+                "writable": [0, 2, 4, 6].contains(&i),
+                "signer": i < 2,
+                "source": if i < 6 { "transaction" } else { "lookupTable" },
+            })
+        })
+        .collect()
+}
+
+fn json_parsed_transaction(
+    keys: &[String],
+    loaded_addresses: Option<Value>,
+) -> EncodedTransactionProps {
+    let mut meta = success_meta(keys.len());
+    if let Some(loaded_addresses) = loaded_addresses {
+        meta["loadedAddresses"] = loaded_addresses;
+    }
+    let encoded = encoded_transaction(
+        json!({
+            "accountKeys": json_parsed_account_keys(keys),
+            "recentBlockhash": "11111111111111111111111111111111",
+            "instructions": [],
+        }),
+        meta,
+    );
+    EncodedTransactionProps::try_from(&encoded).unwrap()
+}
+
+fn writable(transaction: &EncodedTransactionProps) -> Vec<bool> {
+    account_permissions(transaction)
+        .unwrap()
+        .iter()
+        .map(|a| a.is_writable)
+        .collect()
+}
+
+#[test]
+fn raw_message_uses_header_and_loaded_addresses() {
+    let keys = keys();
+    let mut meta = success_meta(keys.len());
+    meta["loadedAddresses"] = json!({"writable": keys[6..8], "readonly": keys[8..]});
+    let encoded = encoded_transaction(
+        json!({
+            "header": {
+                "numRequiredSignatures": 2,
+                "numReadonlySignedAccounts": 1,
+                "numReadonlyUnsignedAccounts": 1,
+            },
+            "accountKeys": keys[..6],
+            "recentBlockhash": "11111111111111111111111111111111",
+            "instructions": [],
+        }),
+        meta,
+    );
+    let transaction = EncodedTransactionProps::try_from(&encoded).unwrap();
+
+    let permissions = account_permissions(&transaction).unwrap();
+    assert_eq!(permissions.accounts_num(), 9);
+    assert_eq!(
+        permissions.get(0),
+        Some(AccountMeta {
+            index: 0,
+            is_signer: true,
+            is_writable: true,
+            is_fee_payer: true,
+            source: AccountSource::Static,
+        })
+    );
+    assert!(permissions.is_signer(1) && !permissions.is_writable(1));
+    assert_eq!(
+        writable(&transaction),
+        vec![true, false, true, true, true, false, true, true, false]
+    );
+    assert_eq!(permissions.source(7), Some(AccountSource::LookupWritable));
+    assert_eq!(permissions.source(8), Some(AccountSource::LookupReadonly));
+    assert_eq!(permissions.get(9), None);
+}
+
+#[test]
+fn json_parsed_header_ignores_demoted_accounts() {
+    let keys = keys();
+    let transaction = json_parsed_transaction(
+        &keys,
+        Some(json!({
+            "writable": keys[6..8],
+            "readonly": keys[8..],
+        })),
+    );
+
+    // 被降级的帐户3位于可写帐户之间，仍按声明视为可写
+    assert_eq!(
+        writable(&transaction),
+        vec![true, false, true, true, true, false, true, true, false]
+    );
+    let permissions = account_permissions(&transaction).unwrap();
+    assert_eq!(permissions.source(7), Some(AccountSource::LookupWritable));
+}
+
+#[test]
+fn json_parsed_without_loaded_addresses_keeps_account_order() {
+    let keys = keys();
+    let transaction = json_parsed_transaction(&keys, None);
+
+    // 没有loadedAddresses时无法识别查找表区域末尾被降级的帐户，但帐户顺序不变
+    let permissions = account_permissions(&transaction).unwrap();
+    assert_eq!(permissions.source(6), Some(AccountSource::LookupWritable));
+    assert_eq!(permissions.source(7), Some(AccountSource::LookupReadonly));
+    let accounts = transaction.get_accounts();
+    let resolved: Vec<_> = (0..keys.len())
+        .filter_map(|i| accounts.get(i).cloned())
+        .collect();
+    assert_eq!(resolved, keys);
+}
@@ -0,0 +1,260 @@
+use block_insight_cross::account_roles::AccountRoleError;
+use block_insight_cross::account_roles::token::{TokenAccountOwner, TokenAccountRoles};
+use block_insight_cross::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use block_insight_cross::parsed_instruction::{
+    InstructionDataFormat, InstructionProgramId, ParsedInstruction, ParsedInstructionData,
+};
+use block_insight_cross::transaction::account_meta::AccountPermissions;
+use block_insight_cross::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+use solana_sdk::message::MessageHeader;
+
+fn parsed(program_id: &Pubkey, accounts: Vec<u8>, data: &[u8]) -> ParsedInstruction {
+    ParsedInstruction {
+        program_id_index: 0,
+        program_id: Some(*program_id),
+        accounts,
+        instruction_data: ParsedInstructionData::parse(
+            InstructionProgramId::Pubkey(program_id),
+            InstructionDataFormat::Binary(data),
+        )
+        .unwrap(),
+        raw_data: None,
+        inner_instructions: None,
+    }
+}
+
+fn keys() -> Vec<Pubkey> {
+    (0..8).map(|_| Pubkey::new_unique()).collect()
+}
+
+#[test]
+fn initialize_account3_owner_from_data() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let owner = Pubkey::new_unique();
+    let data =
+        spl_token::instruction::initialize_account3(&spl_token::id(), &keys[1], &keys[2], &owner)
+            .unwrap()
+            .data;
+    let instruction = parsed(&spl_token::id(), vec![1, 2], &data);
+
+    assert_eq!(
+        instruction.token_account_roles(&accounts).unwrap(),
+        Some(TokenAccountRoles::InitializeAccount {
+            account: &keys[1],
+            mint: &keys[2],
+            owner: TokenAccountOwner::Pubkey(owner),
+        })
+    );
+}
+
+#[test]
+fn initialize_account_owner_from_accounts() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let data =
+        spl_token::instruction::initialize_account(&spl_token::id(), &keys[1], &keys[2], &keys[3])
+            .unwrap()
+            .data;
+    let instruction = parsed(&spl_token::id(), vec![1, 2, 3, 4], &data);
+
+    assert_eq!(
+        instruction.token_account_roles(&accounts).unwrap(),
+        Some(TokenAccountRoles::InitializeAccount {
+            account: &keys[1],
+            mint: &keys[2],
+            owner: TokenAccountOwner::Account(&keys[3]),
+        })
+    );
+}
+
+#[test]
+fn transfer_checked_with_fee_roles() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let data = spl_token_2022::extension::transfer_fee::instruction::transfer_checked_with_fee(
+        &spl_token_2022::id(),
+        &keys[1],
+        &keys[2],
+        &keys[3],
+        &keys[4],
+        &[],
+        1_000,
+        6,
+        10,
+    )
+    .unwrap()
+    .data;
+    let instruction = parsed(&spl_token_2022::id(), vec![1, 2, 3, 4], &data);
+
+    assert_eq!(
+        instruction.instruction_data,
+        ParsedInstructionData::SplToken2022(SplToken2022Instruction::TransferCheckedWithFee {
+            amount: 1_000,
+            decimals: 6,
+            fee: 10,
+        })
+    );
+    assert_eq!(
+        instruction.token_account_roles(&accounts).unwrap(),
+        Some(TokenAccountRoles::Transfer {
+            source: &keys[1],
+            mint: Some(&keys[2]),
+            destination: &keys[3],
+            authority: &keys[4],
+            signers: vec![],
+            extra_accounts: vec![],
+        })
+    );
+}
+
+#[test]
+fn spl_token_transfer_trailing_accounts_are_signers() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let data = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &keys[1],
+        &keys[2],
+        &keys[3],
+        &[&keys[4], &keys[5]],
+        5,
+    )
+    .unwrap()
+    .data;
+    let instruction = parsed(&spl_token::id(), vec![1, 2, 3, 4, 5], &data);
+
+    assert_eq!(
+        instruction.token_account_roles(&accounts).unwrap(),
+        Some(TokenAccountRoles::Transfer {
+            source: &keys[1],
+            mint: None,
+            destination: &keys[2],
+            authority: &keys[3],
+            signers: vec![&keys[4], &keys[5]],
+            extra_accounts: vec![],
+        })
+    );
+}
+
+#[test]
+fn token_2022_transfer_hook_accounts_are_not_signers() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let data = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::id(),
+        &keys[1],
+        &keys[2],
+        &keys[3],
+        &keys[4],
+        &[],
+        1_000,
+        6,
+    )
+    .unwrap()
+    .data;
+    // 5: hook程序, 6: 额外帐户列表PDA
+    let instruction = parsed(&spl_token_2022::id(), vec![1, 2, 3, 4, 5, 6], &data);
+
+    assert_eq!(
+        instruction.token_account_roles(&accounts).unwrap(),
+        Some(TokenAccountRoles::Transfer {
+            source: &keys[1],
+            mint: Some(&keys[2]),
+            destination: &keys[3],
+            authority: &keys[4],
+            signers: vec![],
+            extra_accounts: vec![&keys[5], &keys[6]],
+        })
+    );
+}
+
+#[test]
+fn other_extension_instruction_is_unsupported() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let data =
+        spl_token_2022::extension::memo_transfer::instruction::enable_required_transfer_memos(
+            &spl_token_2022::id(),
+            &keys[1],
+            &keys[2],
+            &[],
+        )
+        .unwrap()
+        .data;
+    let instruction = parsed(&spl_token_2022::id(), vec![1, 2], &data);
+
+    assert_eq!(
+        instruction.instruction_data,
+        ParsedInstructionData::SplToken2022(SplToken2022Instruction::MemoTransferExtension)
+    );
+    assert!(matches!(
+        instruction.token_account_roles(&accounts),
+        Err(AccountRoleError::UnsupportedInstruction(_))
+    ));
+}
+
+#[test]
+fn missing_account_is_error() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let data =
+        spl_token::instruction::transfer(&spl_token::id(), &keys[1], &keys[2], &keys[3], &[], 5)
+            .unwrap()
+            .data;
+    let instruction = parsed(&spl_token::id(), vec![1, 2], &data);
+
+    assert_eq!(
+        instruction.token_account_roles(&accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 2,
+            len: 2
+        })
+    );
+}
+
+#[test]
+fn token_2022_transfer_signers_from_permissions() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    // 前6个帐户是签名者
+    let permissions = AccountPermissions::new(
+        MessageHeader {
+            num_required_signatures: 6,
+            num_readonly_signed_accounts: 0,
+            num_readonly_unsigned_accounts: 0,
+        },
+        keys.len(),
+        0,
+        0,
+    );
+    let data = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::id(),
+        &keys[1],
+        &keys[2],
+        &keys[3],
+        &keys[4],
+        &[&keys[5]],
+        1_000,
+        6,
+    )
+    .unwrap()
+    .data;
+    // 5: 多签的签名者, 6: hook程序, 7: 额外帐户列表PDA
+    let instruction = parsed(&spl_token_2022::id(), vec![1, 2, 3, 4, 5, 6, 7], &data);
+
+    assert_eq!(
+        instruction
+            .token_account_roles_with_permissions(&accounts, &permissions)
+            .unwrap(),
+        Some(TokenAccountRoles::Transfer {
+            source: &keys[1],
+            mint: Some(&keys[2]),
+            destination: &keys[3],
+            authority: &keys[4],
+            signers: vec![&keys[5]],
+            extra_accounts: vec![&keys[6], &keys[7]],
+        })
+    );
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use block_insight_cross::parsed_instruction::diagnostics::ParseTransactionError;
+use block_insight_cross::parsed_instruction::{
+    ParseOptions, ParsedInstructionData, ParsedInstructionList,
+};
+use block_insight_cross::transaction::address_lookup_table::{
+    AddressLookupTableResolver, InMemoryLookupTables, LookupTableError,
+};
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::transaction_filter::TransactionPropsProvider;
+use common::{
+    TestTransaction, encoded_transaction, raw_message, success_meta, system_transfer_data,
+};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+use solana_sdk::address_lookup_table::instruction::{ProgramInstruction, extend_lookup_table};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::v0::{Message, MessageAddressTableLookup};
+use solana_sdk::message::{MessageHeader, VersionedMessage};
+use solana_sdk::signature::Signature;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::EncodedTransactionWithStatusMeta;
+
+const OPTIONS: ParseOptions = ParseOptions {
+    keep_raw_data: false,
+    keep_program_id: true,
+};
+
+/// 帐户: 0 付款人, 1 System, 2 查找表中的收款人(可写)
+fn v0_transfer(table: Pubkey) -> VersionedTransaction {
+    let data = system_transfer_data(7);
+    VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![Pubkey::new_unique(), solana_sdk::system_program::id()],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(1, data, vec![0, 2])],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: table,
+                writable_indexes: vec![1],
+                readonly_indexes: vec![],
+            }],
+        }),
+    }
+}
+
+#[test]
+fn parse_without_meta_resolves_lookup_accounts() {
+    let table = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let mut tables = InMemoryLookupTables::new();
+    tables.insert(
+        table.to_string(),
+        vec![Pubkey::new_unique().to_string(), recipient.to_string()],
+    );
+    let transaction = v0_transfer(table);
+
+    let report =
+        ParsedInstructionList::try_from_transaction_with_resolver(&transaction, &tables, &OPTIONS)
+            .unwrap();
+    assert!(report.is_complete());
+    let instruction = &report.instructions[0];
+    assert_eq!(
+        instruction.program_id,
+        Some(solana_sdk::system_program::id())
+    );
+    assert_eq!(
+        instruction.instruction_data,
+        ParsedInstructionData::System(SystemInstruction::Transfer { lamports: 7 })
+    );
+
+    let loaded = tables.resolve_message(&transaction.message).unwrap();
+    assert_eq!(loaded.writable, vec![recipient.to_string()]);
+    let keys: Vec<_> = transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    let accounts = loaded.transaction_accounts(&keys);
+    assert_eq!(
+        accounts.get(instruction.accounts[1] as usize),
+        Some(&recipient.to_string())
+    );
+}
+
+#[test]
+fn parse_without_meta_reports_missing_table() {
+    let table = Pubkey::new_unique();
+
+    let error = ParsedInstructionList::try_from_transaction_with_resolver(
+        &v0_transfer(table),
+        &InMemoryLookupTables::new(),
+        &OPTIONS,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        ParseTransactionError::LookupTable(LookupTableError::TableNotFound(t)) if t == table.to_string()
+    ));
+}
+
+/// 帐户: 0 权限帐户/付款人, 1 查找表, 2 System, 3 AddressLookupTable
+fn extend_keys(table: &Pubkey) -> Vec<String> {
+    vec![
+        Pubkey::new_unique().to_string(),
+        table.to_string(),
+        solana_sdk::system_program::id().to_string(),
+        solana_sdk::address_lookup_table::program::id().to_string(),
+    ]
+}
+
+/// 向查找表添加`new_addresses`的交易
+fn extend_transaction(
+    table: &Pubkey,
+    new_addresses: &[Pubkey],
+    meta: Value,
+) -> EncodedTransactionWithStatusMeta {
+    let keys = extend_keys(table);
+    let authority: Pubkey = keys[0].parse().unwrap();
+    let data = extend_lookup_table(*table, authority, Some(authority), new_addresses.to_vec()).data;
+    encoded_transaction(
+        raw_message(
+            &keys,
+            2,
+            vec![json!({
+                "programIdIndex": 3,
+                "accounts": [1, 0, 0, 2],
+                "data": bs58::encode(data).into_string(),
+                "stackHeight": null,
+            })],
+        ),
+        meta,
+    )
+}
+
+#[test]
+fn extend_is_applied_without_raw_data() {
+    let table = Pubkey::new_unique();
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let encoded = extend_transaction(&table, &new_addresses, success_meta(4));
+    let report = ParsedInstructionList::try_from_encoded_with_options(&encoded, &OPTIONS).unwrap();
+    assert!(report.instructions[0].raw_data.is_none());
+    assert_eq!(
+        report.instructions[0].instruction_data,
+        ParsedInstructionData::AddressLookupTable(ProgramInstruction::ExtendLookupTable {
+            new_addresses: new_addresses.clone(),
+        })
+    );
+
+    let mut tables = InMemoryLookupTables::new();
+    tables.apply_transaction(&EncodedTransactionProps::try_from(&encoded).unwrap());
+    let expected: Vec<_> = new_addresses.iter().map(|a| a.to_string()).collect();
+    assert_eq!(
+        tables.addresses(&table.to_string()),
+        Some(expected.as_slice())
+    );
+}
+
+#[test]
+fn failed_or_unconfirmed_extend_is_skipped() {
+    let table = Pubkey::new_unique();
+    let new_addresses = [Pubkey::new_unique()];
+    let mut tables = InMemoryLookupTables::new();
+
+    let mut meta = success_meta(4);
+    meta["err"] = json!({"InstructionError": [0, {"Custom": 1}]});
+    meta["status"] = json!({"Err": {"InstructionError": [0, {"Custom": 1}]}});
+    let failed = extend_transaction(&table, &new_addresses, meta);
+    tables.apply_transaction(&EncodedTransactionProps::try_from(&failed).unwrap());
+    assert!(tables.addresses(&table.to_string()).is_none());
+
+    // 没有meta时无法确认交易是否成功
+    let encoded = extend_transaction(&table, &new_addresses, success_meta(4));
+    let transaction = EncodedTransactionProps::try_from(&encoded).unwrap();
+    let without_meta = TestTransaction::new(
+        transaction
+            .get_accounts()
+            .all_accounts()
+            .into_iter()
+            .cloned()
+            .collect(),
+        transaction.parsed_instructions.0.clone(),
+    );
+    tables.apply_transaction(&without_meta);
+    assert!(tables.addresses(&table.to_string()).is_none());
+
+    tables.apply_transaction(&transaction);
+    assert_eq!(
+        tables.addresses(&table.to_string()),
+        Some([new_addresses[0].to_string()].as_slice())
+    );
+}
+
+#[test]
+fn json_parsed_extend_is_applied() {
+    let table = Pubkey::new_unique();
+    let new_address = Pubkey::new_unique().to_string();
+    let keys = extend_keys(&table);
+    let encoded = encoded_transaction(
+        json!({
+            "accountKeys": keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| json!({
+                    "pubkey": key,
+                    "writable": i < 2,
+                    "signer": i == 0,
+                    "source": "transaction",
+                }))
+                .collect::<Vec<_>>(),
+            "recentBlockhash": "11111111111111111111111111111111",
+            "instructions": [{
+                "program": "address-lookup-table",
+                "programId": keys[3],
+                "parsed": {
+                    "type": "extendLookupTable",
+                    "info": {
+                        "lookupTableAccount": keys[1],
+                        "lookupTableAuthority": keys[0],
+                        "payerAccount": keys[0],
+                        "systemProgram": keys[2],
+                        "newAddresses": [new_address],
+                    },
+                },
+                "stackHeight": null,
+            }],
+        }),
+        success_meta(keys.len()),
+    );
+
+    let transaction = EncodedTransactionProps::try_from(&encoded).unwrap();
+    assert_eq!(
+        transaction.parsed_instructions[0].accounts,
+        vec![1, 0, 0, 2]
+    );
+    let mut tables = InMemoryLookupTables::new();
+    tables.apply_transaction(&transaction);
+    assert_eq!(
+        tables.addresses(&table.to_string()),
+        Some([new_address].as_slice())
+    );
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use block_insight_cross::transaction::arbitrage::detect_arbitrage_cycles;
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::transaction_filter::circle_swap_filter::CircleSwapFilter;
+use block_insight_cross::transaction::transaction_filter::{
+    TransactionFilter, TransactionFilterContext,
+};
+use common::{encoded_transaction, raw_message, token_balance, token_transfer};
+use serde_json::json;
+use solana_pubkey::Pubkey;
+use std::collections::HashMap;
+
+const USER: usize = 0;
+const TOKEN: usize = 1;
+const DEX: usize = 2;
+
+/// 依次经过`hops`中各池子的兑换，每个元素为(输入mint, 输出mint, 输入数量, 输出数量)
+///
+/// 每跳是一个调用DEX的顶层指令，内部为用户转入池子和池子转给用户两次转帐，
+/// 相同mint对的兑换使用同一个池子
+fn swap_transaction(mints: usize, hops: &[(usize, usize, u64, u64)]) -> EncodedTransactionProps {
+    let key = || Pubkey::new_unique().to_string();
+    let mint_keys: Vec<String> = (0..mints).map(|_| key()).collect();
+    let mut keys = vec![key(), spl_token::id().to_string(), key()];
+    // 用户每种mint的token帐户
+    let user_accounts: Vec<usize> = (0..mints)
+        .map(|_| {
+            keys.push(key());
+            keys.len() - 1
+        })
+        .collect();
+    let mut token_balances: Vec<_> = user_accounts
+        .iter()
+        .enumerate()
+        .map(|(mint, account)| token_balance(*account, &mint_keys[mint], &keys[USER], 1_000))
+        .collect();
+
+    let mut pools = HashMap::new();
+    let mut instructions = vec![];
+    let mut inner_instructions = vec![];
+    for (hop, (input_mint, output_mint, input, output)) in hops.iter().enumerate() {
+        let (input_vault, output_vault) =
+            *pools.entry((input_mint, output_mint)).or_insert_with(|| {
+                let pool = key();
+                keys.extend([key(), key()]);
+                let vaults = (keys.len() - 2, keys.len() - 1);
+                token_balances.push(token_balance(
+                    vaults.0,
+                    &mint_keys[*input_mint],
+                    &pool,
+                    1_000_000,
+                ));
+                token_balances.push(token_balance(
+                    vaults.1,
+                    &mint_keys[*output_mint],
+                    &pool,
+                    1_000_000,
+                ));
+                vaults
+            });
+
+        instructions.push(json!({
+            "programIdIndex": DEX,
+            "accounts": [input_vault, output_vault, user_accounts[*input_mint], user_accounts[*output_mint], USER],
+            "data": bs58::encode([9u8]).into_string(),
+            "stackHeight": null,
+        }));
+        inner_instructions.push(json!({
+            "index": hop,
+            "instructions": [
+                token_transfer(TOKEN, user_accounts[*input_mint], input_vault, USER, *input),
+                token_transfer(TOKEN, output_vault, user_accounts[*output_mint], DEX, *output),
+            ],
+        }));
+    }
+
+    let encoded = encoded_transaction(
+        raw_message(&keys, 0, instructions),
+        json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": vec![0u64; keys.len()],
+            "postBalances": vec![0u64; keys.len()],
+            "innerInstructions": inner_instructions,
+            "logMessages": [],
+            "preTokenBalances": token_balances,
+            "postTokenBalances": token_balances,
+        }),
+    );
+    EncodedTransactionProps::try_from(&encoded).unwrap()
+}
+
+fn is_circle_swap(transaction: &EncodedTransactionProps) -> bool {
+    CircleSwapFilter.filter(transaction, &mut TransactionFilterContext::default())
+}
+
+#[test]
+fn two_hop_cycle() {
+    let transaction = swap_transaction(2, &[(0, 1, 100, 500), (1, 0, 500, 110)]);
+    assert!(is_circle_swap(&transaction));
+
+    let cycles = detect_arbitrage_cycles(&transaction);
+    assert_eq!(cycles.len(), 1);
+    let cycle = &cycles[0];
+    assert_eq!(cycle.mints.len(), 2);
+    assert_eq!(cycle.instruction_paths.len(), 4);
+    assert_eq!(cycle.path.first(), cycle.path.last());
+    assert_eq!(cycle.input_amount, 100);
+    assert_eq!(cycle.output_amount, 110);
+    assert_eq!(cycle.profit, 10);
+}
+
+#[test]
+fn three_hop_cycle() {
+    let transaction = swap_transaction(3, &[(0, 1, 100, 500), (1, 2, 500, 40), (2, 0, 40, 95)]);
+    assert!(is_circle_swap(&transaction));
+
+    let cycles = detect_arbitrage_cycles(&transaction);
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].mints.len(), 3);
+    assert_eq!(cycles[0].instruction_paths.len(), 6);
+    assert_eq!(cycles[0].profit, -5);
+}
+
+#[test]
+fn chained_swaps_without_return_are_not_a_cycle() {
+    let transaction = swap_transaction(3, &[(0, 1, 100, 500), (1, 2, 500, 40)]);
+    assert!(!is_circle_swap(&transaction));
+    assert!(detect_arbitrage_cycles(&transaction).is_empty());
+}
+
+#[test]
+fn oversized_graph_is_not_searched() {
+    // 每跳产生三条边，超过搜索上限
+    let hops: Vec<_> = (0..200).map(|i| (i % 2, (i + 1) % 2, 100, 100)).collect();
+    let transaction = swap_transaction(2, &hops);
+    assert!(detect_arbitrage_cycles(&transaction).is_empty());
+}
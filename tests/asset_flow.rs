@@ -0,0 +1,205 @@
+mod common;
+
+use block_insight_cross::asset_flow::{
+    AssetFlowKind, FlowAsset, extract_asset_flows, extract_asset_flows_with_balances,
+};
+use block_insight_cross::parsed_instruction::{ParseOptions, ParsedInstructionList};
+use block_insight_cross::utils::TransactionAccounts;
+use common::{account_keys, encoded_transaction, raw_message, success_meta};
+use serde_json::json;
+use solana_pubkey::Pubkey;
+
+const OPTIONS: ParseOptions = ParseOptions {
+    keep_raw_data: false,
+    keep_program_id: true,
+};
+
+fn transfer_with_fee_data(keys: &[String]) -> Vec<u8> {
+    let key = |i: usize| keys[i].parse::<Pubkey>().unwrap();
+    spl_token_2022::extension::transfer_fee::instruction::transfer_checked_with_fee(
+        &spl_token_2022::id(),
+        &key(1),
+        &key(2),
+        &key(3),
+        &key(0),
+        &[],
+        1_000,
+        6,
+        10,
+    )
+    .unwrap()
+    .data
+}
+
+fn assert_transfer_with_fee(list: &ParsedInstructionList, keys: &[String]) {
+    let accounts = TransactionAccounts::from_accounts(Some(keys), None, None);
+    let flows = extract_asset_flows(list, &accounts);
+
+    assert_eq!(flows.len(), 1);
+    let flow = &flows[0];
+    assert_eq!(flow.kind, AssetFlowKind::TransferWithFee);
+    assert_eq!(
+        flow.asset,
+        FlowAsset::Token {
+            mint: Some(&keys[2]),
+            decimals: Some(6),
+        }
+    );
+    assert_eq!(flow.source, Some(&keys[1]));
+    assert_eq!(flow.destination, Some(&keys[3]));
+    assert_eq!(flow.amount, Some(1_000));
+    assert_eq!(flow.fee, Some(10));
+}
+
+#[test]
+fn transfer_with_fee_without_raw_data() {
+    let keys = account_keys(4, &[spl_token_2022::id()]);
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            2,
+            vec![json!({
+                "programIdIndex": 4,
+                "accounts": [1, 2, 3, 0],
+                "data": bs58::encode(transfer_with_fee_data(&keys)).into_string(),
+                "stackHeight": null,
+            })],
+        ),
+        success_meta(keys.len()),
+    );
+
+    let report = ParsedInstructionList::try_from_encoded_with_options(&encoded, &OPTIONS).unwrap();
+    assert!(report.is_complete());
+    assert!(report.instructions[0].raw_data.is_none());
+    assert_transfer_with_fee(&report.instructions, &keys);
+}
+
+#[test]
+fn transfer_with_fee_from_json_parsed() {
+    let keys = account_keys(4, &[spl_token_2022::id()]);
+    let encoded = encoded_transaction(
+        json!({
+            "accountKeys": keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| json!({
+                    "pubkey": key,
+                    "writable": i == 1 || i == 3,
+                    "signer": i == 0,
+                    "source": "transaction",
+                }))
+                .collect::<Vec<_>>(),
+            "recentBlockhash": "11111111111111111111111111111111",
+            "instructions": [{
+                "program": "spl-token",
+                "programId": keys[4],
+                "parsed": {
+                    "type": "transferCheckedWithFee",
+                    "info": {
+                        "source": keys[1],
+                        "mint": keys[2],
+                        "destination": keys[3],
+                        "authority": keys[0],
+                        "tokenAmount": {
+                            "amount": "1000",
+                            "decimals": 6,
+                            "uiAmount": 0.001,
+                            "uiAmountString": "0.001",
+                        },
+                        "feeAmount": {
+                            "amount": "10",
+                            "decimals": 6,
+                            "uiAmount": 0.00001,
+                            "uiAmountString": "0.00001",
+                        },
+                    },
+                },
+                "stackHeight": null,
+            }],
+        }),
+        success_meta(keys.len()),
+    );
+
+    let report = ParsedInstructionList::try_from_encoded_with_options(&encoded, &OPTIONS).unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.instructions[0].accounts, vec![1, 2, 3, 0]);
+    assert_transfer_with_fee(&report.instructions, &keys);
+}
+
+/// 帐户: 0 owner, 1 被关闭的token帐户, 2 提取多余lamports的mint, 3 接收lamports的帐户, 4 Token-2022
+#[test]
+fn released_lamports_from_balances() {
+    let keys = account_keys(4, &[spl_token_2022::id()]);
+    let key = |i: usize| keys[i].parse::<Pubkey>().unwrap();
+    let close = spl_token_2022::instruction::close_account(
+        &spl_token_2022::id(),
+        &key(1),
+        &key(3),
+        &key(0),
+        &[],
+    )
+    .unwrap();
+    let withdraw = spl_token_2022::instruction::withdraw_excess_lamports(
+        &spl_token_2022::id(),
+        &key(2),
+        &key(3),
+        &key(0),
+        &[],
+    )
+    .unwrap();
+    let pre_balances = [1_000_000, 2_039_280, 1_461_600 + 500, 0, 1];
+    let post_balances = [995_000, 0, 1_461_600, 2_039_780, 1];
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            1,
+            vec![
+                json!({
+                    "programIdIndex": 4,
+                    "accounts": [1, 3, 0],
+                    "data": bs58::encode(close.data).into_string(),
+                    "stackHeight": null,
+                }),
+                json!({
+                    "programIdIndex": 4,
+                    "accounts": [2, 3, 0],
+                    "data": bs58::encode(withdraw.data).into_string(),
+                    "stackHeight": null,
+                }),
+            ],
+        ),
+        json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": pre_balances,
+            "postBalances": post_balances,
+            "innerInstructions": [],
+            "logMessages": [],
+        }),
+    );
+    let report = ParsedInstructionList::try_from_encoded_with_options(&encoded, &OPTIONS).unwrap();
+    assert!(report.is_complete());
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+
+    let flows = extract_asset_flows_with_balances(
+        &report.instructions,
+        &accounts,
+        &pre_balances,
+        &post_balances,
+    );
+    assert_eq!(flows.len(), 2);
+    assert_eq!(flows[0].kind, AssetFlowKind::CloseAccount);
+    assert_eq!(flows[0].asset, FlowAsset::Sol);
+    assert_eq!(flows[0].source, Some(&keys[1]));
+    assert_eq!(flows[0].destination, Some(&keys[3]));
+    assert_eq!(flows[0].amount, Some(2_039_280));
+    assert_eq!(flows[1].kind, AssetFlowKind::WithdrawExcessLamports);
+    assert_eq!(flows[1].source, Some(&keys[2]));
+    assert_eq!(flows[1].amount, Some(500));
+
+    // 没有meta时无法得到数量
+    let flows = extract_asset_flows(&report.instructions, &accounts);
+    assert_eq!(flows.len(), 2);
+    assert!(flows.iter().all(|flow| flow.amount.is_none()));
+}
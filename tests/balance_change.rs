@@ -0,0 +1,163 @@
+mod common;
+
+use block_insight_cross::transaction::balance_change::{
+    SolBalanceChange, TokenBalanceChange, sol_balance_changes, token_balance_changes,
+};
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use common::{encoded_transaction, raw_message, unique_keys};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+
+fn token_balance(account_index: u8, mint: &str, owner: Option<&str>, amount: u64) -> Value {
+    let mut balance = json!({
+        "accountIndex": account_index,
+        "mint": mint,
+        "uiTokenAmount": {
+            "uiAmount": amount as f64 / 1e6,
+            "decimals": 6,
+            "amount": amount.to_string(),
+            "uiAmountString": (amount as f64 / 1e6).to_string(),
+        },
+        "programId": spl_token::id().to_string(),
+    });
+    if let Some(owner) = owner {
+        balance["owner"] = json!(owner);
+    }
+    balance
+}
+
+fn props(
+    keys: &[String],
+    pre_balances: Vec<u64>,
+    post_balances: Vec<u64>,
+    pre_token_balances: Vec<Value>,
+    post_token_balances: Vec<Value>,
+) -> EncodedTransactionProps {
+    let encoded = encoded_transaction(
+        raw_message(keys, 0, vec![]),
+        json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": pre_balances,
+            "postBalances": post_balances,
+            "innerInstructions": [],
+            "logMessages": [],
+            "preTokenBalances": pre_token_balances,
+            "postTokenBalances": post_token_balances,
+        }),
+    );
+    EncodedTransactionProps::try_from(&encoded).unwrap()
+}
+
+#[test]
+fn token_changes_cover_pre_only_post_only_and_owner_less() {
+    let keys = unique_keys(5);
+    let mint = Pubkey::new_unique().to_string();
+    let owner = Pubkey::new_unique().to_string();
+    let props = props(
+        &keys,
+        vec![0; 5],
+        vec![0; 5],
+        vec![
+            // 交易中被关闭的帐户，只出现在pre中
+            token_balance(1, &mint, Some(&owner), 500),
+            // 余额没有变化
+            token_balance(2, &mint, Some(&keys[0]), 100),
+            // 没有owner，使用token帐户地址
+            token_balance(3, &mint, None, 300),
+        ],
+        vec![
+            token_balance(2, &mint, Some(&keys[0]), 100),
+            token_balance(3, &mint, None, 1_300),
+            // 交易中新建的帐户，只出现在post中
+            token_balance(4, &mint, Some(&owner), 700),
+        ],
+    );
+
+    let mut expected = vec![
+        TokenBalanceChange {
+            owner: owner.clone(),
+            mint: mint.clone(),
+            decimals: 6,
+            pre_amount: 500,
+            post_amount: 700,
+            change: 200,
+        },
+        TokenBalanceChange {
+            owner: keys[3].clone(),
+            mint: mint.clone(),
+            decimals: 6,
+            pre_amount: 300,
+            post_amount: 1_300,
+            change: 1_000,
+        },
+    ];
+    expected.sort_by(|a, b| (&a.owner, &a.mint).cmp(&(&b.owner, &b.mint)));
+    assert_eq!(token_balance_changes(&props), expected);
+}
+
+#[test]
+fn token_changes_from_one_side_only() {
+    let keys = unique_keys(5);
+    let mint = Pubkey::new_unique().to_string();
+    let props = props(
+        &keys,
+        vec![0; 5],
+        vec![0; 5],
+        vec![token_balance(1, &mint, None, 250)],
+        vec![],
+    );
+
+    let changes = token_balance_changes(&props);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].owner, keys[1]);
+    assert_eq!(changes[0].change, -250);
+    assert_eq!(changes[0].ui_change(), -0.00025);
+}
+
+#[test]
+fn sol_changes_exclude_fee() {
+    let keys = unique_keys(5);
+    let props = props(
+        &keys,
+        vec![1_000_000, 0, 10, 10, 0],
+        vec![894_000, 100_000, 10, 15, 1_000],
+        vec![],
+        vec![],
+    );
+
+    assert_eq!(
+        sol_balance_changes(&props),
+        vec![
+            SolBalanceChange {
+                account: keys[0].clone(),
+                pre_balance: 1_000_000,
+                post_balance: 894_000,
+                change: -101_000,
+                fee: 5000,
+            },
+            SolBalanceChange {
+                account: keys[1].clone(),
+                pre_balance: 0,
+                post_balance: 100_000,
+                change: 100_000,
+                fee: 0,
+            },
+            SolBalanceChange {
+                account: keys[3].clone(),
+                pre_balance: 10,
+                post_balance: 15,
+                change: 5,
+                fee: 0,
+            },
+            SolBalanceChange {
+                account: keys[4].clone(),
+                pre_balance: 0,
+                post_balance: 1_000,
+                change: 1_000,
+                fee: 0,
+            },
+        ]
+    );
+}
@@ -0,0 +1,139 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use block_insight_cross::parsed_instruction::ParsedInstructionData;
+use block_insight_cross::parsed_instruction::ParsedInstructionList;
+use block_insight_cross::parsed_instruction::diagnostics::ParseTransactionError;
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{Message, VersionedMessage, v0};
+use solana_sdk::signature::Signature;
+use solana_sdk::system_instruction::{self, SystemInstruction};
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::EncodedTransactionWithStatusMeta;
+
+/// 帐户: 0 付款人, 1 收款人, 2 System; 顶层一条转帐，其下有一条内部转帐
+fn transaction(versioned: bool) -> (VersionedTransaction, Pubkey, Pubkey) {
+    let payer = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let instructions = [system_instruction::transfer(&payer, &recipient, 1000)];
+    let message = if versioned {
+        VersionedMessage::V0(
+            v0::Message::try_compile(&payer, &instructions, &[], Hash::default()).unwrap(),
+        )
+    } else {
+        VersionedMessage::Legacy(Message::new(&instructions, Some(&payer)))
+    };
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message,
+    };
+    (transaction, payer, recipient)
+}
+
+fn meta() -> Value {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&1u64.to_le_bytes());
+    json!({
+        "err": null,
+        "status": {"Ok": null},
+        "fee": 5000,
+        "preBalances": [10_000, 0, 1],
+        "postBalances": [3_999, 1001, 1],
+        "innerInstructions": [{
+            "index": 0,
+            "instructions": [{
+                "programIdIndex": 2,
+                "accounts": [1, 0],
+                "data": bs58::encode(data).into_string(),
+                "stackHeight": 2,
+            }],
+        }],
+        "logMessages": [],
+    })
+}
+
+fn encoded(transaction: Value) -> EncodedTransactionWithStatusMeta {
+    serde_json::from_value(json!({
+        "transaction": transaction,
+        "meta": meta(),
+    }))
+    .unwrap()
+}
+
+fn assert_transfer(instructions: &ParsedInstructionList) {
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(instructions[0].program_id_index, 2);
+    assert_eq!(instructions[0].accounts, vec![0, 1]);
+    assert_eq!(
+        instructions[0].instruction_data,
+        ParsedInstructionData::System(SystemInstruction::Transfer { lamports: 1000 })
+    );
+    let inner = instructions[0].inner_instructions.as_ref().unwrap();
+    assert_eq!(inner.len(), 1);
+    assert_eq!(inner[0].accounts, vec![1, 0]);
+    assert_eq!(
+        inner[0].instruction_data,
+        ParsedInstructionData::System(SystemInstruction::Transfer { lamports: 1 })
+    );
+}
+
+#[test]
+fn legacy_base58_round_trip() {
+    let (transaction, payer, _) = transaction(false);
+    let bytes = bincode::serialize(&transaction).unwrap();
+    // 旧的base58格式为一个字符串
+    let encoded = encoded(json!(bs58::encode(bytes).into_string()));
+
+    let report = ParsedInstructionList::try_from_encoded(&encoded).unwrap();
+    assert!(report.is_complete());
+    assert_transfer(&report.instructions);
+    assert_eq!(
+        report.instructions[0].program_id,
+        Some(solana_sdk::system_program::id())
+    );
+    assert_eq!(transaction.message.static_account_keys()[0], payer);
+}
+
+#[test]
+fn base58_round_trip() {
+    let (transaction, _, _) = transaction(false);
+    let bytes = bincode::serialize(&transaction).unwrap();
+    let encoded = encoded(json!([bs58::encode(bytes).into_string(), "base58"]));
+
+    let report = ParsedInstructionList::try_from_encoded(&encoded).unwrap();
+    assert!(report.is_complete());
+    assert_transfer(&report.instructions);
+}
+
+#[test]
+fn base64_versioned_round_trip() {
+    let (transaction, _, recipient) = transaction(true);
+    let bytes = bincode::serialize(&transaction).unwrap();
+    let encoded = encoded(json!([STANDARD.encode(bytes), "base64"]));
+
+    let report = ParsedInstructionList::try_from_encoded(&encoded).unwrap();
+    assert!(report.is_complete());
+    assert_transfer(&report.instructions);
+    assert_eq!(transaction.message.static_account_keys()[1], recipient);
+}
+
+#[test]
+fn corrupt_payload_is_decode_error() {
+    let (transaction, _, _) = transaction(false);
+    let mut bytes = bincode::serialize(&transaction).unwrap();
+    bytes.truncate(bytes.len() / 2);
+
+    let encoded = encoded(json!([STANDARD.encode(&bytes), "base64"]));
+    assert!(matches!(
+        ParsedInstructionList::try_from_encoded(&encoded),
+        Err(ParseTransactionError::DecodeTransaction)
+    ));
+
+    // 不是合法的base64
+    let encoded = encoded(json!(["not base64!", "base64"]));
+    assert!(matches!(
+        ParsedInstructionList::try_from_encoded(&encoded),
+        Err(ParseTransactionError::DecodeTransaction)
+    ));
+}
@@ -0,0 +1,384 @@
+mod common;
+
+use block_insight_cross::events::builtin::{PUMP_FUN_PROGRAM, RAYDIUM_CLMM_PROGRAM};
+use block_insight_cross::parsed_instruction::ParsedInstruction;
+use block_insight_cross::pubkeys::ASSOCIATED_TOKEN_PROGRAM;
+use block_insight_cross::transaction::classifier::{
+    Classification, TransactionCategory, TransactionClassifier,
+};
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use common::{encoded_transaction, raw_message, token_transfer, unique_keys};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+
+const USER: usize = 0;
+const SYSTEM: usize = 1;
+const TOKEN: usize = 2;
+const PROGRAM: usize = 3;
+const MINT: usize = 4;
+const OTHER: usize = 5;
+const OTHERS: usize = 16;
+
+/// 帐户: 0 用户(签名者), 1 System, 2 Token, 3 `program`, 4 mint, 之后为其它帐户
+fn account_keys(program: &str) -> Vec<String> {
+    let mut keys = common::account_keys(
+        1,
+        &[
+            solana_sdk::system_program::id().to_string(),
+            spl_token::id().to_string(),
+            program.to_string(),
+        ],
+    );
+    keys.extend(unique_keys(OTHERS + 1));
+    keys
+}
+
+fn instruction(program_id_index: usize, accounts: &[usize], data: &[u8]) -> Value {
+    json!({
+        "programIdIndex": program_id_index,
+        "accounts": accounts,
+        "data": bs58::encode(data).into_string(),
+        "stackHeight": null,
+    })
+}
+
+fn system_transfer(from: usize, to: usize, lamports: u64, stack_height: Option<u32>) -> Value {
+    common::system_transfer(SYSTEM, from, to, lamports, stack_height)
+}
+
+fn initialize_mint(decimals: u8) -> Value {
+    let data = spl_token::instruction::initialize_mint2(
+        &spl_token::id(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        None,
+        decimals,
+    )
+    .unwrap()
+    .data;
+    instruction(TOKEN, &[MINT], &data)
+}
+
+fn mint_to(amount: u64) -> Value {
+    let data = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &[],
+        amount,
+    )
+    .unwrap()
+    .data;
+    instruction(TOKEN, &[MINT, OTHER, USER], &data)
+}
+
+/// 一次调用`program_id`的顶层指令的日志
+fn invocation_logs(program_id: &str, logs: &[&str]) -> Vec<String> {
+    let mut lines = vec![format!("Program {program_id} invoke [1]")];
+    lines.extend(logs.iter().map(|log| format!("Program log: {log}")));
+    lines.push(format!("Program {program_id} success"));
+    lines
+}
+
+fn meta(inner: Vec<Value>, log_messages: Vec<String>) -> Value {
+    json!({
+        "err": null,
+        "status": {"Ok": null},
+        "fee": 5000,
+        "preBalances": vec![0u64; OTHERS + 5],
+        "postBalances": vec![0u64; OTHERS + 5],
+        "innerInstructions": if inner.is_empty() {
+            json!([])
+        } else {
+            json!([{"index": 0, "instructions": inner}])
+        },
+        "logMessages": log_messages,
+    })
+}
+
+fn transaction(program: &str, instructions: Vec<Value>, meta: Value) -> EncodedTransactionProps {
+    let encoded = encoded_transaction(raw_message(&account_keys(program), 0, instructions), meta);
+    EncodedTransactionProps::try_from(&encoded).unwrap()
+}
+
+/// 去掉原始指令数据，模拟关闭`keep_raw_data`或jsonParsed格式的交易
+fn without_raw_data(mut transaction: EncodedTransactionProps) -> EncodedTransactionProps {
+    fn strip(instructions: &mut [ParsedInstruction]) {
+        for instruction in instructions {
+            instruction.raw_data = None;
+            if let Some(inner) = &mut instruction.inner_instructions {
+                strip(inner);
+            }
+        }
+    }
+    strip(&mut transaction.parsed_instructions.0);
+    transaction
+}
+
+fn find(
+    classifications: &[Classification],
+    category: TransactionCategory,
+) -> Option<&Classification> {
+    classifications.iter().find(|c| c.category == category)
+}
+
+fn label(transaction: &EncodedTransactionProps) -> Option<TransactionCategory> {
+    TransactionClassifier::new()
+        .label(transaction)
+        .map(|c| c.category)
+}
+
+#[test]
+fn vote() {
+    let program = solana_sdk::vote::program::id().to_string();
+    let transaction = transaction(
+        &program,
+        vec![instruction(PROGRAM, &[USER], &[])],
+        meta(vec![], vec![]),
+    );
+
+    let classification = TransactionClassifier::new().label(&transaction).unwrap();
+    assert_eq!(classification.category, TransactionCategory::Vote);
+    assert_eq!(classification.confidence, 1.0);
+}
+
+#[test]
+fn failed_transaction_has_no_flow_categories() {
+    let program = Pubkey::new_unique().to_string();
+    let mut meta = meta(vec![], vec![]);
+    let err = json!({"InstructionError": [0, {"Custom": 1}]});
+    meta["err"] = err.clone();
+    meta["status"] = json!({"Err": err});
+    let transaction = transaction(
+        &program,
+        vec![system_transfer(USER, OTHER, 1_000_000, None)],
+        meta,
+    );
+
+    let classifications = TransactionClassifier::new().classify(&transaction);
+    assert_eq!(classifications.len(), 1);
+    assert_eq!(classifications[0].category, TransactionCategory::Failed);
+}
+
+#[test]
+fn top_level_transfers() {
+    let program = Pubkey::new_unique().to_string();
+    let mut token = token_transfer(TOKEN, OTHER, OTHER + 1, USER, 100);
+    token["stackHeight"] = Value::Null;
+    let transaction = transaction(
+        &program,
+        vec![system_transfer(USER, OTHER, 1_000_000, None), token],
+        meta(vec![], vec![]),
+    );
+
+    let classifications = TransactionClassifier::new().classify(&transaction);
+    let sol = find(&classifications, TransactionCategory::SolTransfer).unwrap();
+    assert_eq!(sol.confidence, 0.9);
+    assert_eq!(sol.evidence[0].description, "transfers 1000000 lamports");
+    let token = find(&classifications, TransactionCategory::TokenTransfer).unwrap();
+    assert_eq!(token.confidence, 0.9);
+    assert_eq!(
+        label(&transaction),
+        Some(TransactionCategory::TokenTransfer)
+    );
+}
+
+#[test]
+fn inner_transfers_below_default_threshold() {
+    let program = Pubkey::new_unique().to_string();
+    let transaction = transaction(
+        &program,
+        vec![instruction(PROGRAM, &[USER, OTHER], &[])],
+        meta(
+            vec![
+                system_transfer(USER, OTHER, 1_000_000, Some(2)),
+                token_transfer(TOKEN, OTHER + 1, OTHER + 2, USER, 100),
+            ],
+            vec![],
+        ),
+    );
+
+    let classifications = TransactionClassifier::new().classify(&transaction);
+    assert!(find(&classifications, TransactionCategory::SolTransfer).is_none());
+    assert!(find(&classifications, TransactionCategory::TokenTransfer).is_none());
+
+    // 降低阈值后仍然可以看到低置信度的转帐
+    let classifications = TransactionClassifier::new()
+        .with_min_confidence(0.2)
+        .classify(&transaction);
+    let sol = find(&classifications, TransactionCategory::SolTransfer).unwrap();
+    assert_eq!(sol.confidence, 0.3);
+}
+
+#[test]
+fn nft_mint() {
+    let program = Pubkey::new_unique().to_string();
+    let transaction = transaction(
+        &program,
+        vec![initialize_mint(0), mint_to(1)],
+        meta(vec![], vec![]),
+    );
+
+    let classifications = TransactionClassifier::new().classify(&transaction);
+    let nft = find(&classifications, TransactionCategory::NftMint).unwrap();
+    assert_eq!(nft.confidence, 0.85);
+    assert!(find(&classifications, TransactionCategory::TokenLaunch).is_none());
+    assert_eq!(label(&transaction), Some(TransactionCategory::NftMint));
+}
+
+#[test]
+fn initialize_mint_alone_is_not_token_launch() {
+    let program = Pubkey::new_unique().to_string();
+    let transaction = transaction(
+        &program,
+        vec![initialize_mint(6), mint_to(1_000_000)],
+        meta(vec![], vec![]),
+    );
+
+    let classifications = TransactionClassifier::new()
+        .with_min_confidence(0.0)
+        .classify(&transaction);
+    assert!(find(&classifications, TransactionCategory::TokenLaunch).is_none());
+}
+
+#[test]
+fn swap_on_pool_program_is_not_token_launch() {
+    let logs = [
+        invocation_logs(&spl_token::id().to_string(), &[]),
+        invocation_logs(PUMP_FUN_PROGRAM, &["Instruction: Buy"]),
+    ]
+    .concat();
+    let transaction = without_raw_data(transaction(
+        PUMP_FUN_PROGRAM,
+        vec![
+            initialize_mint(6),
+            instruction(PROGRAM, &[USER, MINT], &[0; 8]),
+        ],
+        meta(vec![], logs),
+    ));
+
+    let classifications = TransactionClassifier::new()
+        .with_min_confidence(0.0)
+        .classify(&transaction);
+    assert!(find(&classifications, TransactionCategory::TokenLaunch).is_none());
+}
+
+#[test]
+fn token_launch_from_program_logs() {
+    let logs = [
+        invocation_logs(&spl_token::id().to_string(), &[]),
+        invocation_logs(PUMP_FUN_PROGRAM, &["Instruction: Create"]),
+    ]
+    .concat();
+    let transaction = without_raw_data(transaction(
+        PUMP_FUN_PROGRAM,
+        vec![
+            initialize_mint(6),
+            instruction(PROGRAM, &[MINT, USER], &[0; 8]),
+        ],
+        meta(vec![], logs),
+    ));
+
+    let classification = TransactionClassifier::new().label(&transaction).unwrap();
+    assert_eq!(classification.category, TransactionCategory::TokenLaunch);
+    assert_eq!(classification.confidence, 0.95);
+    assert_eq!(classification.evidence.len(), 2);
+    assert_eq!(
+        classification.evidence[1].description,
+        format!("creates pool with {PUMP_FUN_PROGRAM} create")
+    );
+}
+
+#[test]
+fn token_launch_from_anchor_discriminator() {
+    let hash = solana_sdk::hash::hashv(&[b"global:", b"create_pool"]);
+    let transaction = transaction(
+        RAYDIUM_CLMM_PROGRAM,
+        vec![
+            initialize_mint(9),
+            instruction(PROGRAM, &[USER, OTHER], &hash.to_bytes()[..8]),
+        ],
+        meta(vec![], vec![]),
+    );
+
+    // 池子的帐户中没有该mint，置信度较低
+    let classification = TransactionClassifier::new().label(&transaction).unwrap();
+    assert_eq!(classification.category, TransactionCategory::TokenLaunch);
+    assert_eq!(classification.confidence, 0.7);
+}
+
+#[test]
+fn program_deploy_without_raw_data() {
+    let program = solana_sdk::bpf_loader_upgradeable::id().to_string();
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&4096u64.to_le_bytes());
+    let transaction = without_raw_data(transaction(
+        &program,
+        vec![instruction(
+            PROGRAM,
+            &[
+                USER,
+                OTHER,
+                OTHER + 1,
+                OTHER + 2,
+                OTHER + 3,
+                OTHER + 4,
+                SYSTEM,
+                USER,
+            ],
+            &data,
+        )],
+        meta(vec![], vec![]),
+    ));
+
+    let classification = TransactionClassifier::new().label(&transaction).unwrap();
+    assert_eq!(classification.category, TransactionCategory::ProgramDeploy);
+    assert_eq!(classification.confidence, 0.95);
+    assert_eq!(classification.evidence[0].description, "deploys program");
+}
+
+#[test]
+fn stake_action() {
+    let program = solana_sdk::stake::program::id().to_string();
+    let transaction = transaction(
+        &program,
+        vec![instruction(PROGRAM, &[OTHER, USER], &[])],
+        meta(vec![], vec![]),
+    );
+
+    assert_eq!(label(&transaction), Some(TransactionCategory::StakeAction));
+}
+
+#[test]
+fn ata_creation_spam() {
+    // 帐户: 0.付款人 1.ATA 2.钱包 3.mint
+    let create = |i: usize| {
+        instruction(
+            PROGRAM,
+            &[USER, OTHER + i, OTHER + 8 + i, MINT, SYSTEM, TOKEN],
+            &[],
+        )
+    };
+
+    let below_threshold = transaction(
+        ASSOCIATED_TOKEN_PROGRAM,
+        (0..4).map(create).collect(),
+        meta(vec![], vec![]),
+    );
+    let classifications = TransactionClassifier::new().classify(&below_threshold);
+    assert!(find(&classifications, TransactionCategory::AtaCreationSpam).is_none());
+
+    let transaction = transaction(
+        ASSOCIATED_TOKEN_PROGRAM,
+        (0..5).map(create).collect(),
+        meta(vec![], vec![]),
+    );
+    let classification = TransactionClassifier::new().label(&transaction).unwrap();
+    assert_eq!(
+        classification.category,
+        TransactionCategory::AtaCreationSpam
+    );
+    assert!((classification.confidence - 0.75).abs() < 1e-6);
+    assert_eq!(classification.evidence.len(), 5);
+}
@@ -0,0 +1,184 @@
+#![allow(dead_code)]
+
+use block_insight_cross::parsed_instruction::ParsedInstruction;
+use block_insight_cross::transaction::transaction_filter::{
+    TransactionMeta, TransactionPropsProvider,
+};
+use block_insight_cross::utils::TransactionAccounts;
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+use solana_transaction_error::TransactionResult;
+use solana_transaction_status_client_types::EncodedTransactionWithStatusMeta;
+
+/// `n`个随机帐户
+pub fn unique_keys(n: usize) -> Vec<String> {
+    (0..n).map(|_| Pubkey::new_unique().to_string()).collect()
+}
+
+/// `n`个随机帐户，之后为`programs`
+pub fn account_keys<P: ToString>(n: usize, programs: &[P]) -> Vec<String> {
+    let mut keys = unique_keys(n);
+    keys.extend(programs.iter().map(|p| p.to_string()));
+    keys
+}
+
+/// 只有第0个帐户签名的json格式消息，最后`readonly_unsigned`个帐户只读
+pub fn raw_message(
+    account_keys: &[String],
+    readonly_unsigned: usize,
+    instructions: Vec<Value>,
+) -> Value {
+    json!({
+        "header": {
+            "numRequiredSignatures": 1,
+            "numReadonlySignedAccounts": 0,
+            "numReadonlyUnsignedAccounts": readonly_unsigned,
+        },
+        "accountKeys": account_keys,
+        "recentBlockhash": "11111111111111111111111111111111",
+        "instructions": instructions,
+    })
+}
+
+/// 由json形式的消息和meta构建RPC返回的交易
+pub fn encoded_transaction(message: Value, meta: Value) -> EncodedTransactionWithStatusMeta {
+    serde_json::from_value(json!({
+        "transaction": {
+            "signatures": ["1111111111111111111111111111111111111111111111111111111111111111"],
+            "message": message,
+        },
+        "meta": meta,
+    }))
+    .expect("invalid encoded transaction fixture")
+}
+
+/// 成功交易的最小meta
+pub fn success_meta(accounts_len: usize) -> Value {
+    json!({
+        "err": null,
+        "status": {"Ok": null},
+        "fee": 5000,
+        "preBalances": vec![0u64; accounts_len],
+        "postBalances": vec![0u64; accounts_len],
+        "innerInstructions": [],
+        "logMessages": [],
+    })
+}
+
+pub fn system_transfer_data(lamports: u64) -> Vec<u8> {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data
+}
+
+/// System Transfer指令，顶层指令的`stack_height`为None
+pub fn system_transfer(
+    program_id_index: usize,
+    from: usize,
+    to: usize,
+    lamports: u64,
+    stack_height: Option<u32>,
+) -> Value {
+    json!({
+        "programIdIndex": program_id_index,
+        "accounts": [from, to],
+        "data": bs58::encode(system_transfer_data(lamports)).into_string(),
+        "stackHeight": stack_height,
+    })
+}
+
+pub fn token_transfer_data(amount: u64) -> Vec<u8> {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+/// stack height为2的SPL Token Transfer内部指令
+pub fn token_transfer(
+    program_id_index: usize,
+    source: usize,
+    destination: usize,
+    authority: usize,
+    amount: u64,
+) -> Value {
+    json!({
+        "programIdIndex": program_id_index,
+        "accounts": [source, destination, authority],
+        "data": bs58::encode(token_transfer_data(amount)).into_string(),
+        "stackHeight": 2,
+    })
+}
+
+/// decimals为0的SPL Token余额
+pub fn token_balance(account_index: usize, mint: &str, owner: &str, amount: u64) -> Value {
+    json!({
+        "accountIndex": account_index,
+        "mint": mint,
+        "uiTokenAmount": {
+            "uiAmount": amount as f64,
+            "decimals": 0,
+            "amount": amount.to_string(),
+            "uiAmountString": amount.to_string(),
+        },
+        "owner": owner,
+        "programId": spl_token::id().to_string(),
+    })
+}
+
+/// 直接由解析好的指令构建的交易，不保留原始的编码数据
+pub struct TestTransaction {
+    pub account_keys: Vec<String>,
+    pub signatures: Vec<String>,
+    pub instructions: Vec<ParsedInstruction>,
+    pub status: TransactionResult<()>,
+    pub fee: u64,
+    /// 交易前后的lamports余额相同，为None时交易没有meta
+    pub balances: Option<Vec<u64>>,
+}
+
+impl TestTransaction {
+    /// 没有签名和meta的交易
+    pub fn new(account_keys: Vec<String>, instructions: Vec<ParsedInstruction>) -> Self {
+        Self {
+            account_keys,
+            signatures: vec![],
+            instructions,
+            status: Ok(()),
+            fee: 0,
+            balances: None,
+        }
+    }
+
+    /// 带有一个签名和成功的meta，所有帐户余额为0
+    pub fn with_meta(mut self, fee: u64) -> Self {
+        self.signatures =
+            vec!["1111111111111111111111111111111111111111111111111111111111111111".to_string()];
+        self.fee = fee;
+        self.balances = Some(vec![0; self.account_keys.len()]);
+        self
+    }
+}
+
+impl TransactionPropsProvider for TestTransaction {
+    fn get_accounts(&self) -> TransactionAccounts<'_, String> {
+        TransactionAccounts::from_accounts(Some(self.account_keys.as_slice()), None, None)
+    }
+
+    fn get_signatures(&self) -> Option<&[String]> {
+        Some(self.signatures.as_slice())
+    }
+
+    fn get_parsed_instructions(&self) -> Option<&[ParsedInstruction]> {
+        Some(self.instructions.as_slice())
+    }
+
+    fn get_meta(&self) -> Option<TransactionMeta<'_>> {
+        let balances = self.balances.as_ref()?;
+        Some(TransactionMeta::new(
+            &self.status,
+            self.fee,
+            balances,
+            balances,
+        ))
+    }
+}
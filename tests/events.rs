@@ -0,0 +1,245 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use block_insight_cross::events::builtin::{
+    PUMP_FUN_PROGRAM, PumpFunTradeEvent, RAYDIUM_CLMM_PROGRAM, RaydiumClmmSwapEvent,
+};
+use block_insight_cross::events::idl::IdlEventDecoder;
+use block_insight_cross::events::{DecodedEvent, EventRegistry, event_discriminator};
+use block_insight_cross::parsed_instruction::instruction_iter::InstructionPath;
+use block_insight_cross::program_log::ProgramLogs;
+use serde_json::json;
+use solana_pubkey::Pubkey;
+
+const PROGRAM: &str = "11111111111111111111111111111111";
+
+fn trade_event() -> PumpFunTradeEvent {
+    PumpFunTradeEvent {
+        mint: Pubkey::new_unique(),
+        sol_amount: 1_000_000,
+        token_amount: 5_000_000,
+        is_buy: true,
+        user: Pubkey::new_unique(),
+        timestamp: 1_700_000_000,
+        virtual_sol_reserves: 30_000_000_000,
+        virtual_token_reserves: 1_000_000_000_000,
+    }
+}
+
+/// 按borsh格式编码TradeEvent, 末尾附加新版本才有的字段
+fn encode_trade_event(event: &PumpFunTradeEvent) -> Vec<u8> {
+    let mut data = event_discriminator("TradeEvent").to_vec();
+    data.extend_from_slice(event.mint.as_ref());
+    data.extend_from_slice(&event.sol_amount.to_le_bytes());
+    data.extend_from_slice(&event.token_amount.to_le_bytes());
+    data.push(event.is_buy as u8);
+    data.extend_from_slice(event.user.as_ref());
+    data.extend_from_slice(&event.timestamp.to_le_bytes());
+    data.extend_from_slice(&event.virtual_sol_reserves.to_le_bytes());
+    data.extend_from_slice(&event.virtual_token_reserves.to_le_bytes());
+    data.extend_from_slice(&[0xff; 16]);
+    data
+}
+
+fn logs(program_id: &str, data: &[u8]) -> ProgramLogs {
+    ProgramLogs::parse(&[
+        format!("Program {PROGRAM} invoke [1]"),
+        format!("Program {program_id} invoke [2]"),
+        "Program log: Instruction: Buy".to_string(),
+        format!("Program data: {}", STANDARD.encode(data)),
+        format!("Program {program_id} success"),
+        format!("Program {PROGRAM} success"),
+    ])
+}
+
+#[test]
+fn discriminator_matches_anchor() {
+    // pump.fun IDL中TradeEvent的discriminator
+    assert_eq!(
+        event_discriminator("TradeEvent"),
+        [189, 219, 127, 211, 78, 230, 97, 238]
+    );
+}
+
+#[test]
+fn builtin_event_from_logs() {
+    let event = trade_event();
+    let logs = logs(PUMP_FUN_PROGRAM, &encode_trade_event(&event));
+
+    let events = EventRegistry::with_builtin().decode_logs(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].path, InstructionPath(vec![0, 0]));
+    assert_eq!(events[0].program_id, PUMP_FUN_PROGRAM);
+    assert_eq!(events[0].event, DecodedEvent::PumpFunTrade(event));
+}
+
+#[test]
+fn events_are_keyed_by_program() {
+    let data = encode_trade_event(&trade_event());
+    let registry = EventRegistry::with_builtin();
+
+    // 相同的discriminator由其它程序输出时不解码
+    assert!(registry.decode_logs(&logs(PROGRAM, &data)).is_empty());
+    assert!(registry.decode(RAYDIUM_CLMM_PROGRAM, &data).is_none());
+    assert!(
+        EventRegistry::new()
+            .decode(PUMP_FUN_PROGRAM, &data)
+            .is_none()
+    );
+}
+
+#[test]
+fn truncated_event_is_not_decoded() {
+    let data = encode_trade_event(&trade_event());
+    let registry = EventRegistry::with_builtin();
+
+    assert!(registry.decode(PUMP_FUN_PROGRAM, &data[..40]).is_none());
+    assert!(registry.decode(PUMP_FUN_PROGRAM, &data[..7]).is_none());
+}
+
+#[test]
+fn raydium_clmm_swap_event() {
+    let event = RaydiumClmmSwapEvent {
+        pool_state: Pubkey::new_unique(),
+        sender: Pubkey::new_unique(),
+        token_account_0: Pubkey::new_unique(),
+        token_account_1: Pubkey::new_unique(),
+        amount_0: 100,
+        transfer_fee_0: 1,
+        amount_1: 200,
+        transfer_fee_1: 2,
+        zero_for_one: false,
+        sqrt_price_x64: 1 << 64,
+        liquidity: u128::MAX,
+        tick: -42,
+    };
+    let mut data = event_discriminator("SwapEvent").to_vec();
+    for key in [
+        event.pool_state,
+        event.sender,
+        event.token_account_0,
+        event.token_account_1,
+    ] {
+        data.extend_from_slice(key.as_ref());
+    }
+    for amount in [
+        event.amount_0,
+        event.transfer_fee_0,
+        event.amount_1,
+        event.transfer_fee_1,
+    ] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    data.push(event.zero_for_one as u8);
+    data.extend_from_slice(&event.sqrt_price_x64.to_le_bytes());
+    data.extend_from_slice(&event.liquidity.to_le_bytes());
+    data.extend_from_slice(&event.tick.to_le_bytes());
+
+    assert_eq!(
+        EventRegistry::with_builtin().decode(RAYDIUM_CLMM_PROGRAM, &data),
+        Some(DecodedEvent::RaydiumClmmSwap(event))
+    );
+}
+
+/// 事件数据: discriminator, u64, Option<pubkey>, Vec<u16>, enum, string, u128
+fn idl_event_data(discriminator: [u8; 8], owner: &Pubkey) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    data.extend_from_slice(&7u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(owner.as_ref());
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(b"abc");
+    data.extend_from_slice(&u128::MAX.to_le_bytes());
+    data
+}
+
+#[test]
+fn idl_event_new_format() {
+    let discriminator = [1, 2, 3, 4, 5, 6, 7, 8];
+    let idl = json!({
+        "events": [{"name": "Deposited", "discriminator": discriminator}],
+        "types": [
+            {
+                "name": "Deposited",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        {"name": "amount", "type": "u64"},
+                        {"name": "owner", "type": {"option": "pubkey"}},
+                        {"name": "bins", "type": {"vec": "u16"}},
+                        {"name": "side", "type": {"defined": {"name": "Side"}}},
+                        {"name": "memo", "type": "string"},
+                        {"name": "liquidity", "type": "u128"},
+                    ],
+                },
+            },
+            {
+                "name": "Side",
+                "type": {"kind": "enum", "variants": [{"name": "Bid"}, {"name": "Ask"}]},
+            },
+        ],
+    });
+    let owner = Pubkey::new_unique();
+    let mut registry = EventRegistry::new();
+    registry.register(IdlEventDecoder::from_json(PROGRAM, &idl).unwrap());
+
+    assert_eq!(
+        registry.decode(PROGRAM, &idl_event_data(discriminator, &owner)),
+        Some(DecodedEvent::Idl {
+            name: "Deposited".to_string(),
+            fields: json!({
+                "amount": 7,
+                "owner": owner.to_string(),
+                "bins": [1, 2],
+                "side": "Ask",
+                "memo": "abc",
+                "liquidity": u128::MAX.to_string(),
+            }),
+        })
+    );
+}
+
+#[test]
+fn idl_event_legacy_format() {
+    // 旧格式没有discriminator, 字段直接写在事件中
+    let idl = json!({
+        "events": [{
+            "name": "Deposited",
+            "fields": [
+                {"name": "amount", "type": "u64", "index": false},
+                {"name": "owner", "type": {"option": "publicKey"}, "index": false},
+                {"name": "bins", "type": {"vec": "u16"}, "index": false},
+                {"name": "side", "type": {"defined": "Side"}, "index": false},
+                {"name": "memo", "type": "string", "index": false},
+                {"name": "liquidity", "type": "u128", "index": false},
+            ],
+        }],
+        "types": [{
+            "name": "Side",
+            "type": {"kind": "enum", "variants": [{"name": "Bid"}, {"name": "Ask"}]},
+        }],
+    });
+    let owner = Pubkey::new_unique();
+    let decoder = IdlEventDecoder::from_json(PROGRAM, &idl).unwrap();
+    let mut registry = EventRegistry::new();
+    registry.register(decoder);
+
+    let data = idl_event_data(event_discriminator("Deposited"), &owner);
+    let Some(DecodedEvent::Idl { name, fields }) = registry.decode(PROGRAM, &data) else {
+        panic!("event not decoded");
+    };
+    assert_eq!(name, "Deposited");
+    assert_eq!(fields["owner"], json!(owner.to_string()));
+    assert_eq!(fields["side"], json!("Ask"));
+    // 数据不足时解码失败
+    assert!(registry.decode(PROGRAM, &data[..data.len() - 1]).is_none());
+}
+
+#[test]
+fn idl_event_without_type_is_error() {
+    let idl = json!({"events": [{"name": "Missing", "discriminator": [0, 0, 0, 0, 0, 0, 0, 0]}]});
+    assert!(IdlEventDecoder::from_json(PROGRAM, &idl).is_err());
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use block_insight_cross::events::builtin::RAYDIUM_CLMM_PROGRAM;
+use block_insight_cross::parsed_instruction::instruction_iter::InstructionPath;
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::failure::{ErrorRegistry, diagnose_failure};
+use common::{encoded_transaction, raw_message};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+use solana_sdk::instruction::InstructionError;
+
+const ROUTER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+/// 帐户: 0 付款人, 1 `program_id`; 一条调用`program_id`的顶层指令
+fn failed_transaction(
+    program_id: &str,
+    err: Value,
+    log_messages: Vec<String>,
+) -> EncodedTransactionProps {
+    let keys = vec![Pubkey::new_unique().to_string(), program_id.to_string()];
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            1,
+            vec![json!({
+                "programIdIndex": 1,
+                "accounts": [0],
+                "data": "",
+                "stackHeight": null,
+            })],
+        ),
+        json!({
+            "err": err,
+            "status": {"Err": err},
+            "fee": 5000,
+            "preBalances": [0, 0],
+            "postBalances": [0, 0],
+            "innerInstructions": [],
+            "logMessages": log_messages,
+        }),
+    );
+    EncodedTransactionProps::try_from(&encoded).unwrap()
+}
+
+fn custom_error(code: u32) -> Value {
+    json!({"InstructionError": [0, {"Custom": code}]})
+}
+
+#[test]
+fn error_in_cpi_is_attributed_to_innermost_program() {
+    let logs = vec![
+        format!("Program {ROUTER} invoke [1]"),
+        "Program log: Instruction: Route".to_string(),
+        format!("Program {RAYDIUM_CLMM_PROGRAM} invoke [2]"),
+        "Program log: Instruction: Swap".to_string(),
+        "Program log: AnchorError occurred. Error Code: TooLittleOutputReceived.".to_string(),
+        format!("Program {RAYDIUM_CLMM_PROGRAM} consumed 50000 of 180000 compute units"),
+        format!("Program {RAYDIUM_CLMM_PROGRAM} failed: custom program error: 0x1786"),
+        format!("Program {ROUTER} consumed 70000 of 200000 compute units"),
+        format!("Program {ROUTER} failed: custom program error: 0x1786"),
+    ];
+    let transaction = failed_transaction(ROUTER, custom_error(6022), logs);
+
+    let diagnostics = diagnose_failure(&transaction, &ErrorRegistry::with_builtin()).unwrap();
+    assert_eq!(diagnostics.instruction_index, 0);
+    assert_eq!(diagnostics.path, InstructionPath(vec![0, 0]));
+    assert_eq!(
+        diagnostics.program_id.as_deref(),
+        Some(RAYDIUM_CLMM_PROGRAM)
+    );
+    assert_eq!(diagnostics.program_name.as_deref(), Some("Raydium CLMM"));
+    assert_eq!(diagnostics.error, InstructionError::Custom(6022));
+    assert_eq!(
+        diagnostics.error_name.as_deref(),
+        Some("TooLittleOutputReceived")
+    );
+    assert!(!diagnostics.error_name_guessed);
+    assert_eq!(
+        diagnostics.logs,
+        vec![
+            "Instruction: Swap".to_string(),
+            "AnchorError occurred. Error Code: TooLittleOutputReceived.".to_string(),
+        ]
+    );
+    assert_eq!(
+        diagnostics.to_string(),
+        "TooLittleOutputReceived in Raydium CLMM"
+    );
+}
+
+#[test]
+fn meteora_dlmm_slippage() {
+    let transaction = failed_transaction(
+        "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo",
+        custom_error(6003),
+        vec![],
+    );
+
+    // 没有日志时使用顶层指令的程序
+    let diagnostics = diagnose_failure(&transaction, &ErrorRegistry::with_builtin()).unwrap();
+    assert_eq!(diagnostics.path, InstructionPath(vec![0]));
+    assert_eq!(
+        diagnostics.error_name.as_deref(),
+        Some("ExceededAmountSlippageTolerance")
+    );
+}
+
+#[test]
+fn anchor_framework_error_only_for_anchor_programs() {
+    let registry = ErrorRegistry::with_builtin();
+
+    // Anchor程序的框架错误码
+    let transaction = failed_transaction(RAYDIUM_CLMM_PROGRAM, custom_error(2006), vec![]);
+    let diagnostics = diagnose_failure(&transaction, &registry).unwrap();
+    assert_eq!(diagnostics.error_name.as_deref(), Some("ConstraintSeeds"));
+    assert!(!diagnostics.error_name_guessed);
+
+    // 已注册的非Anchor程序不按Anchor错误码解释
+    let transaction = failed_transaction(
+        &solana_sdk::system_program::id().to_string(),
+        custom_error(2006),
+        vec![],
+    );
+    let diagnostics = diagnose_failure(&transaction, &registry).unwrap();
+    assert_eq!(diagnostics.error_name, None);
+    assert_eq!(diagnostics.program_name.as_deref(), Some("System Program"));
+
+    // 未注册的程序只能猜测
+    let unknown = Pubkey::new_unique().to_string();
+    let transaction = failed_transaction(&unknown, custom_error(2006), vec![]);
+    let diagnostics = diagnose_failure(&transaction, &registry).unwrap();
+    assert_eq!(diagnostics.error_name.as_deref(), Some("ConstraintSeeds"));
+    assert!(diagnostics.error_name_guessed);
+    assert_eq!(
+        diagnostics.to_string(),
+        format!("ConstraintSeeds(?) in {unknown}")
+    );
+}
+
+#[test]
+fn idl_errors_mark_program_as_anchor() {
+    let program_id = Pubkey::new_unique().to_string();
+    let mut registry = ErrorRegistry::new();
+    registry.register_idl_errors(
+        &program_id,
+        &json!({"errors": [{"code": 6000, "name": "Overflow", "msg": "overflow"}]}),
+    );
+
+    assert_eq!(
+        registry.error_name(Some(&program_id), 6000),
+        Some("Overflow")
+    );
+    assert_eq!(
+        registry.error_name(Some(&program_id), 3012),
+        Some("AccountNotInitialized")
+    );
+    assert_eq!(registry.guess_error_name(Some(&program_id), 3012), None);
+}
+
+#[test]
+fn token_and_builtin_errors() {
+    let registry = ErrorRegistry::with_builtin();
+
+    let transaction = failed_transaction(&spl_token::id().to_string(), custom_error(1), vec![]);
+    let diagnostics = diagnose_failure(&transaction, &registry).unwrap();
+    assert_eq!(diagnostics.error_name.as_deref(), Some("InsufficientFunds"));
+    assert_eq!(diagnostics.program_name.as_deref(), Some("Token Program"));
+
+    let transaction = failed_transaction(
+        ROUTER,
+        json!({"InstructionError": [0, "InvalidAccountData"]}),
+        vec![],
+    );
+    let diagnostics = diagnose_failure(&transaction, &registry).unwrap();
+    assert_eq!(diagnostics.error, InstructionError::InvalidAccountData);
+    assert_eq!(
+        diagnostics.error_name.as_deref(),
+        Some("InvalidAccountData")
+    );
+}
+
+#[test]
+fn no_diagnostics_without_instruction_error() {
+    let registry = ErrorRegistry::with_builtin();
+
+    let transaction = failed_transaction(ROUTER, json!("InsufficientFundsForFee"), vec![]);
+    assert!(diagnose_failure(&transaction, &registry).is_none());
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use block_insight_cross::instructions::compute_budget::ComputeBudgetInstruction;
+use block_insight_cross::parsed_instruction::instruction_iter::InstructionPath;
+use block_insight_cross::parsed_instruction::{
+    ParseOptions, ParsedInstructionData, ParsedInstructionList,
+};
+use block_insight_cross::pubkeys::JITO_TIP_ACCOUNTS;
+use block_insight_cross::transaction::fee::{
+    FeeBreakdown, Tip, fee_breakdown, fee_breakdown_with_lamports_per_signature,
+};
+use common::{TestTransaction, account_keys, encoded_transaction, raw_message, success_meta};
+use serde_json::{Value, json};
+
+const OPTIONS: ParseOptions = ParseOptions {
+    keep_raw_data: false,
+    keep_program_id: true,
+};
+
+const PAYER: usize = 0;
+const RECIPIENT: usize = 1;
+const TIP_ACCOUNT: usize = 2;
+const SYSTEM: usize = 3;
+const COMPUTE_BUDGET: usize = 4;
+
+fn compute_budget_instruction(data: Vec<u8>) -> Value {
+    json!({
+        "programIdIndex": COMPUTE_BUDGET,
+        "accounts": [],
+        "data": bs58::encode(data).into_string(),
+        "stackHeight": null,
+    })
+}
+
+fn set_compute_unit_limit(units: u32) -> Value {
+    let mut data = vec![2u8];
+    data.extend_from_slice(&units.to_le_bytes());
+    compute_budget_instruction(data)
+}
+
+fn set_compute_unit_price(micro_lamports: u64) -> Value {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    compute_budget_instruction(data)
+}
+
+fn system_transfer(to: usize, lamports: u64) -> Value {
+    common::system_transfer(SYSTEM, PAYER, to, lamports, None)
+}
+
+/// 不保留原始指令数据的交易
+///
+/// 帐户: 0 付款人, 1 收款人, 2 Jito小费帐户, 3 System, 4 ComputeBudget
+fn transaction(instructions: Vec<Value>, fee: u64) -> TestTransaction {
+    let keys = account_keys(
+        2,
+        &[
+            JITO_TIP_ACCOUNTS[0].to_string(),
+            solana_sdk::system_program::id().to_string(),
+            solana_sdk::compute_budget::id().to_string(),
+        ],
+    );
+    let encoded = encoded_transaction(
+        raw_message(&keys, 2, instructions),
+        success_meta(keys.len()),
+    );
+    let report = ParsedInstructionList::try_from_encoded_with_options(&encoded, &OPTIONS).unwrap();
+    assert!(report.is_complete());
+    TestTransaction::new(keys, report.instructions.0).with_meta(fee)
+}
+
+#[test]
+fn compute_budget_is_decoded_without_raw_data() {
+    let transaction = transaction(
+        vec![
+            set_compute_unit_limit(300_000),
+            set_compute_unit_price(12_345),
+        ],
+        5000,
+    );
+
+    assert!(transaction.instructions[0].raw_data.is_none());
+    assert_eq!(
+        transaction.instructions[0].instruction_data,
+        ParsedInstructionData::ComputeBudget(ComputeBudgetInstruction::SetComputeUnitLimit(
+            300_000
+        ))
+    );
+    assert_eq!(
+        transaction.instructions[1].instruction_data,
+        ParsedInstructionData::ComputeBudget(ComputeBudgetInstruction::SetComputeUnitPrice(12_345))
+    );
+}
+
+#[test]
+fn estimated_priority_fee_is_price_times_limit_rounded_up() {
+    // 12_345微lamports × 300_000CU = 3_703.5lamports, 向上取整
+    let transaction = transaction(
+        vec![
+            set_compute_unit_limit(300_000),
+            set_compute_unit_price(12_345),
+            system_transfer(RECIPIENT, 1),
+        ],
+        5000 + 3704,
+    );
+
+    assert_eq!(
+        fee_breakdown(&transaction).unwrap(),
+        FeeBreakdown {
+            fee: 8704,
+            base_fee: 5000,
+            priority_fee: 3704,
+            estimated_priority_fee: Some(3704),
+            compute_unit_price: Some(12_345),
+            compute_unit_limit: 300_000,
+            compute_units_consumed: None,
+            tips: vec![],
+        }
+    );
+}
+
+#[test]
+fn default_limit_for_builtin_instructions() {
+    // 没有SetComputeUnitLimit时ComputeBudget和System的指令各3_000CU
+    let transaction = transaction(
+        vec![
+            set_compute_unit_price(1_000_000),
+            system_transfer(RECIPIENT, 1),
+            system_transfer(RECIPIENT, 2),
+        ],
+        5000 + 9000,
+    );
+
+    let breakdown = fee_breakdown(&transaction).unwrap();
+    assert_eq!(breakdown.compute_unit_limit, 9000);
+    assert_eq!(breakdown.priority_fee, 9000);
+    assert_eq!(breakdown.estimated_priority_fee, Some(9000));
+}
+
+#[test]
+fn priority_fee_is_taken_from_fee() {
+    // 估算的CU上限与实际不一致时，优先费仍以实际收取的手续费为准
+    let transaction = transaction(
+        vec![
+            set_compute_unit_price(1_000_000),
+            system_transfer(RECIPIENT, 1),
+        ],
+        5000 + 1000,
+    );
+
+    let breakdown = fee_breakdown(&transaction).unwrap();
+    assert_eq!(breakdown.base_fee + breakdown.priority_fee, breakdown.fee);
+    assert_eq!(breakdown.priority_fee, 1000);
+    assert_eq!(breakdown.estimated_priority_fee, Some(6000));
+}
+
+#[test]
+fn priority_fee_without_price_is_remainder() {
+    let transaction = transaction(vec![system_transfer(RECIPIENT, 1)], 7000);
+
+    let breakdown = fee_breakdown(&transaction).unwrap();
+    assert_eq!(breakdown.compute_unit_price, None);
+    assert_eq!(breakdown.estimated_priority_fee, None);
+    assert_eq!(breakdown.base_fee, 5000);
+    assert_eq!(breakdown.priority_fee, 2000);
+}
+
+#[test]
+fn base_fee_does_not_exceed_fee() {
+    let transaction = transaction(vec![system_transfer(RECIPIENT, 1)], 5000);
+
+    let breakdown = fee_breakdown_with_lamports_per_signature(&transaction, 10_000).unwrap();
+    assert_eq!(breakdown.base_fee, 5000);
+    assert_eq!(breakdown.priority_fee, 0);
+}
+
+#[test]
+fn jito_tip_is_detected() {
+    let transaction = transaction(
+        vec![
+            system_transfer(RECIPIENT, 1_000),
+            system_transfer(TIP_ACCOUNT, 10_000),
+        ],
+        5000,
+    );
+
+    let breakdown = fee_breakdown(&transaction).unwrap();
+    assert_eq!(
+        breakdown.tips,
+        vec![Tip {
+            from: transaction.account_keys[PAYER].clone(),
+            tip_account: JITO_TIP_ACCOUNTS[0].to_string(),
+            lamports: 10_000,
+            path: InstructionPath(vec![1]),
+        }]
+    );
+    assert_eq!(breakdown.total_tip(), 10_000);
+    assert_eq!(breakdown.total_cost(), 15_000);
+}
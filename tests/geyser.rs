@@ -0,0 +1,207 @@
+#![cfg(feature = "yellowstone")]
+
+use block_insight_cross::geyser::transaction::{GeyserBlock, GeyserTransaction};
+use block_insight_cross::instructions::compute_budget::ComputeBudgetInstruction;
+use block_insight_cross::instructions::spl_token::TokenInstruction;
+use block_insight_cross::parsed_instruction::ParsedInstructionData;
+use block_insight_cross::transaction::transaction_filter::TransactionPropsProvider;
+use solana_pubkey::Pubkey;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::transaction::TransactionError;
+use yellowstone_grpc_proto::geyser::{SubscribeUpdateBlock, SubscribeUpdateTransaction};
+use yellowstone_grpc_proto::prost::Message as _;
+
+/// Raydium AMM v4兑换，v0消息，池子帐户和Token程序通过地址查找表加载
+///
+/// 帐户: 静态 0 付款人, 1 付款人的wSOL帐户, 2 付款人的token帐户, 3 ComputeBudget, 4 Raydium AMM v4;
+/// 查找表可写 5 AMM, 6 coin vault, 7 pc vault; 查找表只读 8 Token
+const TRANSACTION_FIXTURE: &str = "tests/fixtures/geyser_transaction.bin";
+/// 第0笔为上面的兑换交易，第1笔为失败的legacy SOL转帐
+const BLOCK_FIXTURE: &str = "tests/fixtures/geyser_block.bin";
+
+const PAYER: [u8; 32] = [11; 32];
+const AMM: [u8; 32] = [21; 32];
+const VAULT_COIN: [u8; 32] = [22; 32];
+const VAULT_PC: [u8; 32] = [23; 32];
+const AMM_AUTHORITY: [u8; 32] = [24; 32];
+const MINT: [u8; 32] = [30; 32];
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+
+fn read_fixture<T: yellowstone_grpc_proto::prost::Message + Default>(path: &str) -> T {
+    let bytes = std::fs::read(path).unwrap();
+    T::decode(bytes.as_slice()).unwrap()
+}
+
+fn key(bytes: [u8; 32]) -> String {
+    Pubkey::new_from_array(bytes).to_string()
+}
+
+fn assert_swap(transaction: &GeyserTransaction) {
+    let accounts = transaction.get_accounts();
+    assert_eq!(accounts.accounts_num(), 9);
+    assert_eq!(accounts.static_accounts_num(), 5);
+    assert_eq!(accounts.loaded_writable_accounts_num(), 3);
+    assert_eq!(accounts.get(0).unwrap(), &key(PAYER));
+    assert_eq!(accounts.get(5).unwrap(), &key(AMM));
+    assert_eq!(accounts.get(6).unwrap(), &key(VAULT_COIN));
+    assert_eq!(accounts.get(7).unwrap(), &key(VAULT_PC));
+    assert_eq!(accounts.get(8).unwrap(), &spl_token::id().to_string());
+
+    let header = transaction.get_message_header().unwrap();
+    assert_eq!(header.num_required_signatures, 1);
+    assert_eq!(header.num_readonly_unsigned_accounts, 2);
+
+    let instructions = transaction.get_parsed_instructions().unwrap();
+    assert_eq!(instructions.len(), 3);
+    assert_eq!(
+        instructions[0].instruction_data,
+        ParsedInstructionData::ComputeBudget(ComputeBudgetInstruction::SetComputeUnitLimit(
+            200_000
+        ))
+    );
+    assert_eq!(
+        instructions[1].instruction_data,
+        ParsedInstructionData::ComputeBudget(ComputeBudgetInstruction::SetComputeUnitPrice(50_000))
+    );
+
+    // 内部指令的Token程序来自查找表
+    let swap = &instructions[2];
+    assert_eq!(swap.accounts, vec![8, 5, 6, 7, 1, 2, 0]);
+    let inner = swap.inner_instructions.as_ref().unwrap();
+    assert_eq!(inner.len(), 2);
+    assert_eq!(inner[0].program_id_index, 8);
+    assert_eq!(inner[0].accounts, vec![1, 7, 0]);
+    assert_eq!(
+        inner[0].instruction_data,
+        ParsedInstructionData::SplToken(TokenInstruction::Transfer { amount: 1_000_000 })
+    );
+    assert_eq!(inner[1].accounts, vec![6, 2, 5]);
+    assert_eq!(
+        inner[1].instruction_data,
+        ParsedInstructionData::SplToken(TokenInstruction::Transfer {
+            amount: 2_500_000_000
+        })
+    );
+
+    let meta = transaction.get_meta().unwrap();
+    assert!(meta.err.is_none());
+    assert_eq!(meta.fee, 15_000);
+    assert_eq!(meta.pre_balances.len(), 9);
+    assert_eq!(meta.compute_units_consumed, Some(41_300));
+    assert_eq!(meta.pre_token_balances.unwrap().len(), 4);
+    assert_eq!(meta.post_token_balances.unwrap().len(), 4);
+}
+
+#[test]
+fn transaction_from_fixture() {
+    let update = read_fixture::<SubscribeUpdateTransaction>(TRANSACTION_FIXTURE);
+    let transaction = GeyserTransaction::try_from(&update).unwrap();
+
+    assert_eq!(transaction.slot, 42);
+    assert_eq!(transaction.index, 3);
+    assert!(!transaction.is_vote);
+    assert_eq!(
+        transaction.get_signatures().unwrap(),
+        &[solana_sdk::signature::Signature::from([7; 64]).to_string()]
+    );
+    assert_swap(&transaction);
+}
+
+#[test]
+fn loaded_addresses_from_fixture() {
+    let update = read_fixture::<SubscribeUpdateTransaction>(TRANSACTION_FIXTURE);
+    let transaction = GeyserTransaction::try_from(&update).unwrap();
+
+    assert_eq!(
+        transaction.meta.loaded_addresses.writable,
+        vec![
+            Pubkey::new_from_array(AMM),
+            Pubkey::new_from_array(VAULT_COIN),
+            Pubkey::new_from_array(VAULT_PC),
+        ]
+    );
+    assert_eq!(
+        transaction.meta.loaded_addresses.readonly,
+        vec![spl_token::id()]
+    );
+    let lookups = transaction
+        .transaction
+        .message
+        .address_table_lookups()
+        .unwrap();
+    assert_eq!(lookups.len(), 1);
+    assert_eq!(lookups[0].writable_indexes, vec![0, 1, 2]);
+    assert_eq!(lookups[0].readonly_indexes, vec![7]);
+}
+
+#[test]
+fn token_balances_from_fixture() {
+    let update = read_fixture::<SubscribeUpdateTransaction>(TRANSACTION_FIXTURE);
+    let transaction = GeyserTransaction::try_from(&update).unwrap();
+    let index = transaction.token_account_index();
+
+    assert_eq!(index.len(), 4);
+    let user_wsol = index.get(1).unwrap();
+    assert_eq!(user_wsol.mint, WSOL);
+    assert_eq!(user_wsol.decimals, 9);
+    assert_eq!(user_wsol.owner.as_deref(), Some(key(PAYER).as_str()));
+    // 查找表加载的vault也能按索引找到
+    let vault = index.get(6).unwrap();
+    assert_eq!(vault.mint, key(MINT));
+    assert_eq!(vault.decimals, 6);
+    assert_eq!(vault.owner.as_deref(), Some(key(AMM_AUTHORITY).as_str()));
+
+    let inner = &transaction.parsed_instructions[2]
+        .inner_instructions
+        .as_ref()
+        .unwrap()[1];
+    let resolved = index.resolve_transfer(inner.get_token_transfer_data().unwrap());
+    assert_eq!(resolved.mint, Some(key(MINT).as_str()));
+    assert_eq!(resolved.source_owner, Some(key(AMM_AUTHORITY).as_str()));
+    assert_eq!(resolved.destination_owner, Some(key(PAYER).as_str()));
+}
+
+#[test]
+fn block_from_fixture() {
+    let update = read_fixture::<SubscribeUpdateBlock>(BLOCK_FIXTURE);
+    let block = GeyserBlock::try_from(&update).unwrap();
+
+    assert_eq!(block.slot, 100);
+    assert_eq!(block.block_time, Some(1_700_000_000));
+    assert_eq!(block.block_height, Some(90));
+    assert_eq!(block.transactions.len(), 2);
+    for (index, transaction) in block.transactions.iter().enumerate() {
+        assert_eq!(transaction.slot, 100);
+        assert_eq!(transaction.index, index as u64);
+    }
+    assert_swap(&block.transactions[0]);
+
+    let failed = &block.transactions[1];
+    assert_eq!(failed.get_accounts().accounts_num(), 3);
+    let instructions = failed.get_parsed_instructions().unwrap();
+    assert_eq!(instructions.len(), 1);
+    assert_eq!(
+        instructions[0].instruction_data,
+        ParsedInstructionData::System(SystemInstruction::Transfer { lamports: 1000 })
+    );
+    let meta = failed.get_meta().unwrap();
+    assert_eq!(
+        meta.err,
+        Some(&TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(1)
+        ))
+    );
+    assert_eq!(meta.post_balances, &[995_000, 0, 1]);
+}
+
+#[test]
+fn missing_transaction_is_error() {
+    let mut update = read_fixture::<SubscribeUpdateTransaction>(TRANSACTION_FIXTURE);
+    let mut info = update.transaction.take().unwrap();
+    assert!(GeyserTransaction::try_from(&update).is_err());
+
+    info.meta = None;
+    assert!(GeyserTransaction::from_transaction_info(1, &info).is_err());
+}
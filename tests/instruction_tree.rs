@@ -0,0 +1,206 @@
+mod common;
+
+use block_insight_cross::parsed_instruction::diagnostics::{
+    InstructionDiagnostic, InstructionIssue, InstructionLocation,
+};
+use block_insight_cross::parsed_instruction::instruction_iter::InstructionPath;
+use block_insight_cross::parsed_instruction::{
+    ParsedInstruction, ParsedInstructionData, ParsedInstructionList,
+};
+use common::{
+    account_keys, encoded_transaction, raw_message, success_meta, system_transfer_data,
+    token_transfer_data,
+};
+use serde_json::{Value, json};
+
+const DEX: u8 = 3;
+const TOKEN: u8 = 4;
+const SYSTEM: u8 = 5;
+
+/// 帐户: 0 付款人, 1/2 其它帐户, 3 DEX, 4 Token, 5 System
+fn keys() -> Vec<String> {
+    account_keys(4, &[spl_token::id(), solana_sdk::system_program::id()])
+}
+
+fn instruction(program: u8, accounts: &[u8], data: &[u8], stack_height: Option<u32>) -> Value {
+    json!({
+        "programIdIndex": program,
+        "accounts": accounts,
+        "data": bs58::encode(data).into_string(),
+        "stackHeight": stack_height,
+    })
+}
+
+/// 第1条顶层指令调用DEX，DEX再调用两次token转账，最后DEX直接调用一次System转账
+fn transaction(inner: Vec<Value>, inner_index: usize) -> ParsedInstructionList {
+    let keys = keys();
+    let mut meta = success_meta(keys.len());
+    meta["innerInstructions"] = json!([{ "index": inner_index, "instructions": inner }]);
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            3,
+            vec![
+                instruction(SYSTEM, &[0, 1], &system_transfer_data(1), None),
+                instruction(DEX, &[0, 1, 2], &[9], None),
+            ],
+        ),
+        meta,
+    );
+    ParsedInstructionList::from(&encoded)
+}
+
+fn nested_inner(with_stack_height: bool) -> Vec<Value> {
+    let height = |h: u32| with_stack_height.then_some(h);
+    vec![
+        instruction(DEX, &[1, 2], &[8], height(2)),
+        instruction(TOKEN, &[1, 2, 0], &token_transfer_data(10), height(3)),
+        instruction(TOKEN, &[2, 1, 0], &token_transfer_data(20), height(3)),
+        instruction(SYSTEM, &[0, 2], &system_transfer_data(30), height(2)),
+    ]
+}
+
+fn paths(list: &ParsedInstructionList) -> Vec<String> {
+    list.iter_with_path()
+        .map(|node| node.path.to_string())
+        .collect()
+}
+
+#[test]
+fn rebuilds_call_tree_from_stack_height() {
+    let list = transaction(nested_inner(true), 1);
+
+    assert_eq!(list.len(), 2);
+    assert!(list[0].inner_instructions.is_none());
+    let dex_children = list[1].inner_instructions.as_ref().unwrap();
+    assert_eq!(dex_children.len(), 2);
+    assert_eq!(dex_children[0].program_id_index, DEX);
+    assert_eq!(
+        dex_children[0].inner_instructions.as_ref().unwrap().len(),
+        2
+    );
+    assert_eq!(dex_children[1].program_id_index, SYSTEM);
+    assert!(dex_children[1].inner_instructions.is_none());
+
+    assert_eq!(paths(&list), ["0", "1", "1.0", "1.0.0", "1.0.1", "1.1"]);
+    let depths = list
+        .iter_with_path()
+        .map(|node| node.depth())
+        .collect::<Vec<_>>();
+    assert_eq!(depths, [0, 0, 1, 2, 2, 1]);
+}
+
+#[test]
+fn missing_stack_height_attaches_flat() {
+    let list = transaction(nested_inner(false), 1);
+
+    let children = list[1].inner_instructions.as_ref().unwrap();
+    assert_eq!(children.len(), 4);
+    assert!(children.iter().all(|c| c.inner_instructions.is_none()));
+    assert_eq!(paths(&list), ["0", "1", "1.0", "1.1", "1.2", "1.3"]);
+}
+
+#[test]
+fn path_resolves_to_instruction() {
+    let list = transaction(nested_inner(true), 1);
+
+    let path = InstructionPath(vec![1, 0, 1]);
+    assert_eq!(path.to_string(), "1.0.1");
+    assert_eq!(path.depth(), 2);
+    assert_eq!(path.top_level_index(), Some(1));
+    assert_eq!(path.parent(), Some(InstructionPath(vec![1, 0])));
+    assert_eq!(InstructionPath(vec![1]).parent(), None);
+    assert!(InstructionPath(vec![1]).is_ancestor_of(&path));
+    assert!(!path.is_ancestor_of(&path));
+    assert!(!InstructionPath(vec![0]).is_ancestor_of(&path));
+
+    let instruction = path.resolve(&list).unwrap();
+    assert_eq!(instruction.program_id_index, TOKEN);
+    assert_eq!(instruction.accounts, vec![2, 1, 0]);
+    assert!(matches!(
+        instruction.instruction_data,
+        ParsedInstructionData::SplToken(_)
+    ));
+    assert!(InstructionPath(vec![1, 0, 2]).resolve(&list).is_none());
+    assert!(InstructionPath(vec![]).resolve(&list).is_none());
+
+    for node in list.iter_with_path() {
+        assert_eq!(node.path.resolve(&list), Some(node.instruction));
+    }
+}
+
+#[test]
+fn inner_group_without_parent_is_reported() {
+    let keys = keys();
+    let mut meta = success_meta(keys.len());
+    meta["innerInstructions"] = json!([{
+        "index": 5,
+        "instructions": [instruction(TOKEN, &[1, 2, 0], &token_transfer_data(10), Some(2))],
+    }]);
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            3,
+            vec![instruction(SYSTEM, &[0, 1], &system_transfer_data(1), None)],
+        ),
+        meta,
+    );
+
+    let report = ParsedInstructionList::try_from_encoded(&encoded).unwrap();
+    assert_eq!(report.instructions.len(), 1);
+    assert!(report.instructions[0].inner_instructions.is_none());
+    assert!(report.diagnostics.iter().any(|d| {
+        d.location == InstructionLocation::TopLevel { index: 5 }
+            && d.issue == InstructionIssue::MissingParent
+    }));
+}
+
+#[test]
+fn unparsable_instruction_keeps_position() {
+    let keys = keys();
+    let mut meta = success_meta(keys.len());
+    meta["innerInstructions"] = json!([{
+        "index": 1,
+        "instructions": [
+            instruction(50, &[1], &token_transfer_data(10), Some(2)),
+            instruction(TOKEN, &[1, 2, 0], &token_transfer_data(10), Some(2)),
+        ],
+    }]);
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            3,
+            vec![
+                instruction(50, &[0, 1], &system_transfer_data(1), None),
+                instruction(DEX, &[0, 1, 2], &[9], None),
+            ],
+        ),
+        meta,
+    );
+
+    let report = ParsedInstructionList::try_from_encoded(&encoded).unwrap();
+    let list = &report.instructions;
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0], ParsedInstruction::placeholder(50));
+    assert_eq!(list[1].program_id_index, DEX);
+    let children = list[1].inner_instructions.as_ref().unwrap();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0], ParsedInstruction::placeholder(50));
+    assert_eq!(children[1].program_id_index, TOKEN);
+    assert_eq!(
+        report.diagnostics,
+        vec![
+            InstructionDiagnostic {
+                location: InstructionLocation::TopLevel { index: 0 },
+                issue: InstructionIssue::MissingProgram(50),
+            },
+            InstructionDiagnostic {
+                location: InstructionLocation::Inner {
+                    index: 1,
+                    position: 0
+                },
+                issue: InstructionIssue::MissingProgram(50),
+            },
+        ]
+    );
+}
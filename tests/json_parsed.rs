@@ -0,0 +1,268 @@
+mod common;
+
+use block_insight_cross::parsed_instruction::ParsedInstructionList;
+use block_insight_cross::parsed_instruction::diagnostics::ParsedInstructionReport;
+use common::{account_keys, encoded_transaction, raw_message, success_meta, system_transfer_data};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+
+const SYSTEM: usize = 4;
+const TOKEN: usize = 5;
+const TOKEN_2022: usize = 6;
+const MEMO: usize = 7;
+
+/// 帐户: 0 签名者, 1..3 可写帐户, 4 System, 5 Token, 6 Token-2022, 7 Memo
+fn keys() -> Vec<String> {
+    account_keys(
+        4,
+        &[
+            solana_sdk::system_program::id().to_string(),
+            spl_token::id().to_string(),
+            spl_token_2022::id().to_string(),
+            "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".to_string(),
+        ],
+    )
+}
+
+/// 分别以json(原始数据)和jsonParsed格式解析同一条顶层指令
+fn parse_both(
+    keys: &[String],
+    program: usize,
+    accounts: &[u8],
+    data: &[u8],
+    parsed: Value,
+) -> (ParsedInstructionReport, ParsedInstructionReport) {
+    let raw = encoded_transaction(
+        raw_message(
+            keys,
+            4,
+            vec![json!({
+                "programIdIndex": program,
+                "accounts": accounts,
+                "data": bs58::encode(data).into_string(),
+                "stackHeight": null,
+            })],
+        ),
+        success_meta(keys.len()),
+    );
+    let json_parsed = encoded_transaction(
+        json!({
+            "accountKeys": keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| json!({
+                    "pubkey": key,
+                    "writable": (1..4).contains(&i),
+                    "signer": i == 0,
+                    "source": "transaction",
+                }))
+                .collect::<Vec<_>>(),
+            "recentBlockhash": "11111111111111111111111111111111",
+            "instructions": [{
+                "program": "test",
+                "programId": keys[program],
+                "parsed": parsed,
+                "stackHeight": null,
+            }],
+        }),
+        success_meta(keys.len()),
+    );
+
+    let raw = ParsedInstructionList::try_from_encoded(&raw).unwrap();
+    let json_parsed = ParsedInstructionList::try_from_encoded(&json_parsed).unwrap();
+    assert!(json_parsed.is_complete(), "{:?}", json_parsed.diagnostics);
+    (raw, json_parsed)
+}
+
+fn assert_round_trip(
+    program: usize,
+    accounts: &[u8],
+    data: &[u8],
+    parsed: impl Fn(&[String]) -> Value,
+) {
+    let keys = keys();
+    let (raw, json_parsed) = parse_both(&keys, program, accounts, data, parsed(&keys));
+    assert!(raw.is_complete(), "{:?}", raw.diagnostics);
+    let (raw, json_parsed) = (&raw.instructions[0], &json_parsed.instructions[0]);
+    assert_eq!(raw.program_id_index, json_parsed.program_id_index);
+    assert_eq!(raw.program_id, json_parsed.program_id);
+    assert_eq!(raw.instruction_data, json_parsed.instruction_data);
+    assert_eq!(raw.accounts, json_parsed.accounts);
+    assert!(json_parsed.raw_data.is_none());
+}
+
+#[test]
+fn system_transfer() {
+    let data = system_transfer_data(42);
+    assert_round_trip(SYSTEM, &[0, 1], &data, |keys| {
+        json!({
+            "type": "transfer",
+            "info": {"source": keys[0], "destination": keys[1], "lamports": 42},
+        })
+    });
+}
+
+#[test]
+fn system_create_account() {
+    let owner = spl_token::id();
+    let data = bincode::serialize(&SystemInstruction::CreateAccount {
+        lamports: 2039280,
+        space: 165,
+        owner,
+    })
+    .unwrap();
+    assert_round_trip(SYSTEM, &[0, 1], &data, |keys| {
+        json!({
+            "type": "createAccount",
+            "info": {
+                "source": keys[0],
+                "newAccount": keys[1],
+                "lamports": 2039280,
+                "space": 165,
+                "owner": owner.to_string(),
+            },
+        })
+    });
+}
+
+#[test]
+fn token_transfer() {
+    let data = spl_token::instruction::TokenInstruction::Transfer { amount: 1000 }.pack();
+    assert_round_trip(TOKEN, &[1, 2, 0], &data, |keys| {
+        json!({
+            "type": "transfer",
+            "info": {
+                "source": keys[1],
+                "destination": keys[2],
+                "authority": keys[0],
+                "amount": "1000",
+            },
+        })
+    });
+}
+
+#[test]
+fn token_transfer_checked() {
+    let data = spl_token::instruction::TokenInstruction::TransferChecked {
+        amount: 1000,
+        decimals: 6,
+    }
+    .pack();
+    assert_round_trip(TOKEN, &[1, 3, 2, 0], &data, |keys| {
+        json!({
+            "type": "transferChecked",
+            "info": {
+                "source": keys[1],
+                "mint": keys[3],
+                "destination": keys[2],
+                "authority": keys[0],
+                "tokenAmount": {
+                    "amount": "1000",
+                    "decimals": 6,
+                    "uiAmount": 0.001,
+                    "uiAmountString": "0.001",
+                },
+            },
+        })
+    });
+}
+
+#[test]
+fn token_initialize_account3() {
+    let owner = Pubkey::new_unique();
+    let data = spl_token::instruction::TokenInstruction::InitializeAccount3 { owner }.pack();
+    assert_round_trip(TOKEN, &[1, 3], &data, |keys| {
+        json!({
+            "type": "initializeAccount3",
+            "info": {"account": keys[1], "mint": keys[3], "owner": owner.to_string()},
+        })
+    });
+}
+
+#[test]
+fn token_2022_transfer_checked() {
+    let data = spl_token_2022::instruction::TokenInstruction::TransferChecked {
+        amount: 7,
+        decimals: 0,
+    }
+    .pack();
+    assert_round_trip(TOKEN_2022, &[1, 3, 2, 0], &data, |keys| {
+        json!({
+            "type": "transferChecked",
+            "info": {
+                "source": keys[1],
+                "mint": keys[3],
+                "destination": keys[2],
+                "authority": keys[0],
+                "tokenAmount": {
+                    "amount": "7",
+                    "decimals": 0,
+                    "uiAmount": 7.0,
+                    "uiAmountString": "7",
+                },
+            },
+        })
+    });
+}
+
+#[test]
+fn token_2022_burn_checked() {
+    let data = spl_token_2022::instruction::TokenInstruction::BurnChecked {
+        amount: 5,
+        decimals: 2,
+    }
+    .pack();
+    assert_round_trip(TOKEN_2022, &[1, 3, 0], &data, |keys| {
+        json!({
+            "type": "burnChecked",
+            "info": {
+                "account": keys[1],
+                "mint": keys[3],
+                "authority": keys[0],
+                "tokenAmount": {
+                    "amount": "5",
+                    "decimals": 2,
+                    "uiAmount": 0.05,
+                    "uiAmountString": "0.05",
+                },
+            },
+        })
+    });
+}
+
+#[test]
+fn unmapped_instruction_keeps_info_accounts() {
+    let keys = keys();
+    let (_, json_parsed) = parse_both(
+        &keys,
+        TOKEN,
+        &[],
+        &[],
+        json!({
+            "type": "someFutureInstruction",
+            "info": {
+                "account": keys[1],
+                "signers": [keys[0], keys[2]],
+                "amount": "12",
+                "unknownPubkey": Pubkey::new_unique().to_string(),
+            },
+        }),
+    );
+    let mut accounts = json_parsed.instructions[0].accounts.clone();
+    accounts.sort();
+    assert_eq!(accounts, vec![0, 1, 2]);
+}
+
+#[test]
+fn unmapped_program_keeps_info_accounts() {
+    let keys = keys();
+    let (_, json_parsed) = parse_both(
+        &keys,
+        MEMO,
+        &[],
+        &[],
+        json!({"type": "memo", "info": {"signer": keys[0]}}),
+    );
+    assert_eq!(json_parsed.instructions[0].accounts, vec![0]);
+}
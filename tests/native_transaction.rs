@@ -0,0 +1,186 @@
+use block_insight_cross::instructions::spl_token::TokenInstruction;
+use block_insight_cross::parsed_instruction::{ParsedInstructionData, ParsedInstructionList};
+use solana_pubkey::Pubkey;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, CompiledInstruction, Instruction};
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::message::{VersionedMessage, v0};
+use solana_sdk::signature::Signature;
+use solana_sdk::system_instruction::{self, SystemInstruction};
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::{
+    InnerInstruction, InnerInstructions, TransactionStatusMeta,
+};
+
+struct Keys {
+    payer: Pubkey,
+    recipient: Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+    mint: Pubkey,
+    program: Pubkey,
+}
+
+/// v0交易: 顶层 0 SOL转帐, 1 调用某个程序，其下有一条Token TransferChecked内部指令
+///
+/// 收款人、token帐户及mint都通过地址查找表加载
+fn transaction(keys: &Keys) -> VersionedTransaction {
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: vec![keys.recipient, keys.source, keys.destination, keys.mint],
+    };
+    let instructions = [
+        system_instruction::transfer(&keys.payer, &keys.recipient, 1000),
+        Instruction::new_with_bytes(
+            keys.program,
+            &[9],
+            vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new(keys.source, false),
+                AccountMeta::new_readonly(keys.mint, false),
+                AccountMeta::new(keys.destination, false),
+                AccountMeta::new(keys.payer, true),
+            ],
+        ),
+    ];
+    let message =
+        v0::Message::try_compile(&keys.payer, &instructions, &[table], Hash::default()).unwrap();
+    VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(message),
+    }
+}
+
+fn loaded_addresses(transaction: &VersionedTransaction, keys: &Keys) -> LoadedAddresses {
+    let lookups = transaction.message.address_table_lookups().unwrap();
+    assert_eq!(lookups.len(), 1);
+    // 与查找表中的顺序一致
+    let table = [keys.recipient, keys.source, keys.destination, keys.mint];
+    LoadedAddresses {
+        writable: lookups[0]
+            .writable_indexes
+            .iter()
+            .map(|i| table[*i as usize])
+            .collect(),
+        readonly: lookups[0]
+            .readonly_indexes
+            .iter()
+            .map(|i| table[*i as usize])
+            .collect(),
+    }
+}
+
+/// 帐户在交易帐户列表(静态帐户+查找表可写+查找表只读)中的索引
+fn index_of(transaction: &VersionedTransaction, loaded: &LoadedAddresses, key: &Pubkey) -> u8 {
+    transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .chain(&loaded.writable)
+        .chain(&loaded.readonly)
+        .position(|k| k == key)
+        .unwrap() as u8
+}
+
+#[test]
+fn from_native_with_lookup_table_and_inner_instructions() {
+    let keys = Keys {
+        payer: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        source: Pubkey::new_unique(),
+        destination: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        program: Pubkey::new_unique(),
+    };
+    let transaction = transaction(&keys);
+    let loaded = loaded_addresses(&transaction, &keys);
+    assert_eq!(
+        loaded.writable,
+        vec![keys.recipient, keys.source, keys.destination]
+    );
+    assert_eq!(loaded.readonly, vec![keys.mint]);
+
+    let index = |key: &Pubkey| index_of(&transaction, &loaded, key);
+    let static_len = transaction.message.static_account_keys().len() as u8;
+    // 查找表中的帐户排在静态帐户之后
+    assert_eq!(index(&keys.recipient), static_len);
+    assert_eq!(index(&keys.mint), static_len + 3);
+
+    let inner = spl_token::instruction::transfer_checked(
+        &spl_token::id(),
+        &keys.source,
+        &keys.mint,
+        &keys.destination,
+        &keys.payer,
+        &[],
+        2_500,
+        6,
+    )
+    .unwrap();
+    let meta = TransactionStatusMeta {
+        status: Ok(()),
+        fee: 5000,
+        pre_balances: vec![0; static_len as usize + 4],
+        post_balances: vec![0; static_len as usize + 4],
+        inner_instructions: Some(vec![InnerInstructions {
+            index: 1,
+            instructions: vec![InnerInstruction {
+                instruction: CompiledInstruction {
+                    program_id_index: index(&spl_token::id()),
+                    accounts: vec![
+                        index(&keys.source),
+                        index(&keys.mint),
+                        index(&keys.destination),
+                        index(&keys.payer),
+                    ],
+                    data: inner.data,
+                },
+                stack_height: Some(2),
+            }],
+        }]),
+        loaded_addresses: loaded.clone(),
+        ..Default::default()
+    };
+
+    let instructions = ParsedInstructionList::from_native(&transaction, &meta);
+    assert_eq!(instructions.len(), 2);
+
+    let transfer = &instructions[0];
+    assert_eq!(
+        transfer.program_id_index,
+        index(&solana_sdk::system_program::id())
+    );
+    assert_eq!(
+        transfer.accounts,
+        vec![index(&keys.payer), index(&keys.recipient)]
+    );
+    assert_eq!(
+        transfer.instruction_data,
+        ParsedInstructionData::System(SystemInstruction::Transfer { lamports: 1000 })
+    );
+    assert!(transfer.inner_instructions.is_none());
+
+    let call = &instructions[1];
+    assert_eq!(call.program_id_index, index(&keys.program));
+    assert_eq!(call.instruction_data, ParsedInstructionData::Unknown);
+    let inner = call.inner_instructions.as_ref().unwrap();
+    assert_eq!(inner.len(), 1);
+    assert_eq!(inner[0].program_id_index, index(&spl_token::id()));
+    assert_eq!(
+        inner[0].instruction_data,
+        ParsedInstructionData::SplToken(TokenInstruction::TransferChecked {
+            amount: 2_500,
+            decimals: 6,
+        })
+    );
+
+    // 内部指令的帐户来自查找表
+    let data = inner[0].get_token_transfer_data().unwrap();
+    assert_eq!(data.source, index(&keys.source));
+    assert_eq!(data.destination, index(&keys.destination));
+    assert_eq!(data.mint, Some(index(&keys.mint)));
+    assert_eq!(data.signer, index(&keys.payer));
+    assert_eq!(data.amount, 2_500);
+    assert_eq!(data.decimal, Some(6));
+}
@@ -0,0 +1,128 @@
+use block_insight_cross::parsed_instruction::{
+    InstructionDataFormat, InstructionParseError, InstructionParseErrorKind, InstructionProgramId,
+    ParsedInstructionData,
+};
+use solana_sdk::program_error::ProgramError;
+
+fn parse_error(
+    program_id: InstructionProgramId,
+    data: InstructionDataFormat,
+) -> InstructionParseError {
+    match ParsedInstructionData::parse_or_error(program_id, data) {
+        ParsedInstructionData::Error(e) => e,
+        other => panic!("expected parse error, got {other:?}"),
+    }
+}
+
+#[test]
+fn truncated_system_instruction() {
+    let program_id = solana_sdk::system_program::id();
+    // Transfer缺少lamports
+    let error = parse_error(
+        InstructionProgramId::Pubkey(&program_id),
+        InstructionDataFormat::Binary(&[2, 0, 0, 0]),
+    );
+    assert_eq!(error.kind, InstructionParseErrorKind::TruncatedData);
+    assert_eq!(error.program_id, program_id.to_string());
+    assert_eq!(error.data_len, Some(4));
+}
+
+#[test]
+fn empty_data_is_truncated() {
+    let program_id = spl_token::id();
+    let error = parse_error(
+        InstructionProgramId::Pubkey(&program_id),
+        InstructionDataFormat::Binary(&[]),
+    );
+    assert_eq!(error.kind, InstructionParseErrorKind::TruncatedData);
+    assert_eq!(error.data_len, Some(0));
+}
+
+#[test]
+fn token_program_error_keeps_code() {
+    let program_id = spl_token::id();
+    let error = parse_error(
+        InstructionProgramId::Pubkey(&program_id),
+        InstructionDataFormat::Binary(&[200, 1, 2]),
+    );
+    let expected = ProgramError::from(spl_token::error::TokenError::InvalidInstruction);
+    assert_eq!(
+        error.kind,
+        InstructionParseErrorKind::ProgramError {
+            code: u64::from(expected.clone()),
+            message: expected.to_string(),
+        }
+    );
+    assert_eq!(error.data_len, Some(3));
+}
+
+#[test]
+fn unknown_system_variant_is_bincode_error() {
+    let program_id = solana_sdk::system_program::id();
+    let error = parse_error(
+        InstructionProgramId::Pubkey(&program_id),
+        InstructionDataFormat::Binary(&[255, 0, 0, 0]),
+    );
+    assert!(matches!(
+        error.kind,
+        InstructionParseErrorKind::Bincode { .. }
+    ));
+}
+
+#[test]
+fn invalid_base58_data() {
+    let program_id = spl_token::id();
+    let error = parse_error(
+        InstructionProgramId::Pubkey(&program_id),
+        InstructionDataFormat::Base58("0OIl"),
+    );
+    assert!(matches!(
+        error.kind,
+        InstructionParseErrorKind::Base58 { .. }
+    ));
+    assert_eq!(error.data_len, None);
+}
+
+#[test]
+fn invalid_program_id() {
+    let error = parse_error(
+        InstructionProgramId::Base58("abc"),
+        InstructionDataFormat::Binary(&[1]),
+    );
+    assert_eq!(error.kind, InstructionParseErrorKind::InvalidProgramId);
+    assert_eq!(error.program_id, "abc");
+    assert_eq!(error.data_len, Some(1));
+}
+
+#[test]
+fn valid_data_is_not_error() {
+    let program_id = solana_sdk::system_program::id();
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&7u64.to_le_bytes());
+    assert!(!matches!(
+        ParsedInstructionData::parse_or_error(
+            InstructionProgramId::Pubkey(&program_id),
+            InstructionDataFormat::Binary(&data),
+        ),
+        ParsedInstructionData::Error(_)
+    ));
+}
+
+#[cfg(feature = "serde-traits")]
+#[test]
+fn error_serializes_structured() {
+    let program_id = solana_sdk::system_program::id();
+    let data = ParsedInstructionData::parse_or_error(
+        InstructionProgramId::Pubkey(&program_id),
+        InstructionDataFormat::Binary(&[2, 0, 0, 0]),
+    );
+
+    let value = serde_json::to_value(&data).unwrap();
+    assert_eq!(value["error"]["kind"], "truncatedData");
+    assert_eq!(value["error"]["programId"], program_id.to_string());
+    assert_eq!(value["error"]["dataLen"], 4);
+    assert_eq!(
+        serde_json::from_value::<ParsedInstructionData>(value).unwrap(),
+        data
+    );
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use block_insight_cross::pubkeys::JITO_TIP_ACCOUNTS;
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::profit::{MintProfit, transaction_profit};
+use common::{account_keys, encoded_transaction, raw_message, system_transfer, token_balance};
+use serde_json::{Value, json};
+use solana_transaction_status_client_types::EncodedTransaction;
+use std::collections::HashMap;
+
+const SIGNER: usize = 0;
+const OTHER_SIGNER: usize = 1;
+const WSOL_ACCOUNT: usize = 2;
+const TOKEN_ACCOUNT: usize = 3;
+const TIP_ACCOUNT: usize = 4;
+const SYSTEM: usize = 5;
+
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// 帐户: 0 签名者, 1 另一个签名者, 2 签名者的wSOL帐户, 3 签名者的token帐户, 4 Jito小费帐户, 5 System
+fn signer_keys() -> Vec<String> {
+    account_keys(
+        4,
+        &[
+            JITO_TIP_ACCOUNTS[0].to_string(),
+            solana_sdk::system_program::id().to_string(),
+        ],
+    )
+}
+
+fn wsol_balance(account_index: usize, owner: &str, amount: u64) -> Value {
+    let mut balance = token_balance(account_index, WSOL, owner, amount);
+    balance["uiTokenAmount"]["decimals"] = json!(9);
+    balance
+}
+
+fn tip(from: usize, lamports: u64) -> Value {
+    system_transfer(SYSTEM, from, TIP_ACCOUNT, lamports, None)
+}
+
+struct Fixture {
+    keys: Vec<String>,
+    signatures: usize,
+    instructions: Vec<Value>,
+    fee: u64,
+    pre_balances: Vec<u64>,
+    post_balances: Vec<u64>,
+    pre_token_balances: Vec<Value>,
+    post_token_balances: Vec<Value>,
+}
+
+impl Fixture {
+    fn new(keys: Vec<String>) -> Self {
+        Self {
+            signatures: 1,
+            instructions: vec![],
+            fee: 5000,
+            pre_balances: vec![0; keys.len()],
+            post_balances: vec![0; keys.len()],
+            pre_token_balances: vec![],
+            post_token_balances: vec![],
+            keys,
+        }
+    }
+
+    fn build(self) -> EncodedTransactionProps {
+        let mut message = raw_message(&self.keys, 1, self.instructions);
+        message["header"]["numRequiredSignatures"] = json!(self.signatures);
+        let mut encoded = encoded_transaction(
+            message,
+            json!({
+                "err": null,
+                "status": {"Ok": null},
+                "fee": self.fee,
+                "preBalances": self.pre_balances,
+                "postBalances": self.post_balances,
+                "innerInstructions": [],
+                "logMessages": [],
+                "preTokenBalances": self.pre_token_balances,
+                "postTokenBalances": self.post_token_balances,
+            }),
+        );
+        if let EncodedTransaction::Json(transaction) = &mut encoded.transaction {
+            transaction.signatures = (0..self.signatures)
+                .map(|i| solana_sdk::signature::Signature::from([i as u8 + 1; 64]).to_string())
+                .collect();
+        }
+        EncodedTransactionProps::try_from(&encoded).unwrap()
+    }
+}
+
+#[test]
+fn sol_and_wsol_are_merged() {
+    let keys = signer_keys();
+    let signer = keys[SIGNER].clone();
+    let mut fixture = Fixture::new(keys);
+    fixture.pre_balances[SIGNER] = 10_000_000;
+    fixture.post_balances[SIGNER] = 10_000_000 - 5000;
+    // wSOL帐户付出1_000_000, 得到150个token
+    fixture.pre_balances[WSOL_ACCOUNT] = 2_039_280 + 3_000_000;
+    fixture.post_balances[WSOL_ACCOUNT] = 2_039_280 + 2_000_000;
+    fixture.pre_token_balances = vec![
+        wsol_balance(WSOL_ACCOUNT, &signer, 3_000_000),
+        token_balance(TOKEN_ACCOUNT, MINT, &signer, 0),
+    ];
+    fixture.post_token_balances = vec![
+        wsol_balance(WSOL_ACCOUNT, &signer, 2_000_000),
+        token_balance(TOKEN_ACCOUNT, MINT, &signer, 150),
+    ];
+
+    let profit = transaction_profit(&fixture.build()).unwrap();
+    assert_eq!(profit.signer, signer);
+    assert_eq!(profit.rent, 0);
+    assert_eq!(
+        profit.changes,
+        vec![
+            MintProfit {
+                mint: MINT.to_string(),
+                decimals: 0,
+                change: 150,
+            },
+            MintProfit {
+                mint: WSOL.to_string(),
+                decimals: 9,
+                change: -1_005_000,
+            },
+        ]
+    );
+    assert_eq!(profit.sol_change(), -1_005_000);
+    assert_eq!(profit.gross_sol_change(), -1_000_000);
+}
+
+#[test]
+fn rent_is_not_counted_as_loss() {
+    let keys = signer_keys();
+    let signer = keys[SIGNER].clone();
+    let mut fixture = Fixture::new(keys);
+    // 创建wSOL帐户并包装1_000_000, 兑换后wSOL余额为0, 帐户没有关闭
+    fixture.pre_balances[SIGNER] = 10_000_000;
+    fixture.post_balances[SIGNER] = 10_000_000 - 5000 - 2_039_280 - 1_000_000;
+    fixture.post_balances[WSOL_ACCOUNT] = 2_039_280;
+    fixture.post_token_balances = vec![
+        wsol_balance(WSOL_ACCOUNT, &signer, 0),
+        token_balance(TOKEN_ACCOUNT, MINT, &signer, 150),
+    ];
+
+    let profit = transaction_profit(&fixture.build()).unwrap();
+    assert_eq!(profit.rent, 2_039_280);
+    assert_eq!(profit.sol_change(), -1_005_000);
+
+    // 关闭wSOL帐户退回租金和剩余的wSOL
+    let keys = signer_keys();
+    let signer = keys[SIGNER].clone();
+    let mut fixture = Fixture::new(keys);
+    fixture.pre_balances[SIGNER] = 10_000_000;
+    fixture.post_balances[SIGNER] = 10_000_000 - 5000 + 2_039_280 + 500_000;
+    fixture.pre_balances[WSOL_ACCOUNT] = 2_039_280 + 500_000;
+    fixture.pre_token_balances = vec![wsol_balance(WSOL_ACCOUNT, &signer, 500_000)];
+
+    let profit = transaction_profit(&fixture.build()).unwrap();
+    assert_eq!(profit.rent, -2_039_280);
+    assert_eq!(profit.sol_change(), -5000);
+}
+
+#[test]
+fn fee_and_tip_are_subtracted() {
+    let keys = signer_keys();
+    let mut fixture = Fixture::new(keys);
+    fixture.instructions = vec![tip(SIGNER, 10_000)];
+    fixture.fee = 7000;
+    fixture.pre_balances[SIGNER] = 1_000_000;
+    fixture.post_balances[SIGNER] = 1_000_000 - 7000 - 10_000;
+    fixture.post_balances[TIP_ACCOUNT] = 10_000;
+
+    let profit = transaction_profit(&fixture.build()).unwrap();
+    assert_eq!(profit.fee, 7000);
+    assert_eq!(profit.base_fee, 5000);
+    assert_eq!(profit.priority_fee, 2000);
+    assert_eq!(profit.tip, 10_000);
+    assert_eq!(profit.sol_change(), -17_000);
+    assert_eq!(profit.gross_sol_change(), 0);
+    assert_eq!(profit.paid_to_leader(), 12_000);
+}
+
+#[test]
+fn tip_from_other_signer_is_not_counted() {
+    let keys = signer_keys();
+    let mut fixture = Fixture::new(keys);
+    fixture.signatures = 2;
+    fixture.instructions = vec![tip(OTHER_SIGNER, 10_000)];
+    fixture.fee = 10_000;
+    fixture.pre_balances[SIGNER] = 1_000_000;
+    fixture.post_balances[SIGNER] = 1_000_000 - 10_000;
+    fixture.pre_balances[OTHER_SIGNER] = 1_000_000;
+    fixture.post_balances[OTHER_SIGNER] = 1_000_000 - 10_000;
+    fixture.post_balances[TIP_ACCOUNT] = 10_000;
+
+    let profit = transaction_profit(&fixture.build()).unwrap();
+    assert_eq!(profit.base_fee, 10_000);
+    assert_eq!(profit.tip, 0);
+    assert_eq!(profit.sol_change(), -10_000);
+    assert_eq!(profit.paid_to_leader(), 0);
+}
+
+#[test]
+fn quote_value_requires_all_prices() {
+    let keys = signer_keys();
+    let signer = keys[SIGNER].clone();
+    let mut fixture = Fixture::new(keys);
+    fixture.pre_balances[SIGNER] = 10_000_000;
+    fixture.post_balances[SIGNER] = 10_000_000 - 5000 - 1_000_000;
+    fixture.post_token_balances = vec![token_balance(TOKEN_ACCOUNT, MINT, &signer, 2)];
+
+    let profit = transaction_profit(&fixture.build()).unwrap();
+    let mut prices = HashMap::from([(WSOL.to_string(), 200.0)]);
+    assert_eq!(profit.quote_value(&prices), None);
+
+    prices.insert(MINT.to_string(), 1.0);
+    let value = profit.quote_value(&prices).unwrap();
+    // -0.001005 SOL × 200 + 2 × 1
+    assert!((value - 1.799).abs() < 1e-9);
+}
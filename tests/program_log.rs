@@ -0,0 +1,166 @@
+use block_insight_cross::parsed_instruction::instruction_iter::InstructionPath;
+use block_insight_cross::parsed_instruction::{ParsedInstruction, ParsedInstructionData};
+use block_insight_cross::program_log::{InvocationStatus, LogEntry, ProgramLogs};
+use block_insight_cross::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+
+const ROUTER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGqPFXCWuBvf9Ss623VQ5DA";
+const MEMO: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+fn lines(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|l| l.to_string()).collect()
+}
+
+/// 路由程序调用两次Token程序，之后Memo程序失败
+fn swap_logs() -> Vec<String> {
+    lines(&[
+        &format!("Program {ROUTER} invoke [1]"),
+        "Program log: Instruction: Route",
+        &format!("Program {TOKEN} invoke [2]"),
+        "Program log: Instruction: Transfer",
+        &format!("Program {TOKEN} consumed 4645 of 190000 compute units"),
+        &format!("Program {TOKEN} success"),
+        &format!("Program {TOKEN} invoke [2]"),
+        "Program log: Instruction: Transfer",
+        &format!("Program {TOKEN} consumed 4736 of 180000 compute units"),
+        &format!("Program {TOKEN} success"),
+        "Program data: AQID BAUG",
+        &format!("Program return: {ROUTER} AQAAAAAAAAA="),
+        &format!("Program {ROUTER} consumed 30000 of 200000 compute units"),
+        &format!("Program {ROUTER} success"),
+        &format!("Program {MEMO} invoke [1]"),
+        "unexpected line",
+        &format!("Program {MEMO} consumed 100 of 170000 compute units"),
+        &format!("Program {MEMO} failed: custom program error: 0x1"),
+    ])
+}
+
+#[test]
+fn invocations_follow_instruction_paths() {
+    let logs = ProgramLogs::parse(&swap_logs());
+
+    assert!(!logs.truncated);
+    let paths: Vec<_> = logs
+        .invocations
+        .iter()
+        .map(|i| (i.path.to_string(), i.program_id.as_str()))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("0".to_string(), ROUTER),
+            ("0.0".to_string(), TOKEN),
+            ("0.1".to_string(), TOKEN),
+            ("1".to_string(), MEMO),
+        ]
+    );
+
+    let router = logs.get(&InstructionPath(vec![0])).unwrap();
+    assert_eq!(router.status, InvocationStatus::Success);
+    assert_eq!(
+        router.logs,
+        vec![
+            LogEntry::Log("Instruction: Route".to_string()),
+            LogEntry::Data(vec!["AQID".to_string(), "BAUG".to_string()]),
+            LogEntry::Return {
+                program_id: ROUTER.to_string(),
+                data: "AQAAAAAAAAA=".to_string(),
+            },
+        ]
+    );
+    assert_eq!(router.compute_units_consumed, Some(30000));
+    assert_eq!(router.compute_units_budget, Some(200000));
+
+    let memo = logs.get(&InstructionPath(vec![1])).unwrap();
+    assert_eq!(
+        memo.status,
+        InvocationStatus::Failed("custom program error: 0x1".to_string())
+    );
+    assert_eq!(
+        memo.logs,
+        vec![LogEntry::Other("unexpected line".to_string())]
+    );
+}
+
+#[test]
+fn exclusive_compute_units_exclude_children() {
+    let logs = ProgramLogs::parse(&swap_logs());
+
+    assert_eq!(
+        logs.exclusive_compute_units(&InstructionPath(vec![0])),
+        Some(30000 - 4645 - 4736)
+    );
+    assert_eq!(
+        logs.exclusive_compute_units(&InstructionPath(vec![0, 1])),
+        Some(4736)
+    );
+    assert_eq!(
+        logs.exclusive_compute_units(&InstructionPath(vec![2])),
+        None
+    );
+}
+
+#[test]
+fn truncated_logs_leave_invocation_incomplete() {
+    let logs = ProgramLogs::parse(&lines(&[
+        &format!("Program {ROUTER} invoke [1]"),
+        &format!("Program {TOKEN} invoke [2]"),
+        &format!("Program {TOKEN} success"),
+        "Log truncated",
+    ]));
+
+    assert!(logs.truncated);
+    assert_eq!(logs.invocations[0].status, InvocationStatus::Incomplete);
+    assert_eq!(logs.invocations[0].compute_units_consumed, None);
+    assert_eq!(logs.invocations[1].status, InvocationStatus::Success);
+}
+
+fn instruction(
+    program_id: &str,
+    inner_instructions: Option<Vec<ParsedInstruction>>,
+) -> ParsedInstruction {
+    ParsedInstruction {
+        program_id_index: 0,
+        program_id: Some(program_id.parse::<Pubkey>().unwrap()),
+        accounts: vec![],
+        instruction_data: ParsedInstructionData::Unknown,
+        raw_data: None,
+        inner_instructions,
+    }
+}
+
+#[test]
+fn attach_matches_paths_and_program_ids() {
+    let logs = ProgramLogs::parse(&swap_logs());
+    // 第二条内部指令的程序与日志不一致
+    let instructions = vec![
+        instruction(
+            ROUTER,
+            Some(vec![instruction(TOKEN, None), instruction(MEMO, None)]),
+        ),
+        instruction(MEMO, None),
+    ];
+    let keys: Vec<String> = vec![];
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+
+    let attached = logs.attach(&instructions, &accounts);
+    let matched: Vec<_> = attached
+        .iter()
+        .map(|l| {
+            (
+                l.node.path.to_string(),
+                l.invocation.map(|i| i.path.to_string()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        matched,
+        vec![
+            ("0".to_string(), Some("0".to_string())),
+            ("0.0".to_string(), Some("0.0".to_string())),
+            ("0.1".to_string(), None),
+            ("1".to_string(), Some("1".to_string())),
+        ]
+    );
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::sandwich::{Sandwich, detect_sandwiches};
+use block_insight_cross::transaction::transaction_filter::TransactionPropsProvider;
+use common::{encoded_transaction, raw_message, token_balance, token_transfer};
+use serde_json::json;
+use solana_pubkey::Pubkey;
+
+fn key() -> String {
+    Pubkey::new_unique().to_string()
+}
+
+/// 池子的owner及两种mint的vault
+struct Pool {
+    owner: String,
+    mints: [String; 2],
+    vaults: [String; 2],
+}
+
+impl Pool {
+    fn new(mints: &[String; 2]) -> Self {
+        Self {
+            owner: key(),
+            mints: mints.clone(),
+            vaults: [key(), key()],
+        }
+    }
+}
+
+/// `signer`在未知DEX的`pool`中用`input_amount`个mints[input]换得`output_amount`个另一种mint
+fn swap_transaction(
+    signer: &str,
+    pool: &Pool,
+    input: usize,
+    input_amount: u64,
+    output_amount: u64,
+) -> EncodedTransactionProps {
+    let output = 1 - input;
+    // 0 签名者, 1 Token, 2 DEX, 3 输入token帐户, 4 输出token帐户, 5 输入vault, 6 输出vault
+    let keys = vec![
+        signer.to_string(),
+        spl_token::id().to_string(),
+        key(),
+        key(),
+        key(),
+        pool.vaults[input].clone(),
+        pool.vaults[output].clone(),
+    ];
+    let token_balances = vec![
+        token_balance(3, &pool.mints[input], signer, input_amount),
+        token_balance(4, &pool.mints[output], signer, 0),
+        token_balance(5, &pool.mints[input], &pool.owner, 1_000_000),
+        token_balance(6, &pool.mints[output], &pool.owner, 1_000_000),
+    ];
+    let encoded = encoded_transaction(
+        raw_message(
+            &keys,
+            0,
+            vec![json!({
+                "programIdIndex": 2,
+                "accounts": [3, 4, 5, 6, 0],
+                "data": bs58::encode([9u8]).into_string(),
+                "stackHeight": null,
+            })],
+        ),
+        json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": vec![0u64; keys.len()],
+            "postBalances": vec![0u64; keys.len()],
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [
+                    token_transfer(1, 3, 5, 0, input_amount),
+                    token_transfer(1, 6, 4, 2, output_amount),
+                ],
+            }],
+            "logMessages": [],
+            "preTokenBalances": token_balances,
+            "postTokenBalances": token_balances,
+        }),
+    );
+    EncodedTransactionProps::try_from(&encoded).unwrap()
+}
+
+fn detect(transactions: &[EncodedTransactionProps]) -> Vec<Sandwich> {
+    let providers: Vec<&dyn TransactionPropsProvider> = transactions
+        .iter()
+        .map(|t| t as &dyn TransactionPropsProvider)
+        .collect();
+    detect_sandwiches(&providers)
+}
+
+#[test]
+fn sandwich_on_same_pool() {
+    let (attacker, victim) = (key(), key());
+    let pool = Pool::new(&[key(), key()]);
+    let sandwiches = detect(&[
+        swap_transaction(&attacker, &pool, 0, 100, 1_000),
+        swap_transaction(&victim, &pool, 0, 100, 800),
+        swap_transaction(&attacker, &pool, 1, 1_000, 120),
+    ]);
+
+    assert_eq!(sandwiches.len(), 1);
+    let sandwich = &sandwiches[0];
+    assert_eq!(sandwich.attacker, attacker);
+    assert_eq!(sandwich.victim, victim);
+    assert_eq!(sandwich.pool, pool.owner);
+    assert_eq!(sandwich.base_mint, pool.mints[0]);
+    assert_eq!(sandwich.target_mint, pool.mints[1]);
+    assert_eq!(
+        (
+            sandwich.front_run.index,
+            sandwich.victim_transaction.index,
+            sandwich.back_run.index
+        ),
+        (0, 1, 2)
+    );
+    // 按抢跑价格受害者应得1000
+    assert_eq!(sandwich.victim_loss, 200);
+    assert_eq!(sandwich.attacker_profit, 20);
+}
+
+#[test]
+fn victim_on_other_pool_is_not_sandwiched() {
+    let (attacker, victim) = (key(), key());
+    let mints = [key(), key()];
+    let (pool, other_pool) = (Pool::new(&mints), Pool::new(&mints));
+    let sandwiches = detect(&[
+        swap_transaction(&attacker, &pool, 0, 100, 1_000),
+        swap_transaction(&victim, &other_pool, 0, 100, 800),
+        swap_transaction(&attacker, &pool, 1, 1_000, 120),
+    ]);
+
+    assert!(sandwiches.is_empty());
+}
+
+#[test]
+fn partial_back_run_apportions_cost() {
+    let (attacker, victim) = (key(), key());
+    let pool = Pool::new(&[key(), key()]);
+    let sandwiches = detect(&[
+        swap_transaction(&attacker, &pool, 0, 100, 1_000),
+        swap_transaction(&victim, &pool, 0, 100, 800),
+        swap_transaction(&attacker, &pool, 1, 500, 70),
+    ]);
+
+    assert_eq!(sandwiches.len(), 1);
+    // 只卖出一半，成本为抢跑投入的一半
+    assert_eq!(sandwiches[0].attacker_profit, 20);
+}
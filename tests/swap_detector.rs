@@ -0,0 +1,336 @@
+mod common;
+
+use block_insight_cross::events::builtin::PUMP_FUN_PROGRAM;
+use block_insight_cross::parsed_instruction::instruction_iter::InstructionPath;
+use block_insight_cross::pubkeys::RAYDIUM_AMM_V4_PROGRAM;
+use block_insight_cross::transaction::encoded_transaction::EncodedTransactionProps;
+use block_insight_cross::transaction::swap_detector::{Swap, SwapDetector};
+use common::{encoded_transaction, raw_message, token_balance, token_transfer, unique_keys};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+
+const USER: usize = 0;
+const SYSTEM: usize = 1;
+const TOKEN: usize = 2;
+const PROGRAM: usize = 3;
+
+fn system_transfer(from: usize, to: usize, lamports: u64) -> Value {
+    common::system_transfer(SYSTEM, from, to, lamports, Some(2))
+}
+
+/// 帐户: 0 用户(签名者), 1 System, 2 Token, 3 DEX程序, 之后为其它帐户
+fn account_keys(program: &str, others: usize) -> Vec<String> {
+    let mut keys = common::account_keys(
+        1,
+        &[
+            solana_sdk::system_program::id().to_string(),
+            spl_token::id().to_string(),
+            program.to_string(),
+        ],
+    );
+    keys.extend(unique_keys(others));
+    keys
+}
+
+struct Fixture {
+    keys: Vec<String>,
+    instruction_accounts: Vec<usize>,
+    inner: Vec<Value>,
+    balances: (Vec<u64>, Vec<u64>),
+    token_balances: Vec<Value>,
+}
+
+impl Fixture {
+    fn props(&self) -> EncodedTransactionProps {
+        let encoded = encoded_transaction(
+            raw_message(
+                &self.keys,
+                0,
+                vec![json!({
+                    "programIdIndex": PROGRAM,
+                    "accounts": self.instruction_accounts,
+                    "data": bs58::encode([9u8]).into_string(),
+                    "stackHeight": null,
+                })],
+            ),
+            json!({
+                "err": null,
+                "status": {"Ok": null},
+                "fee": 5000,
+                "preBalances": self.balances.0,
+                "postBalances": self.balances.1,
+                "innerInstructions": [{ "index": 0, "instructions": self.inner }],
+                "logMessages": [],
+                "preTokenBalances": self.token_balances,
+                "postTokenBalances": self.token_balances,
+            }),
+        );
+        EncodedTransactionProps::try_from(&encoded).unwrap()
+    }
+}
+
+fn raydium_fixture() -> Fixture {
+    // 4 用户源token帐户, 5 用户目标token帐户, 6 coin vault, 7 pc vault, 8 amm authority, 9.. 其它池子帐户
+    let keys = account_keys(RAYDIUM_AMM_V4_PROGRAM, 15);
+    let (coin_mint, pc_mint) = (
+        Pubkey::new_unique().to_string(),
+        Pubkey::new_unique().to_string(),
+    );
+    let (source, destination, coin_vault, pc_vault, authority) = (4, 5, 6, 7, 8);
+    let mut instruction_accounts = vec![TOKEN, 9, authority, 10, 11, coin_vault, pc_vault];
+    instruction_accounts.extend(12..19);
+    instruction_accounts.extend([source, destination, USER]);
+    let user = &keys[USER];
+    let authority_key = &keys[authority];
+    Fixture {
+        instruction_accounts,
+        inner: vec![
+            token_transfer(TOKEN, source, pc_vault, USER, 1_000),
+            token_transfer(TOKEN, coin_vault, destination, authority, 50),
+        ],
+        balances: (vec![0; keys.len()], vec![0; keys.len()]),
+        token_balances: vec![
+            token_balance(source, &pc_mint, user, 1_000),
+            token_balance(destination, &coin_mint, user, 50),
+            token_balance(coin_vault, &coin_mint, authority_key, 10_000),
+            token_balance(pc_vault, &pc_mint, authority_key, 20_000),
+        ],
+        keys,
+    }
+}
+
+#[test]
+fn raydium_amm_v4_swap() {
+    let fixture = raydium_fixture();
+    assert_eq!(fixture.instruction_accounts.len(), 18);
+    let props = fixture.props();
+    let mints = |i: usize| {
+        fixture.token_balances[i]["mint"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let swaps = SwapDetector::with_builtin().detect(&props);
+    assert_eq!(
+        swaps,
+        vec![Swap {
+            trader: fixture.keys[USER].clone(),
+            input_mint: mints(0),
+            input_amount: 1_000,
+            output_mint: mints(1),
+            output_amount: 50,
+            venue_program: Some(RAYDIUM_AMM_V4_PROGRAM.to_string()),
+            pool: Some(fixture.keys[fixture.instruction_accounts[1]].clone()),
+            instruction_path: InstructionPath(vec![0]),
+        }]
+    );
+}
+
+#[test]
+fn unknown_dex_is_inferred_from_transfers() {
+    let fixture = raydium_fixture();
+    let props = fixture.props();
+
+    // 没有解码器时根据转帐推断，除池子外结果一致；推断的池子是vault的owner
+    let decoded = SwapDetector::with_builtin().detect(&props);
+    let inferred = SwapDetector::new().detect(&props);
+    assert_eq!(inferred.len(), 1);
+    assert_eq!(inferred[0].pool.as_ref(), Some(&fixture.keys[8]));
+    assert_eq!(
+        Swap {
+            pool: None,
+            ..inferred[0].clone()
+        },
+        Swap {
+            pool: None,
+            ..decoded[0].clone()
+        }
+    );
+}
+
+/// 4 global, 5 fee recipient, 6 mint, 7 bonding curve, 8 bonding curve的token帐户, 9 用户的token帐户
+fn pump_fun_fixture(buy: bool) -> Fixture {
+    let keys = account_keys(PUMP_FUN_PROGRAM, 6);
+    let (fee_recipient, mint, curve, curve_token_account, user_token_account) = (5, 6, 7, 8, 9);
+    let mut pre_balances = vec![0; keys.len()];
+    let mut post_balances = vec![0; keys.len()];
+    pre_balances[curve] = 10_000_000;
+    let inner = if buy {
+        post_balances[curve] = 11_000_000;
+        vec![
+            token_transfer(
+                TOKEN,
+                curve_token_account,
+                user_token_account,
+                curve,
+                5_000_000,
+            ),
+            system_transfer(USER, curve, 1_000_000),
+            system_transfer(USER, fee_recipient, 10_000),
+        ]
+    } else {
+        post_balances[curve] = 9_000_000;
+        vec![token_transfer(
+            TOKEN,
+            user_token_account,
+            curve_token_account,
+            USER,
+            5_000_000,
+        )]
+    };
+    Fixture {
+        instruction_accounts: vec![
+            4,
+            fee_recipient,
+            mint,
+            curve,
+            curve_token_account,
+            user_token_account,
+            USER,
+            SYSTEM,
+            TOKEN,
+        ],
+        inner,
+        balances: (pre_balances, post_balances),
+        token_balances: vec![
+            token_balance(curve_token_account, &keys[mint], &keys[curve], 100_000_000),
+            token_balance(user_token_account, &keys[mint], &keys[USER], 5_000_000),
+        ],
+        keys,
+    }
+}
+
+#[test]
+fn pump_fun_buy_pays_native_sol() {
+    let fixture = pump_fun_fixture(true);
+    let swaps = SwapDetector::with_builtin().detect(&fixture.props());
+
+    assert_eq!(swaps.len(), 1);
+    let swap = &swaps[0];
+    assert_eq!(swap.trader, fixture.keys[USER]);
+    assert_eq!(swap.input_mint, spl_token::native_mint::ID.to_string());
+    assert_eq!(swap.input_amount, 1_000_000);
+    assert_eq!(swap.output_mint, fixture.keys[6]);
+    assert_eq!(swap.output_amount, 5_000_000);
+    assert_eq!(swap.venue_program.as_deref(), Some(PUMP_FUN_PROGRAM));
+    assert_eq!(swap.pool.as_ref(), Some(&fixture.keys[7]));
+}
+
+#[test]
+fn pump_fun_sell_receives_native_sol_from_balance_change() {
+    let fixture = pump_fun_fixture(false);
+    let swaps = SwapDetector::with_builtin().detect(&fixture.props());
+
+    assert_eq!(swaps.len(), 1);
+    let swap = &swaps[0];
+    assert_eq!(swap.trader, fixture.keys[USER]);
+    assert_eq!(swap.input_mint, fixture.keys[6]);
+    assert_eq!(swap.input_amount, 5_000_000);
+    assert_eq!(swap.output_mint, spl_token::native_mint::ID.to_string());
+    assert_eq!(swap.output_amount, 1_000_000);
+
+    // 卖出没有SOL转帐，仅根据转帐无法推断
+    assert!(SwapDetector::new().detect(&fixture.props()).is_empty());
+}
+
+/// 路由程序在同一顶层指令下依次调用两个未知DEX: X -> Y -> Z
+///
+/// 4 DEX程序, 5/6/7 用户的X/Y/Z token帐户, 8/9 池子A的X/Y vault, 10/11 池子B的Y/Z vault,
+/// 12/13 池子A/B的authority, 14 另一个用户, 15/16 另一个用户的Y/Z token帐户
+fn route_fixture(second_trader: usize, second_input: u64) -> Fixture {
+    let keys = account_keys(&Pubkey::new_unique().to_string(), 13);
+    let (x, y, z) = (
+        Pubkey::new_unique().to_string(),
+        Pubkey::new_unique().to_string(),
+        Pubkey::new_unique().to_string(),
+    );
+    let (dex, pool_a, pool_b, other) = (4, 12, 13, 14);
+    let (y_account, z_account) = if second_trader == USER {
+        (6, 7)
+    } else {
+        (15, 16)
+    };
+    let venue = |accounts: Vec<usize>| {
+        json!({
+            "programIdIndex": dex,
+            "accounts": accounts,
+            "data": bs58::encode([1u8]).into_string(),
+            "stackHeight": 2,
+        })
+    };
+    let transfer = |source, destination, authority, amount| {
+        let mut transfer = token_transfer(TOKEN, source, destination, authority, amount);
+        transfer["stackHeight"] = json!(3);
+        transfer
+    };
+    let owner = |i: usize| keys[i].as_str();
+    Fixture {
+        instruction_accounts: vec![dex, TOKEN, USER],
+        inner: vec![
+            venue(vec![TOKEN, 8, 9, 5, 6, USER]),
+            transfer(5, 8, USER, 1_000),
+            transfer(9, 6, pool_a, 500),
+            venue(vec![TOKEN, 10, 11, y_account, z_account, second_trader]),
+            transfer(y_account, 10, second_trader, second_input),
+            transfer(11, z_account, pool_b, 30),
+        ],
+        balances: (vec![0; keys.len()], vec![0; keys.len()]),
+        token_balances: vec![
+            token_balance(5, &x, owner(USER), 0),
+            token_balance(6, &y, owner(USER), 0),
+            token_balance(7, &z, owner(USER), 30),
+            token_balance(8, &x, owner(pool_a), 10_000),
+            token_balance(9, &y, owner(pool_a), 10_000),
+            token_balance(10, &y, owner(pool_b), 10_000),
+            token_balance(11, &z, owner(pool_b), 10_000),
+            token_balance(15, &y, owner(other), 1_000),
+            token_balance(16, &z, owner(other), 30),
+        ],
+        keys,
+    }
+}
+
+#[test]
+fn chained_hops_are_merged() {
+    let fixture = route_fixture(USER, 500);
+    let swaps = SwapDetector::new().detect(&fixture.props());
+
+    assert_eq!(swaps.len(), 1);
+    let swap = &swaps[0];
+    assert_eq!(swap.trader, fixture.keys[USER]);
+    assert_eq!(
+        swap.input_mint,
+        fixture.token_balances[0]["mint"].as_str().unwrap()
+    );
+    assert_eq!(swap.input_amount, 1_000);
+    assert_eq!(
+        swap.output_mint,
+        fixture.token_balances[2]["mint"].as_str().unwrap()
+    );
+    assert_eq!(swap.output_amount, 30);
+    assert_eq!(swap.venue_program.as_ref(), Some(&fixture.keys[PROGRAM]));
+    assert_eq!(swap.pool, None);
+    assert_eq!(swap.instruction_path, InstructionPath(vec![0]));
+}
+
+#[test]
+fn hop_spending_more_than_previous_output_is_not_merged() {
+    let fixture = route_fixture(USER, 600);
+    let swaps = SwapDetector::new().detect(&fixture.props());
+
+    assert_eq!(swaps.len(), 2);
+    assert_eq!(swaps[0].output_amount, 500);
+    assert_eq!(swaps[1].input_amount, 600);
+    assert_eq!(swaps[1].instruction_path, InstructionPath(vec![0, 1]));
+}
+
+#[test]
+fn hops_of_different_traders_are_not_merged() {
+    let fixture = route_fixture(14, 500);
+    let swaps = SwapDetector::new().detect(&fixture.props());
+
+    assert_eq!(swaps.len(), 2);
+    assert_eq!(swaps[0].trader, fixture.keys[USER]);
+    assert_eq!(swaps[1].trader, fixture.keys[14]);
+}
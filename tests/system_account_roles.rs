@@ -0,0 +1,241 @@
+use block_insight_cross::account_roles::AccountRoleError;
+use block_insight_cross::account_roles::system::{SystemAccountRoles, system_account_roles};
+use block_insight_cross::utils::TransactionAccounts;
+use solana_pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+
+fn keys() -> Vec<Pubkey> {
+    (0..6).map(|_| Pubkey::new_unique()).collect()
+}
+
+#[test]
+fn create_account_roles() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let instruction = SystemInstruction::CreateAccount {
+        lamports: 2_039_280,
+        space: 165,
+        owner: spl_token::id(),
+    };
+
+    assert_eq!(
+        system_account_roles(&instruction, &[0, 1], &accounts),
+        Ok(SystemAccountRoles::CreateAccount {
+            funding: &keys[0],
+            new_account: &keys[1],
+            base: None,
+        })
+    );
+    assert_eq!(
+        system_account_roles(&instruction, &[0], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 1,
+            len: 1
+        })
+    );
+}
+
+#[test]
+fn create_account_with_seed_base_is_optional() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let instruction = SystemInstruction::CreateAccountWithSeed {
+        base: keys[0],
+        seed: "seed".to_string(),
+        lamports: 1,
+        space: 0,
+        owner: spl_token::id(),
+    };
+
+    // base与funding相同时只有两个帐户
+    assert_eq!(
+        system_account_roles(&instruction, &[0, 1], &accounts),
+        Ok(SystemAccountRoles::CreateAccount {
+            funding: &keys[0],
+            new_account: &keys[1],
+            base: None,
+        })
+    );
+    assert_eq!(
+        system_account_roles(&instruction, &[0, 1, 2], &accounts),
+        Ok(SystemAccountRoles::CreateAccount {
+            funding: &keys[0],
+            new_account: &keys[1],
+            base: Some(&keys[2]),
+        })
+    );
+}
+
+#[test]
+fn transfer_roles() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let instruction = SystemInstruction::Transfer { lamports: 1000 };
+
+    assert_eq!(
+        system_account_roles(&instruction, &[3, 1], &accounts),
+        Ok(SystemAccountRoles::Transfer {
+            from: &keys[3],
+            to: &keys[1],
+            base: None,
+        })
+    );
+    assert_eq!(
+        system_account_roles(&instruction, &[], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 0,
+            len: 0
+        })
+    );
+    // 指令引用了交易中不存在的帐户
+    assert_eq!(
+        system_account_roles(&instruction, &[0, 6], &accounts),
+        Err(AccountRoleError::UnknownAccount { index: 6 })
+    );
+}
+
+#[test]
+fn transfer_with_seed_roles() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    let instruction = SystemInstruction::TransferWithSeed {
+        lamports: 1000,
+        from_seed: "seed".to_string(),
+        from_owner: Pubkey::new_unique(),
+    };
+
+    // 帐户顺序: from, base, to
+    assert_eq!(
+        system_account_roles(&instruction, &[0, 1, 2], &accounts),
+        Ok(SystemAccountRoles::Transfer {
+            from: &keys[0],
+            to: &keys[2],
+            base: Some(&keys[1]),
+        })
+    );
+    assert_eq!(
+        system_account_roles(&instruction, &[0, 1], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 2,
+            len: 2
+        })
+    );
+}
+
+#[test]
+fn assign_roles() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+
+    assert_eq!(
+        system_account_roles(
+            &SystemInstruction::Assign {
+                owner: spl_token::id()
+            },
+            &[2],
+            &accounts
+        ),
+        Ok(SystemAccountRoles::Assign {
+            account: &keys[2],
+            base: None,
+        })
+    );
+
+    let with_seed = SystemInstruction::AssignWithSeed {
+        base: keys[1],
+        seed: "seed".to_string(),
+        owner: spl_token::id(),
+    };
+    assert_eq!(
+        system_account_roles(&with_seed, &[2, 1], &accounts),
+        Ok(SystemAccountRoles::Assign {
+            account: &keys[2],
+            base: Some(&keys[1]),
+        })
+    );
+    assert_eq!(
+        system_account_roles(&with_seed, &[2], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 1,
+            len: 1
+        })
+    );
+}
+
+#[test]
+fn nonce_roles() {
+    let keys = keys();
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+
+    // nonce, RecentBlockhashes, authority
+    assert_eq!(
+        system_account_roles(
+            &SystemInstruction::AdvanceNonceAccount,
+            &[0, 1, 2],
+            &accounts
+        ),
+        Ok(SystemAccountRoles::AdvanceNonceAccount {
+            nonce: &keys[0],
+            authority: &keys[2],
+        })
+    );
+    assert_eq!(
+        system_account_roles(&SystemInstruction::AdvanceNonceAccount, &[0, 1], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 2,
+            len: 2
+        })
+    );
+
+    // nonce, recipient, RecentBlockhashes, Rent, authority
+    let withdraw = SystemInstruction::WithdrawNonceAccount(1000);
+    assert_eq!(
+        system_account_roles(&withdraw, &[0, 3, 1, 2, 4], &accounts),
+        Ok(SystemAccountRoles::WithdrawNonceAccount {
+            nonce: &keys[0],
+            recipient: &keys[3],
+            authority: &keys[4],
+        })
+    );
+    assert_eq!(
+        system_account_roles(&withdraw, &[0, 3, 1, 2], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 4,
+            len: 4
+        })
+    );
+
+    // 新的authority在指令数据中
+    assert_eq!(
+        system_account_roles(
+            &SystemInstruction::InitializeNonceAccount(keys[5]),
+            &[0, 1, 2],
+            &accounts
+        ),
+        Ok(SystemAccountRoles::InitializeNonceAccount { nonce: &keys[0] })
+    );
+
+    assert_eq!(
+        system_account_roles(
+            &SystemInstruction::AuthorizeNonceAccount(keys[5]),
+            &[0, 4],
+            &accounts
+        ),
+        Ok(SystemAccountRoles::AuthorizeNonceAccount {
+            nonce: &keys[0],
+            authority: &keys[4],
+        })
+    );
+
+    assert_eq!(
+        system_account_roles(&SystemInstruction::UpgradeNonceAccount, &[0], &accounts),
+        Ok(SystemAccountRoles::UpgradeNonceAccount { nonce: &keys[0] })
+    );
+    assert_eq!(
+        system_account_roles(&SystemInstruction::UpgradeNonceAccount, &[], &accounts),
+        Err(AccountRoleError::MissingAccount {
+            position: 0,
+            len: 0
+        })
+    );
+}
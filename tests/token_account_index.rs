@@ -0,0 +1,110 @@
+use block_insight_cross::token_account_index::TokenAccountIndex;
+use block_insight_cross::token_transfer_data::TokenTransferData;
+use block_insight_cross::utils::TransactionAccounts;
+use serde_json::json;
+use solana_pubkey::Pubkey;
+use solana_transaction_status_client_types::UiTransactionTokenBalance;
+
+const SOURCE: u8 = 1;
+/// 交易中新创建的帐户，只出现在post_token_balances中
+const DESTINATION: u8 = 2;
+const UNKNOWN: u8 = 5;
+
+fn balance(account_index: u8, mint: &str, owner: &str, amount: u64) -> UiTransactionTokenBalance {
+    serde_json::from_value(json!({
+        "accountIndex": account_index,
+        "mint": mint,
+        "uiTokenAmount": {
+            "uiAmount": amount as f64 / 1e6,
+            "decimals": 6,
+            "amount": amount.to_string(),
+            "uiAmountString": (amount as f64 / 1e6).to_string(),
+        },
+        "owner": owner,
+        "programId": spl_token::id().to_string(),
+    }))
+    .unwrap()
+}
+
+fn transfer(source: u8, destination: u8, decimal: Option<u8>) -> TokenTransferData {
+    TokenTransferData {
+        source,
+        destination,
+        signer: 0,
+        amount: 100,
+        mint: None,
+        decimal,
+    }
+}
+
+/// 帐户: 0 付款人, 1 付款人的token帐户, 2 收款人新建的token帐户, 3 收款人, 4 Token
+fn keys() -> Vec<String> {
+    (0..5).map(|_| Pubkey::new_unique().to_string()).collect()
+}
+
+#[test]
+fn index_merges_pre_and_post_balances() {
+    let keys = keys();
+    let mint = Pubkey::new_unique().to_string();
+    let index = TokenAccountIndex::from_ui_token_balances(
+        &[balance(SOURCE, &mint, &keys[0], 1_000)],
+        &[
+            balance(SOURCE, &mint, &keys[0], 900),
+            balance(DESTINATION, &mint, &keys[3], 100),
+        ],
+    );
+
+    assert_eq!(index.len(), 2);
+    let destination = index.get(DESTINATION).unwrap();
+    assert_eq!(destination.mint, mint);
+    assert_eq!(destination.decimals, 6);
+    assert_eq!(destination.owner.as_deref(), Some(keys[3].as_str()));
+    assert_eq!(
+        destination.program_id.as_deref(),
+        Some(spl_token::id().to_string().as_str())
+    );
+    assert!(index.get(UNKNOWN).is_none());
+
+    let accounts = TransactionAccounts::from_accounts(Some(keys.as_slice()), None, None);
+    assert_eq!(index.get_by_address(&accounts, &keys[2]), Some(destination));
+    assert!(index.get_by_address(&accounts, &keys[0]).is_none());
+}
+
+#[test]
+fn resolve_unchecked_transfer() {
+    let keys = keys();
+    let mint = Pubkey::new_unique().to_string();
+    let index = TokenAccountIndex::from_ui_token_balances(
+        &[balance(SOURCE, &mint, &keys[0], 1_000)],
+        &[
+            balance(SOURCE, &mint, &keys[0], 900),
+            balance(DESTINATION, &mint, &keys[3], 100),
+        ],
+    );
+
+    // 未检查的Transfer中没有mint和精度
+    let resolved = index.resolve_transfer(transfer(SOURCE, DESTINATION, None));
+    assert_eq!(resolved.mint, Some(mint.as_str()));
+    assert_eq!(resolved.decimals, Some(6));
+    assert_eq!(resolved.source_owner, Some(keys[0].as_str()));
+    assert_eq!(resolved.destination_owner, Some(keys[3].as_str()));
+    assert_eq!(resolved.data.amount, 100);
+
+    // source没有余额信息时，mint和精度取自只出现在post中的destination
+    let resolved = index.resolve_transfer(transfer(UNKNOWN, DESTINATION, None));
+    assert_eq!(resolved.mint, Some(mint.as_str()));
+    assert_eq!(resolved.decimals, Some(6));
+    assert_eq!(resolved.source_owner, None);
+    assert_eq!(resolved.destination_owner, Some(keys[3].as_str()));
+
+    // 指令中的精度优先
+    let resolved = index.resolve_transfer(transfer(DESTINATION, UNKNOWN, Some(9)));
+    assert_eq!(resolved.mint, Some(mint.as_str()));
+    assert_eq!(resolved.decimals, Some(9));
+    assert_eq!(resolved.source_owner, Some(keys[3].as_str()));
+    assert_eq!(resolved.destination_owner, None);
+
+    let resolved = index.resolve_transfer(transfer(UNKNOWN, UNKNOWN, None));
+    assert_eq!(resolved.mint, None);
+    assert_eq!(resolved.decimals, None);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use block_insight_cross::account_roles::AccountRoleError;
+use block_insight_cross::instructions::spl_token::TokenInstruction as SplTokenInstruction;
+use block_insight_cross::instructions::spl_token_2022::TokenInstruction as SplToken2022Instruction;
+use block_insight_cross::parsed_instruction::{ParsedInstruction, ParsedInstructionData};
+use block_insight_cross::transaction::transaction_filter::circle_swap_filter::CircleSwapFilter;
+use block_insight_cross::transaction::transaction_filter::{
+    TransactionFilter, TransactionFilterContext,
+};
+use common::TestTransaction;
+use proptest::prelude::*;
+use solana_sdk::system_instruction::SystemInstruction;
+
+fn instruction_data() -> impl Strategy<Value = ParsedInstructionData> {
+    prop_oneof![
+        any::<u64>().prop_map(|amount| ParsedInstructionData::SplToken(
+            SplTokenInstruction::Transfer { amount }
+        )),
+        (any::<u64>(), any::<u8>()).prop_map(|(amount, decimals)| {
+            ParsedInstructionData::SplToken(SplTokenInstruction::TransferChecked {
+                amount,
+                decimals,
+            })
+        }),
+        any::<u64>().prop_map(|amount| ParsedInstructionData::SplToken2022(
+            SplToken2022Instruction::Transfer { amount }
+        )),
+        (any::<u64>(), any::<u8>()).prop_map(|(amount, decimals)| {
+            ParsedInstructionData::SplToken2022(SplToken2022Instruction::TransferChecked {
+                amount,
+                decimals,
+            })
+        }),
+        any::<u64>().prop_map(|amount| ParsedInstructionData::SplToken(
+            SplTokenInstruction::MintTo { amount }
+        )),
+        any::<u64>().prop_map(|lamports| ParsedInstructionData::System(
+            SystemInstruction::Transfer { lamports }
+        )),
+        Just(ParsedInstructionData::Unknown),
+    ]
+}
+
+fn parsed_instruction() -> impl Strategy<Value = ParsedInstruction> {
+    let leaf = (
+        any::<u8>(),
+        prop::collection::vec(any::<u8>(), 0..6),
+        instruction_data(),
+    )
+        .prop_map(
+            |(program_id_index, accounts, instruction_data)| ParsedInstruction {
+                program_id_index,
+                program_id: None,
+                accounts,
+                instruction_data,
+                raw_data: None,
+                inner_instructions: None,
+            },
+        );
+    leaf.prop_recursive(3, 32, 4, |inner| {
+        (inner.clone(), prop::collection::vec(inner, 0..4)).prop_map(|(mut parent, children)| {
+            parent.inner_instructions = Some(children);
+            parent
+        })
+    })
+}
+
+/// 需要的帐户数量，不是转帐指令时为None
+fn required_accounts(data: &ParsedInstructionData) -> Option<usize> {
+    match data {
+        ParsedInstructionData::SplToken(SplTokenInstruction::Transfer { .. }) => Some(3),
+        ParsedInstructionData::SplToken(SplTokenInstruction::TransferChecked { .. }) => Some(4),
+        ParsedInstructionData::SplToken2022(SplToken2022Instruction::Transfer { .. }) => Some(3),
+        ParsedInstructionData::SplToken2022(SplToken2022Instruction::TransferChecked {
+            ..
+        }) => Some(4),
+        _ => None,
+    }
+}
+
+proptest! {
+    #[test]
+    fn token_transfer_data_never_panics(instruction in parsed_instruction()) {
+        let result = instruction.try_get_token_transfer_data();
+        match required_accounts(&instruction.instruction_data) {
+            None => prop_assert!(matches!(result, Ok(None))),
+            Some(required) if instruction.accounts.len() >= required => {
+                let data = result.unwrap().unwrap();
+                prop_assert_eq!(data.source, instruction.accounts[0]);
+                prop_assert_eq!(data.signer, instruction.accounts[required - 1]);
+                prop_assert_eq!(data.mint.is_some(), required == 4);
+            }
+            Some(_) => {
+                let is_missing_account = matches!(result, Err(AccountRoleError::MissingAccount { .. }));
+                prop_assert!(is_missing_account);
+                prop_assert!(instruction.get_token_transfer_data().is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn circle_swap_filter_never_panics(
+        instructions in prop::collection::vec(parsed_instruction(), 0..8),
+        accounts_len in 0usize..8,
+    ) {
+        let transaction = TestTransaction::new(
+            (0..accounts_len).map(|i| i.to_string()).collect(),
+            instructions,
+        );
+        let mut context = TransactionFilterContext::default();
+        CircleSwapFilter.filter(&transaction, &mut context);
+    }
+}